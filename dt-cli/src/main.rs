@@ -1,12 +1,20 @@
-use std::path::PathBuf;
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
 
+use signal_hook::{
+    consts::{SIGHUP, SIGINT, SIGTERM},
+    iterator::Signals,
+};
 use structopt::StructOpt;
 
 use dt_core::{
-    config::DTConfig,
+    config::{DTConfig, DumpFormat},
     error::{Error as AppError, Result},
-    syncing,
-    utils::default_config_path,
+    registry::list_helpers,
+    syncing::{self, SyncOptions, WatchEvent},
+    utils::{config_search_list, default_config_path, load_env_file},
 };
 
 #[derive(StructOpt, Debug)]
@@ -22,10 +30,148 @@ struct Opt {
     #[structopt(name = "group_name")]
     group_names: Vec<String>,
 
+    /// Prefixes every group's absolute target with given root directory,
+    /// like `DESTDIR` for `make install`
+    #[structopt(long, value_name = "root")]
+    target_prefix: Option<PathBuf>,
+
+    /// Loads environment variables from given file (one `KEY=VALUE` per
+    /// line) before loading config and templating
+    #[structopt(long, value_name = "path")]
+    env_file: Option<PathBuf>,
+
+    /// Renders the config file with side-effect-free helpers before parsing
+    /// it, as if its first line were `# dt:template`
+    #[structopt(long)]
+    template_config: bool,
+
+    /// Treats current machine as having given hostname, overrides
+    /// `global.hostname_override` in config
+    #[structopt(long, value_name = "name")]
+    hostname: Option<String>,
+
+    /// Sets every target to given octal permission bits (e.g. 0644)
+    /// instead of mirroring the source's, overrides `global.force_mode` in
+    /// config
+    #[structopt(long, value_name = "octal", parse(try_from_str = parse_mode))]
+    target_mode: Option<u32>,
+
+    /// Only processes groups enabled by the given profile, combines with
+    /// given group name(s)
+    #[structopt(long, value_name = "name")]
+    profile: Option<String>,
+
+    /// Only processes groups that have any of the given tag(s)
+    #[structopt(long = "tag", value_name = "tag", number_of_values = 1)]
+    tags: Vec<String>,
+
+    /// Only processes groups that have none of the given tag(s)
+    #[structopt(long = "not-tag", value_name = "tag", number_of_values = 1)]
+    not_tags: Vec<String>,
+
     /// Shows changes to be made without actually syncing files
     #[structopt(short, long)]
     dry_run: bool,
 
+    /// Ignores failures of all groups for this run, regardless of their
+    /// `ignore_failure` settings
+    #[structopt(long, conflicts_with = "no-ignore-failure")]
+    ignore_failure: bool,
+
+    /// Aborts on failures of any group for this run, regardless of their
+    /// `ignore_failure` settings
+    #[structopt(long, conflicts_with = "ignore-failure")]
+    no_ignore_failure: bool,
+
+    /// Continues past failed items and reports all failures at the end,
+    /// exits with a non-zero status if any item failed
+    #[structopt(long)]
+    keep_going: bool,
+
+    /// Removes targets synced by the previous run with this flag but no
+    /// longer produced by the config, can not be combined with group
+    /// filters
+    #[structopt(
+        long,
+        conflicts_with_all = &["group_name", "profile", "tags", "not-tags"]
+    )]
+    delete_orphans: bool,
+
+    /// Removes directories created by syncing that became empty after
+    /// removing orphaned targets, requires `--delete-orphans`
+    #[structopt(long, requires = "delete-orphans")]
+    prune: bool,
+
+    /// Does not lock the staging root directory, allows concurrent runs
+    #[structopt(long)]
+    no_lock: bool,
+
+    /// Only syncs items whose target would change, skips up-to-date items
+    /// without writing anything for them
+    #[structopt(long)]
+    only_changed: bool,
+
+    /// Only syncs items changed in git since given revision, skips groups
+    /// whose base is not in a git repository
+    #[structopt(long, value_name = "rev")]
+    since_commit: Option<String>,
+
+    /// Prints the absolute path to the staging directory of given group,
+    /// then exits without syncing
+    #[structopt(long, value_name = "group")]
+    print_staging_path: Option<String>,
+
+    /// Prints a unified diff between every target that is missing or not
+    /// up to date and what syncing would write to it, then exits without
+    /// syncing
+    #[structopt(long)]
+    diff: bool,
+
+    /// Opens each difference with given command (e.g. `nvim -d`) instead of
+    /// printing it, by appending paths to the current and proposed contents
+    /// to the command, requires `--diff`
+    #[structopt(long, value_name = "cmd", requires = "diff")]
+    diff_tool: Option<String>,
+
+    /// Prints why each source is included or excluded, then exits without
+    /// syncing
+    #[structopt(long)]
+    explain: bool,
+
+    /// Prints which group and source each target is synced from after
+    /// resolving priorities, then exits without syncing
+    #[structopt(long)]
+    resolve_only: bool,
+
+    /// Prints what given source renders to under the current context, then
+    /// exits without syncing
+    #[structopt(long, value_name = "source")]
+    show_rendered: Option<PathBuf>,
+
+    /// Prints names and descriptions of all selected groups, then exits
+    /// without syncing
+    #[structopt(long)]
+    list_groups: bool,
+
+    /// Prints names and descriptions of all template helpers, including
+    /// user-defined ones, then exits without syncing
+    #[structopt(long)]
+    list_helpers: bool,
+
+    /// Prints the templating context in given format (`json` or `toml`),
+    /// then exits without syncing
+    #[structopt(long, value_name = "format", possible_values = &["json", "toml"])]
+    dump_context: Option<DumpFormat>,
+
+    /// Prints time spent on each group after syncing, slowest first
+    #[structopt(long)]
+    stats: bool,
+
+    /// Keeps running after syncing: reloads the config file and syncs again
+    /// on SIGHUP, exits on SIGTERM or SIGINT
+    #[structopt(long, conflicts_with = "stats")]
+    watch: bool,
+
     /// Increases logging verbosity
     #[structopt(short, long, parse(from_occurrences), conflicts_with = "quiet")]
     verbose: i8,
@@ -33,31 +179,218 @@ struct Opt {
     /// Decreases logging verbosity
     #[structopt(short, long, parse(from_occurrences), conflicts_with = "verbose")]
     quiet: i8,
+
+    #[structopt(subcommand)]
+    cmd: Option<Command>,
+}
+
+#[derive(StructOpt, Debug)]
+enum Command {
+    /// Renders every source into given directory without linking or
+    /// staging, instead of syncing
+    Render {
+        /// Directory to write rendered files to, at their target-relative
+        /// paths
+        #[structopt(long, value_name = "dir")]
+        output_dir: PathBuf,
+    },
+    /// Prints every target that differs from what syncing would produce,
+    /// instead of syncing, exits with a non-zero status if any differs
+    Verify,
 }
 
 fn run() -> Result<()> {
     let opt = Opt::from_args();
     setup(opt.verbose - opt.quiet + { opt.dry_run as i8 });
 
-    log::trace!("Parsed command line: {:#?}", &opt);
+    log::trace!("Parsed command line: {:#?}", opt);
+
+    if let Some(env_file) = &opt.env_file {
+        log::debug!(
+            "Loading environment variables from '{}'",
+            env_file.display()
+        );
+        load_env_file(env_file)?;
+    }
 
-    let config_path = match opt.config_path {
+    let config_path = match &opt.config_path {
         Some(p) => {
             log::debug!("Using config file '{}' (from command line)", p.display(),);
-            p
+            p.to_owned()
         }
-        None => default_config_path("DT_CLI_CONFIG_PATH", "DT_CONFIG_DIR", &["cli.toml"])?,
+        None => default_config_path(
+            "DT_CLI_CONFIG_PATH",
+            "DT_CONFIG_DIR",
+            &config_search_list(
+                "DT_CLI_CONFIG_SEARCH",
+                &["cli.toml", "config.toml", "dt.toml"],
+            ),
+        )?,
     };
 
-    let config = DTConfig::from_path(config_path)?;
-    // Filter groups when appropriate
-    let config = if opt.group_names.is_empty() {
+    let config = load_config(&opt, &config_path)?;
+    if opt.list_helpers {
+        for (name, description) in list_helpers(&config) {
+            println!("{:<26}{}", name, description);
+        }
+        return Ok(());
+    }
+    if let Some(format) = opt.dump_context {
+        println!("{}", config.context.dump(format)?);
+        return Ok(());
+    }
+    if let Some(group_name) = &opt.print_staging_path {
+        println!("{}", config.get_staging_dir(group_name)?.display());
+        return Ok(());
+    }
+    let config = select_groups(config, &opt)?;
+    if opt.list_groups {
+        for (name, description) in config.list_groups() {
+            println!("{:<26}{}", name, description);
+        }
+        return Ok(());
+    }
+    if opt.diff {
+        for item in syncing::diff(config)? {
+            match &opt.diff_tool {
+                Some(tool) => {
+                    item.open_with(tool)?;
+                }
+                None => print!("{}", item.unified()),
+            }
+        }
+        return Ok(());
+    }
+    if opt.explain {
+        for explanation in syncing::explain(config)? {
+            println!("{}", explanation);
+        }
+        return Ok(());
+    }
+    if opt.resolve_only {
+        for resolution in syncing::resolved(config)? {
+            println!("{}", resolution);
+        }
+        return Ok(());
+    }
+    if let Some(source) = &opt.show_rendered {
+        let content = syncing::render_source(config, source)?;
+        std::io::stdout().write_all(&content)?;
+        return Ok(());
+    }
+    match &opt.cmd {
+        Some(Command::Render { output_dir }) => {
+            syncing::render(config, output_dir)?;
+            return Ok(());
+        }
+        Some(Command::Verify) => {
+            let drifts = syncing::verify(config)?;
+            for drift in &drifts {
+                println!("{}", drift);
+            }
+            if !drifts.is_empty() {
+                return Err(AppError::SyncingError(format!(
+                    "{} {} drifted",
+                    drifts.len(),
+                    if drifts.len() <= 1 {
+                        "target"
+                    } else {
+                        "targets"
+                    },
+                )));
+            }
+            return Ok(());
+        }
+        None => {}
+    }
+    let options = SyncOptions {
+        dry_run: opt.dry_run,
+        ignore_failure: if opt.ignore_failure {
+            Some(true)
+        } else if opt.no_ignore_failure {
+            Some(false)
+        } else {
+            None
+        },
+        keep_going: opt.keep_going,
+        delete_orphans: opt.delete_orphans,
+        prune: opt.prune,
+        no_lock: opt.no_lock,
+        only_changed: opt.only_changed,
+        since_commit: opt.since_commit.to_owned(),
+    };
+    if opt.watch {
+        let mut signals = Signals::new([SIGHUP, SIGTERM, SIGINT])?;
+        let events = signals.forever().map(|signal| match signal {
+            SIGHUP => WatchEvent::Reload,
+            _ => WatchEvent::Exit,
+        });
+        syncing::watch(
+            config,
+            || select_groups(load_config(&opt, &config_path)?, &opt),
+            options,
+            events,
+        )?;
+        return Ok(());
+    }
+    let summary = syncing::sync_with_options(config, options)?;
+    if opt.stats {
+        let mut stats = summary.stats;
+        stats.sort_by_key(|s| std::cmp::Reverse(s.total()));
+        for group_stats in stats {
+            println!("{}", group_stats);
+        }
+    }
+    Ok(())
+}
+
+/// Loads the config file at `config_path`, with overrides from command line
+/// applied.
+fn load_config(opt: &Opt, config_path: &Path) -> Result<DTConfig> {
+    let config = if opt.template_config {
+        DTConfig::from_path_templated(config_path)?
+    } else {
+        DTConfig::from_path(config_path)?
+    };
+    let config = match &opt.target_prefix {
+        Some(root) => config.with_target_prefix(root)?,
+        None => config,
+    };
+    config.with_global(|global| {
+        if let Some(hostname) = &opt.hostname {
+            global.hostname_override = Some(hostname.to_owned());
+        }
+        if let Some(mode) = opt.target_mode {
+            global.force_mode = Some(mode);
+        }
+    })
+}
+
+/// Filters groups of `config` by the profile, tags and group names given on
+/// command line.
+fn select_groups(config: DTConfig, opt: &Opt) -> Result<DTConfig> {
+    let config = match &opt.profile {
+        Some(profile_name) => config.select_profile(profile_name)?,
+        None => config,
+    };
+    let config = if opt.tags.is_empty() && opt.not_tags.is_empty() {
         config
     } else {
-        config.filter_names(opt.group_names)
+        config.filter_tags(opt.tags.to_owned(), opt.not_tags.to_owned())
     };
-    syncing::sync(config, opt.dry_run)?;
-    Ok(())
+    Ok(if opt.group_names.is_empty() {
+        config
+    } else {
+        config.filter_names(opt.group_names.to_owned())
+    })
+}
+
+/// Parses octal permission bits, with or without a leading `0o`.
+fn parse_mode(s: &str) -> std::result::Result<u32, String> {
+    match u32::from_str_radix(s.trim_start_matches("0o"), 8) {
+        Ok(mode) if mode <= 0o7777 => Ok(mode),
+        _ => Err(format!("'{}' is not a valid octal permission mode", s)),
+    }
 }
 
 fn setup(verbosity: i8) {
@@ -74,21 +407,29 @@ fn setup(verbosity: i8) {
     pretty_env_logger::init();
 }
 
+/// Exit code for given error, the largest exit code of the contained errors
+/// is used for [`AppError::MultipleErrors`].
+fn exit_code(e: &AppError) -> i32 {
+    match e {
+        AppError::ConfigError(_) => 1,
+        AppError::IoError(_) => 2,
+        AppError::ParseError(_) => 3,
+        AppError::PathError(_) => 4,
+        AppError::RenderingError(_) => 5,
+        AppError::SyncingError(_) => 6,
+        AppError::TemplatingError(_) => 7,
+        AppError::NetworkError(_) => 8,
+        AppError::MultipleErrors(errors) => errors.iter().map(exit_code).max().unwrap_or(255),
+
+        #[allow(unreachable_patterns)]
+        _ => 255,
+    }
+}
+
 fn main() {
     if let Err(e) = run() {
         log::error!("{}", e);
-        match e {
-            AppError::ConfigError(_) => std::process::exit(1),
-            AppError::IoError(_) => std::process::exit(2),
-            AppError::ParseError(_) => std::process::exit(3),
-            AppError::PathError(_) => std::process::exit(4),
-            AppError::RenderingError(_) => std::process::exit(5),
-            AppError::SyncingError(_) => std::process::exit(6),
-            AppError::TemplatingError(_) => std::process::exit(7),
-
-            #[allow(unreachable_patterns)]
-            _ => std::process::exit(255),
-        }
+        std::process::exit(exit_code(&e));
     }
 }
 
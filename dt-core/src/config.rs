@@ -1,5 +1,8 @@
 use std::{
+    collections::HashMap,
     fmt::Display,
+    io::Read,
+    os::unix::ffi::OsStrExt,
     path::{Path, PathBuf},
     rc::Rc,
     str::FromStr,
@@ -11,6 +14,10 @@ use serde_regex;
 use serde_tuple::Deserialize_tuple;
 use url::Url;
 
+#[cfg(not(test))]
+use crate::utils::hostname;
+#[cfg(test)]
+use crate::utils::testing::hostname;
 use crate::{
     error::{Error as AppError, Result},
     item::Operate,
@@ -26,6 +33,11 @@ impl Display for GroupName {
         f.write_str(&self.0.to_string_lossy())
     }
 }
+/// Characters that are not allowed in group names besides control
+/// characters, since they are either invalid in file names on some
+/// platforms or special to shells.
+const PATH_HOSTILE_CHARS: &[char] = &['\\', ':', '*', '?', '"', '<', '>', '|'];
+
 impl GroupName {
     /// Gets the first component of this name, components are separated by
     /// slashes.
@@ -62,6 +74,16 @@ impl GroupName {
             Err(AppError::ConfigError(
                 "Group name should not be empty".to_owned(),
             ))
+        } else if let Some(c) = self
+            .0
+            .to_string_lossy()
+            .chars()
+            .find(|c| c.is_control() || PATH_HOSTILE_CHARS.contains(c))
+        {
+            Err(AppError::ConfigError(format!(
+                "Group name should not contain control or path-hostile characters, found {:?} in {:?}",
+                c, self.0,
+            )))
         } else {
             Ok(())
         }
@@ -92,6 +114,57 @@ impl GroupName {
                 .collect::<PathBuf>(),
         )
     }
+    /// Gets the [`log`] target used for messages emitted while processing
+    /// the group with this name, so that they can be filtered with e.g.
+    /// `RUST_LOG=dt::gui=trace`.  Components of the name are joined with
+    /// `::` instead of `/`, since `env_logger` starts a regex filter at the
+    /// first `/`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use dt_core::config::GroupName;
+    /// # use pretty_assertions::assert_eq;
+    /// let gn = GroupName("gui/gtk".into());
+    /// assert_eq!(gn.log_target(), "dt::gui::gtk");
+    /// ```
+    ///
+    /// [`log`]: https://docs.rs/log
+    pub fn log_target(&self) -> String {
+        let components: Vec<_> = self.0.iter().map(|comp| comp.to_string_lossy()).collect();
+        format!("dt::{}", components.join("::"))
+    }
+    /// Gets the number of slash-separated components of this name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use dt_core::config::GroupName;
+    /// assert_eq!(GroupName("gui".into()).depth(), 1);
+    /// assert_eq!(GroupName("gui/gtk/theme".into()).depth(), 3);
+    /// ```
+    pub fn depth(&self) -> usize {
+        self.0.components().count()
+    }
+    /// Checks if this name is within the hierarchy of `other`, i.e. if this
+    /// name is `other` itself or one of its (nested) subgroups.  Names are
+    /// compared component-wise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use dt_core::config::GroupName;
+    /// let gn = GroupName("a/b/c".into());
+    /// assert!(gn.is_subgroup_of(&GroupName("a".into())));
+    /// assert!(gn.is_subgroup_of(&GroupName("a/b".into())));
+    /// assert!(gn.is_subgroup_of(&GroupName("a/b/c".into())));
+    /// assert!(!gn.is_subgroup_of(&GroupName("a/b/c/d".into())));
+    /// assert!(!GroupName("abc".into()).is_subgroup_of(&GroupName("a".into())));
+    /// assert!(!GroupName("a/bcd".into()).is_subgroup_of(&GroupName("a/b".into())));
+    /// ```
+    pub fn is_subgroup_of(&self, other: &GroupName) -> bool {
+        self.0.starts_with(&other.0)
+    }
 }
 /// Helper type for config key [`staging`]
 ///
@@ -117,12 +190,80 @@ pub enum SyncMethod {
     /// staging directory, then symlink staged items from their staging
     /// directory to target.
     Symlink,
+    /// Instructs syncing module to symlink each item from its source
+    /// directly to target, without staging.  Items are NOT rendered with
+    /// this method.
+    SymlinkDirect,
 }
+#[allow(clippy::derivable_impls)]
 impl Default for SyncMethod {
     fn default() -> Self {
         SyncMethod::Symlink
     }
 }
+/// Which item to use when both an item and its host-specific counterpart
+/// exist.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum HostSpecificPrecedence {
+    /// The host-specific counterpart always overrides the original item.
+    #[serde(alias = "override")]
+    Override,
+    /// The host-specific counterpart is only used when the original item
+    /// does not exist.
+    #[serde(alias = "fallback")]
+    Fallback,
+}
+#[allow(clippy::derivable_impls)]
+impl Default for HostSpecificPrecedence {
+    fn default() -> Self {
+        HostSpecificPrecedence::Override
+    }
+}
+/// What to do with a listed source that does not exist (or is no longer
+/// readable) when expanding a group.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum OnMissingSource {
+    /// Abort expanding with an error.
+    #[serde(alias = "error")]
+    Error,
+    /// Skip the source with a warning.
+    #[serde(alias = "warn")]
+    Warn,
+    /// Skip the source silently.
+    #[serde(alias = "skip")]
+    Skip,
+}
+#[allow(clippy::derivable_impls)]
+impl Default for OnMissingSource {
+    fn default() -> Self {
+        OnMissingSource::Error
+    }
+}
+/// How to create symlinks with the [`Symlink`] and [`SymlinkDirect`]
+/// methods.
+///
+/// [`Symlink`]: SyncMethod::Symlink
+/// [`SymlinkDirect`]: SyncMethod::SymlinkDirect
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum LinkMode {
+    /// Always create symlinks, errors if the target filesystem does not
+    /// support them.
+    #[serde(alias = "symlink")]
+    Symlink,
+    /// Copy the item (the staged one with the [`Symlink`] method) to its
+    /// target instead, when the target filesystem (e.g. FAT/exFAT) does not
+    /// support symlinks.
+    ///
+    /// [`Symlink`]: SyncMethod::Symlink
+    #[serde(alias = "copy_fallback")]
+    CopyFallback,
+}
+#[allow(clippy::derivable_impls)]
+impl Default for LinkMode {
+    fn default() -> Self {
+        LinkMode::Symlink
+    }
+}
 /// Helper type for config key [`subgroup_prefix`]
 ///
 /// [`subgroup_prefix`]: GlobalConfig::subgroup_prefix
@@ -281,6 +422,7 @@ pub enum DTScope {
     /// The scope for drop-in replacements, it has the highest priority.
     Dropin,
 }
+#[allow(clippy::derivable_impls)]
 impl Default for DTScope {
     fn default() -> Self {
         DTScope::General
@@ -299,27 +441,127 @@ pub struct DTConfig {
     pub context: ContextConfig,
 
     /// Groups containing local files.
+    ///
+    /// Groups are guaranteed to keep the order they are defined in, through
+    /// loading (e.g. [`DTConfig::from_str`]), filtering and expanding for
+    /// syncing, since former defined groups have higher priority than latter
+    /// ones of the same [`scope`].
+    ///
+    /// [`scope`]: Group::scope
     pub local: Vec<LocalGroup>,
 
     /// Groups containing remote files.
     pub remote: Vec<RemoteGroup>,
+
+    /// Named profiles, each enables a set of groups.  See [`Profile`].
+    pub profiles: HashMap<String, Profile>,
+
+    /// User-defined template snippets, registered as partials with their
+    /// names, e.g. `greeting = "Hi {{name}}"` can be used in templates as
+    /// `{{> greeting}}`.  Names should not clash with built-in helpers.
+    pub helpers: HashMap<String, String>,
+
+    /// Whether groups have been filtered out of this config, e.g. with
+    /// [`DTConfig::filter_names`], [`DTConfig::filter_tags`] or
+    /// [`DTConfig::select_profile`].  A filtered config does not describe
+    /// every target, thus can not be used to delete orphaned targets, see
+    /// [`SyncOptions::delete_orphans`].
+    ///
+    /// [`SyncOptions::delete_orphans`]: crate::syncing::SyncOptions::delete_orphans
+    #[serde(skip)]
+    pub filtered: bool,
+}
+
+/// A named set of groups that can be activated together, e.g. groups
+/// meant for a laptop.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    /// Names of the groups enabled by this profile.  Groups are matched
+    /// hierarchically, like [`DTConfig::filter_names`].
+    pub groups: Vec<String>,
 }
 
+/// First line of a config file that is rendered before it is parsed, see
+/// [`DTConfig::from_str_templated`].
+pub const TEMPLATE_MARKER: &str = "# dt:template";
+
 impl FromStr for DTConfig {
     type Err = AppError;
 
-    /// Loads configuration from string.
+    /// Loads configuration from string.  For quick one-off configs, a
+    /// singular `[group]` table is accepted in place of `[[local]]`, and is
+    /// loaded as the only local group.  A string whose first line is
+    /// [`TEMPLATE_MARKER`] is rendered first, see
+    /// [`DTConfig::from_str_templated`].
     fn from_str(s: &str) -> Result<Self> {
-        toml::from_str::<Self>(s)?.expand_tilde().validate()
+        Self::load(s, None)
     }
 }
 
 impl DTConfig {
+    /// Loads configuration from string, after rendering it with
+    /// [`render_config`], e.g. `base = "~/.config/{{get_mine}}"`.  Only
+    /// helpers without side effects are available, and there is no
+    /// context.
+    ///
+    /// [`render_config`]: crate::registry::render_config
+    pub fn from_str_templated(s: &str) -> Result<Self> {
+        Self::parse(&crate::registry::render_config(s)?, None)
+    }
+
+    /// Loads configuration from string `s`, read from the config file at
+    /// `path` if any, see [`DTConfig::from_str`].
+    fn load(s: &str, path: Option<&Path>) -> Result<Self> {
+        if s.lines().next().map(str::trim_end) == Some(TEMPLATE_MARKER) {
+            Self::parse(&crate::registry::render_config(s)?, path)
+        } else {
+            Self::parse(s, path)
+        }
+    }
+
+    /// Loads configuration from string as it is, see [`DTConfig::from_str`].
+    fn parse(s: &str, path: Option<&Path>) -> Result<Self> {
+        let mut value: toml::Value = toml::from_str(s)?;
+        if let Some(table) = value.as_table_mut() {
+            if let Some(group) = table.remove("group") {
+                if table.contains_key("local") {
+                    return Err(AppError::ConfigError(
+                        "`group` and `local` should not be both defined".to_owned(),
+                    ));
+                }
+                table.insert("local".to_owned(), toml::Value::Array(vec![group]));
+            }
+        }
+        value
+            .try_into::<Self>()?
+            .expand_tilde()
+            .isolate_staging(match path {
+                Some(path) => path.as_os_str().as_bytes(),
+                None => s.as_bytes(),
+            })
+            .validate()?
+            .validate_fs()
+    }
+
+    /// Appends a short hash of `key` (the config file's canonical path, or
+    /// the config string when it is not loaded from a file) to
+    /// `global.staging` when [`isolate_staging`] is set.
+    ///
+    /// [`isolate_staging`]: GlobalConfig::isolate_staging
+    fn isolate_staging(mut self, key: &[u8]) -> Self {
+        if self.global.isolate_staging {
+            let staging = &mut self.global.staging.0;
+            staging.push(crate::utils::short_hash(key));
+        }
+        self
+    }
+
     /// Loads configuration from a file.
     pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
         let path = path.as_ref();
         if let Ok(confstr) = std::fs::read_to_string(path) {
-            Self::from_str(&confstr)
+            Self::load(&confstr, Some(&path.canonicalize()?))
         } else {
             Err(AppError::ConfigError(format!(
                 "Could not load config from '{}'",
@@ -328,39 +570,330 @@ impl DTConfig {
         }
     }
 
+    /// Loads configuration from a file, after rendering it like
+    /// [`DTConfig::from_str_templated`].
+    pub fn from_path_templated(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let confstr = std::fs::read_to_string(path)?;
+        Self::parse(
+            &crate::registry::render_config(&confstr)?,
+            Some(&path.canonicalize()?),
+        )
+    }
+
+    /// Loads configuration from a reader, e.g. the standard input or an
+    /// in-memory buffer.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self> {
+        let mut confstr = String::new();
+        reader.read_to_string(&mut confstr)?;
+        Self::from_str(&confstr)
+    }
+
+    /// Gets names of all groups, in the order they are defined, with local
+    /// groups before remote ones.  Remote groups are only included when
+    /// `include_remote` is `true`.
+    pub fn group_names(&self, include_remote: bool) -> Vec<String> {
+        let local = self.local.iter().map(|l| l.name.to_string());
+        if include_remote {
+            local
+                .chain(self.remote.iter().map(|r| r.name.to_string()))
+                .collect()
+        } else {
+            local.collect()
+        }
+    }
+
+    /// Lists names and [`description`]s of all groups, in the order they are
+    /// defined, with local groups before remote ones.  Groups without a
+    /// description are listed with an empty one.
+    ///
+    /// [`description`]: Group::description
+    pub fn list_groups(&self) -> Vec<(String, String)> {
+        let local = self
+            .local
+            .iter()
+            .map(|l| (l.name.to_string(), l.description.to_owned()));
+        let remote = self
+            .remote
+            .iter()
+            .map(|r| (r.name.to_string(), r.description.to_owned()));
+        local
+            .chain(remote)
+            .map(|(name, description)| (name, description.unwrap_or_default()))
+            .collect()
+    }
+
     /// Construct another [`DTConfig`] object with groups that match given
     /// filters.  Groups are matched hierarchically, e.g. a filter `a/b` will
     /// select `a/b/c` and `a/b/d`, but not `a/bcd`.
     pub fn filter_names(self, group_names: Vec<String>) -> Self {
+        let group_names: Vec<GroupName> = group_names
+            .into_iter()
+            .map(|n| GroupName(n.into()))
+            .collect();
+        Self {
+            global: self.global,
+            context: self.context,
+            profiles: self.profiles,
+            helpers: self.helpers,
+            filtered: true,
+            local: self
+                .local
+                .iter()
+                .filter(|l| group_names.iter().any(|n| l.name.is_subgroup_of(n)))
+                .map(|l| l.to_owned())
+                .collect(),
+            remote: self
+                .remote
+                .iter()
+                .filter(|l| group_names.iter().any(|n| l.name.is_subgroup_of(n)))
+                .map(|l| l.to_owned())
+                .collect(),
+        }
+    }
+
+    /// Construct another [`DTConfig`] object with groups that have any of
+    /// the `tags` and none of the `excluded_tags`.  An empty `tags` selects
+    /// all groups that have none of the `excluded_tags`.
+    pub fn filter_tags(self, tags: Vec<String>, excluded_tags: Vec<String>) -> Self {
+        let selected = |group_tags: &[String]| {
+            (tags.is_empty() || group_tags.iter().any(|t| tags.contains(t)))
+                && !group_tags.iter().any(|t| excluded_tags.contains(t))
+        };
         Self {
             global: self.global,
             context: self.context,
+            profiles: self.profiles,
+            helpers: self.helpers,
+            filtered: true,
             local: self
                 .local
                 .iter()
-                .filter(|l| group_names.iter().any(|n| l.name.0.starts_with(n)))
+                .filter(|l| selected(&l.tags))
                 .map(|l| l.to_owned())
                 .collect(),
             remote: self
                 .remote
                 .iter()
-                .filter(|l| group_names.iter().any(|n| l.name.0.starts_with(n)))
+                .filter(|l| selected(&l.tags))
                 .map(|l| l.to_owned())
                 .collect(),
         }
     }
 
-    /// Validates config object.  After this, the original `global` and
-    /// `context` sections are referenced by each group via an [Rc] and can be
-    /// safely ignored in further processing.
+    /// Prefixes the absolute target of every local and remote group with
+    /// `root`, like `make DESTDIR=<root> install`, so that a group targeting
+    /// `/etc/xdg` syncs to `<root>/etc/xdg`.  Groups are validated again
+    /// with their prefixed targets.
+    pub fn with_target_prefix<P: AsRef<Path>>(self, root: P) -> Result<Self> {
+        let root = root.as_ref().to_owned().absolute()?;
+        let mut ret = self;
+        for group in &mut ret.local {
+            let target = group.target.to_owned().absolute()?;
+            group.target = root.join(target.strip_prefix("/")?);
+            group.validate()?;
+        }
+        for group in &mut ret.remote {
+            let target = group.target.to_owned().absolute()?;
+            group.target = root.join(target.strip_prefix("/")?);
+            group.validate_no_fs()?;
+        }
+        Ok(ret)
+    }
+
+    /// Applies `overrides` to the `global` section, e.g. from command line
+    /// options, and attaches the updated section to every group.  Groups
+    /// are validated again with the updated section.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::str::FromStr;
+    /// # use dt_core::{config::DTConfig, error::Error as AppError};
+    /// let config = DTConfig::from_str(
+    ///     r#"
+    /// [[local]]
+    /// name = "nvim"
+    /// base = "/tmp/dotfiles/nvim"
+    /// sources = []
+    /// target = "/tmp/.config/nvim""#,
+    /// )?
+    /// .with_global(|global| global.force_mode = Some(0o644))?;
+    /// assert_eq!(config.local[0].global.force_mode, Some(0o644));
+    /// # Ok::<(), AppError>(())
+    /// ```
+    pub fn with_global<F: FnOnce(&mut GlobalConfig)>(self, overrides: F) -> Result<Self> {
+        let mut ret = self;
+        overrides(&mut ret.global);
+        let global_ref = Rc::new(ret.global.to_owned());
+        for group in &mut ret.local {
+            group.global = Rc::clone(&global_ref);
+            group.validate_no_fs()?;
+        }
+        for group in &mut ret.remote {
+            group.global = Rc::clone(&global_ref);
+            group.validate_no_fs()?;
+        }
+        Ok(ret)
+    }
+
+    /// Construct another [`DTConfig`] object with groups that are enabled by
+    /// the profile with given name.  Errors if no profile is named so.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::str::FromStr;
+    /// # use dt_core::{config::DTConfig, error::Error as AppError};
+    /// let config = DTConfig::from_str(
+    ///     r#"
+    /// [profiles.laptop]
+    /// groups = ["gui"]
+    ///
+    /// [[local]]
+    /// name = "gui/gtk"
+    /// base = "/tmp/dotfiles/gtk"
+    /// sources = []
+    /// target = "/tmp/.config/gtk-3.0"
+    ///
+    /// [[local]]
+    /// name = "server"
+    /// base = "/tmp/dotfiles/server"
+    /// sources = []
+    /// target = "/tmp/.config/server""#,
+    /// )?;
+    /// let laptop = config.to_owned().select_profile("laptop")?;
+    /// assert_eq!(laptop.local.len(), 1);
+    /// assert_eq!(laptop.local[0].name.to_string(), "gui/gtk");
+    /// assert!(config.select_profile("desktop").is_err());
+    /// # Ok::<(), AppError>(())
+    /// ```
+    pub fn select_profile(self, profile_name: &str) -> Result<Self> {
+        match self.profiles.get(profile_name) {
+            Some(profile) => {
+                let group_names = profile.groups.to_owned();
+                Ok(self.filter_names(group_names))
+            }
+            None => Err(AppError::ConfigError(format!(
+                "no profile is named '{}'",
+                profile_name,
+            ))),
+        }
+    }
+
+    /// Gets the absolute path to the staging directory of the local group
+    /// with given name, see [`Group::get_staging_dir`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::{path::PathBuf, str::FromStr};
+    /// # use dt_core::{config::DTConfig, error::Error as AppError};
+    /// let config = DTConfig::from_str(
+    ///     r##"
+    /// [global]
+    /// staging = "/tmp/staging"
+    /// subgroup_prefix = "#"
+    ///
+    /// [[local]]
+    /// name = "gui/gtk"
+    /// base = "/tmp/dotfiles/gtk"
+    /// sources = []
+    /// target = "/tmp/.config/gtk-3.0""##,
+    /// )?;
+    /// assert_eq!(
+    ///     config.get_staging_dir("gui/gtk")?,
+    ///     PathBuf::from_str("/tmp/staging/gui/#gtk").unwrap(),
+    /// );
+    /// assert!(config.get_staging_dir("gui").is_err());
+    /// # Ok::<(), AppError>(())
+    /// ```
+    pub fn get_staging_dir(&self, group_name: &str) -> Result<PathBuf> {
+        match self
+            .local
+            .iter()
+            .find(|group| group.name.0 == Path::new(group_name))
+        {
+            Some(group) => group.get_staging_dir().absolute(),
+            None => Err(AppError::ConfigError(format!(
+                "no local group is named '{}'",
+                group_name,
+            ))),
+        }
+    }
+
+    /// Validates config object without querying the filesystem, so that
+    /// configs built programmatically can be validated as well.  After this,
+    /// the original `global` and `context` sections are referenced by each
+    /// group via an [Rc] and can be safely ignored in further processing.
+    ///
+    /// `${key}` placeholders in each group's `name` and `target` are
+    /// substituted with scalar values from the `context` section here, where
+    /// `key` is a dotted path, e.g. `${gui.font}`.  A group with a
+    /// [`staging_name`] should not share its staging directory with any
+    /// other group.
+    ///
+    /// Checks that need to query the filesystem are done separately with
+    /// [`validate_fs`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use dt_core::{
+    /// #     config::{DTConfig, GroupName, LocalGroup},
+    /// #     error::Error as AppError,
+    /// # };
+    /// let config = DTConfig {
+    ///     local: vec![LocalGroup {
+    ///         name: GroupName("nvim".into()),
+    ///         base: "/tmp/dotfiles/nvim".into(),
+    ///         sources: vec!["init.lua".into()],
+    ///         target: "/tmp/.config/nvim".into(),
+    ///         ..Default::default()
+    ///     }],
+    ///     ..Default::default()
+    /// };
+    /// let config = config.validate()?;
+    /// assert_eq!(config.local[0].get_hostname_sep(), "@@");
+    ///
+    /// let config = DTConfig {
+    ///     local: vec![LocalGroup {
+    ///         name: GroupName("nvim".into()),
+    ///         base: "/tmp/dotfiles/nvim".into(),
+    ///         sources: vec!["../init.lua".into()],
+    ///         target: "/tmp/.config/nvim".into(),
+    ///         ..Default::default()
+    ///     }],
+    ///     ..Default::default()
+    /// };
+    /// assert!(config.validate().is_err());
+    /// # Ok::<(), AppError>(())
+    /// ```
     ///
     /// [Rc]: std::rc::Rc
-    fn validate(self) -> Result<Self> {
-        if !self.context.0.is_table() {
+    /// [`staging_name`]: Group::staging_name
+    /// [`validate_fs`]: DTConfig::validate_fs
+    pub fn validate(self) -> Result<Self> {
+        if !self.context.as_value().is_table() {
             return Err(AppError::ConfigError(
                 "`context` is expected to be a table".to_owned(),
             ));
         }
+        let missing: Vec<&str> = self
+            .global
+            .required_context
+            .iter()
+            .filter(|key| self.context.get(key).is_none())
+            .map(|key| key.as_str())
+            .collect();
+        if !missing.is_empty() {
+            return Err(AppError::ConfigError(format!(
+                "required context key(s) missing: {}",
+                missing.join(", "),
+            )));
+        }
+
+        self.global.get_source_encoding()?;
 
         let global_ref = Rc::new(self.global.to_owned());
         let context_ref = Rc::new(self.context.to_owned());
@@ -368,26 +901,85 @@ impl DTConfig {
         let mut ret: Self = self;
 
         for group in &mut ret.local {
+            group.name = GroupName(
+                context_ref
+                    .substitute(&group.name.0.to_string_lossy())?
+                    .into(),
+            );
+            group.target = context_ref
+                .substitute(&group.target.to_string_lossy())?
+                .into();
             group.global = Rc::clone(&global_ref);
             group.context = Rc::clone(&context_ref);
-            group.validate()?;
+            group.validate_no_fs()?;
         }
         for group in &mut ret.remote {
+            group.name = GroupName(
+                context_ref
+                    .substitute(&group.name.0.to_string_lossy())?
+                    .into(),
+            );
+            // Remote items are not expanded like local ones, so their
+            // targets are made absolute here
+            group.target = PathBuf::from(context_ref.substitute(&group.target.to_string_lossy())?)
+                .absolute()?;
             group.global = Rc::clone(&global_ref);
             group.context = Rc::clone(&context_ref);
-            group.validate()?;
+            group.validate_no_fs()?;
+        }
+
+        // Groups with a `staging_name` should not share their staging
+        // directory with any other group
+        let staging_dirs: Vec<(&GroupName, bool, PathBuf)> = ret
+            .local
+            .iter()
+            .map(|g| (&g.name, g.staging_name.is_some(), g.get_staging_dir()))
+            .chain(
+                ret.remote
+                    .iter()
+                    .map(|g| (&g.name, g.staging_name.is_some(), g.get_staging_dir())),
+            )
+            .collect();
+        for (i, (name, named, dir)) in staging_dirs.iter().enumerate() {
+            if let Some((other, _, _)) = staging_dirs[i + 1..]
+                .iter()
+                .find(|(_, other_named, other_dir)| (*named || *other_named) && other_dir == dir)
+            {
+                return Err(AppError::ConfigError(format!(
+                    "groups '{}' and '{}' share the same staging directory '{}'",
+                    name,
+                    other,
+                    dir.display(),
+                )));
+            }
         }
 
         Ok(ret)
     }
 
+    /// Validates config object via querying the filesystem.  This is expected
+    /// to be called after [`validate`], which attaches the `global` and
+    /// `context` sections to each group.
+    ///
+    /// [`validate`]: DTConfig::validate
+    pub fn validate_fs(self) -> Result<Self> {
+        for group in &self.local {
+            group.validate_fs()?;
+        }
+        for group in &self.remote {
+            group.validate_fs()?;
+        }
+
+        Ok(self)
+    }
+
     fn expand_tilde(self) -> Self {
         let mut ret = self;
 
         // Expand tilde in `global.staging`
         let staging = &mut ret.global.staging;
         *staging = if *staging == StagingPath("".into()) {
-            log::warn!("Empty staging path is replaced to '.'");
+            crate::utils::warning!("Empty staging path is replaced to '.'");
             StagingPath(".".into())
         } else {
             StagingPath(
@@ -395,11 +987,17 @@ impl DTConfig {
             )
         };
 
+        // Expand tilde in `global.conflict_log`
+        if let Some(conflict_log) = &mut ret.global.conflict_log {
+            *conflict_log =
+                PathBuf::from_str(&shellexpand::tilde(&conflict_log.to_string_lossy())).unwrap();
+        }
+
         // Expand tilde in `base` and `target` of `local`
         for group in &mut ret.local {
             // `local.base`
             group.base = if group.base == PathBuf::from_str("").unwrap() {
-                log::warn!("[{}]: Empty base is replaced to '.'", group.name);
+                crate::utils::warning!("[{}]: Empty base is replaced to '.'", group.name);
                 ".".into()
             } else {
                 PathBuf::from_str(&shellexpand::tilde(&group.base.to_string_lossy())).unwrap()
@@ -407,7 +1005,7 @@ impl DTConfig {
 
             // `local.target`
             group.target = if group.target == PathBuf::from_str("").unwrap() {
-                log::warn!("[{}]: Empty target is replaced to '.'", group.name,);
+                crate::utils::warning!("[{}]: Empty target is replaced to '.'", group.name,);
                 ".".into()
             } else {
                 PathBuf::from_str(&shellexpand::tilde(&group.target.to_string_lossy())).unwrap()
@@ -436,6 +1034,57 @@ pub struct RenamingRule {
     pub substitution: String,
 }
 
+/// Suffix of a [`Sidecar`] file's name, appended to the name of the item it
+/// configures.
+pub const SIDECAR_SUFFIX: &str = ".dt.toml";
+
+/// Per-item overrides, read from an optional sidecar file next to the item,
+/// named after the item with a [`SIDECAR_SUFFIX`] (e.g. `foo.conf.dt.toml`
+/// for `foo.conf`).  Sidecar files are never synced themselves.
+///
+/// ```toml
+/// method = "Copy"
+/// rename = [["^foo.conf$", "bar.conf"]]
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Sidecar {
+    /// (Optional) Syncing method of the item, overrides its group's
+    /// [`method`] and [`global.default_method_for_binary`].
+    ///
+    /// [`method`]: Group::method
+    /// [`global.default_method_for_binary`]: GlobalConfig::default_method_for_binary
+    pub method: Option<SyncMethod>,
+
+    /// (Optional) Renaming rules of the item, replaces the renaming rules
+    /// from both its group's [`rename`] and [`global.rename`].
+    ///
+    /// [`rename`]: Group::rename
+    /// [`global.rename`]: GlobalConfig::rename
+    pub rename: Option<RenamingRules>,
+}
+
+impl Sidecar {
+    /// Reads the sidecar of given item, returns the default (i.e. no
+    /// overrides) if the item has no sidecar.
+    pub fn of<P: AsRef<Path>>(item: P) -> Result<Self> {
+        let mut path = item.as_ref().as_os_str().to_owned();
+        path.push(SIDECAR_SUFFIX);
+        let path = PathBuf::from(path);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        toml::from_str(&std::fs::read_to_string(&path)?).map_err(|e| {
+            AppError::ConfigError(format!("invalid sidecar '{}': {}", path.display(), e))
+        })
+    }
+
+    /// Checks whether given path is a sidecar file.
+    pub fn is_sidecar<P: AsRef<Path>>(path: P) -> bool {
+        path.as_ref().to_string_lossy().ends_with(SIDECAR_SUFFIX)
+    }
+}
+
 /// Configures default behaviours.
 #[derive(Clone, Debug, Default, Deserialize)]
 pub struct GlobalConfig {
@@ -457,23 +1106,78 @@ pub struct GlobalConfig {
     #[serde(default)]
     pub staging: StagingPath,
 
+    /// Whether to stage items under a subdirectory of [`staging`] named by a
+    /// short hash of the config file's canonical path (or of the config's
+    /// contents when it is not loaded from a file), so that different
+    /// configs do not clobber each other's staging trees.  Default to
+    /// `false`.
+    ///
+    /// [`staging`]: GlobalConfig::staging
+    #[serde(default)]
+    pub isolate_staging: bool,
+
+    /// Whether to create target symlinks of items with the [`Symlink`]
+    /// method even when the [`staging`] directory does not exist yet (e.g.
+    /// it is on a volume that is not mounted yet), leaving them dangling
+    /// until the staging directory is populated.  Nothing is staged, and no
+    /// lock is taken in this case.  Default to `false`, i.e. the staging
+    /// directory is created and every item is staged before linking.
+    ///
+    /// [`Symlink`]: SyncMethod::Symlink
+    /// [`staging`]: GlobalConfig::staging
+    #[serde(default)]
+    pub symlink_broken_ok: bool,
+
+    /// Command run with `sh -c` once before syncing any group, e.g. to
+    /// mount the staging volume.  Syncing is aborted when it fails, unless
+    /// [`ignore_hook_failure`] is set.  It is only printed under dry run.
+    /// Default to `None`.
+    ///
+    /// [`ignore_hook_failure`]: GlobalConfig::ignore_hook_failure
+    #[serde(default)]
+    pub pre_sync: Option<String>,
+
+    /// Command run with `sh -c` once after all groups are synced
+    /// successfully, e.g. to reload the window manager.  It is only printed
+    /// under dry run.  Default to `None`.
+    #[serde(default)]
+    pub post_sync: Option<String>,
+
+    /// Whether to treat failures of [`pre_sync`] and [`post_sync`] commands
+    /// as warnings instead of errors.  Default to `false`.
+    ///
+    /// [`pre_sync`]: GlobalConfig::pre_sync
+    /// [`post_sync`]: GlobalConfig::post_sync
+    #[serde(default)]
+    pub ignore_hook_failure: bool,
+
     /// The syncing method.
     ///
     /// Available values are:
     ///
     /// - [`Copy`]
     /// - [`Symlink`]
+    /// - [`SymlinkDirect`]
     ///
-    /// When [`method`] is [`Copy`], the above [`staging`] setting will be
-    /// disabled.
+    /// When [`method`] is [`Copy`] or [`SymlinkDirect`], the above
+    /// [`staging`] setting will be disabled.
     ///
     /// [`method`]: GlobalConfig::method
     /// [`staging`]: GlobalConfig::staging
     /// [`Copy`]: SyncMethod::Copy
     /// [`Symlink`]: SyncMethod::Symlink
+    /// [`SymlinkDirect`]: SyncMethod::SymlinkDirect
     #[serde(default)]
     pub method: SyncMethod,
 
+    /// (Optional) Syncing method for items with binary contents, overrides
+    /// the syncing method of their groups, e.g. set it to
+    /// [`SymlinkDirect`] to avoid staging large binary files.
+    ///
+    /// [`SymlinkDirect`]: SyncMethod::SymlinkDirect
+    #[serde(default)]
+    pub default_method_for_binary: Option<SyncMethod>,
+
     /// A string to be prepended to a subgroup's name when creating its
     /// staging directory with the [`Symlink`] syncing method.
     ///
@@ -514,6 +1218,20 @@ pub struct GlobalConfig {
     #[serde(default)]
     pub hostname_sep: HostnameSeparator,
 
+    /// Which item to use when both an item and its host-specific
+    /// counterpart exist.
+    ///
+    /// Available values are:
+    ///
+    /// - [`Override`]: the host-specific counterpart wins (default)
+    /// - [`Fallback`]: the original item wins, the host-specific counterpart
+    ///   is only used when the original item does not exist
+    ///
+    /// [`Override`]: HostSpecificPrecedence::Override
+    /// [`Fallback`]: HostSpecificPrecedence::Fallback
+    #[serde(default)]
+    pub host_specific_precedence: HostSpecificPrecedence,
+
     /// Global item renaming rules.
     ///
     /// Rules defined here will be prepended to renaming rules of each group.
@@ -522,18 +1240,288 @@ pub struct GlobalConfig {
     /// [`Group::rename`]: Group::rename
     #[serde(default)]
     pub rename: RenamingRules,
-}
 
-/// Templating values are defined in this section.
-#[derive(Clone, Debug, Deserialize, Serialize)]
-pub struct ContextConfig(toml::Value);
-impl Default for ContextConfig {
-    fn default() -> Self {
-        Self(toml::map::Map::new().into())
-    }
-}
+    /// Ignored names of all groups, e.g. `[".git", ".DS_Store", "*.swp"]`.
+    ///
+    /// Patterns defined here will be prepended to ignored names of each
+    /// group.  See [`Group::ignored`].
+    ///
+    /// [`Group::ignored`]: Group::ignored
+    #[serde(default)]
+    pub ignore_patterns: Vec<String>,
 
-/// Configures how items are grouped.
+    /// Dotted paths to keys that must be defined in the [`context`]
+    /// section, e.g. `["gui.font", "gui.dpi"]`.  Loading a config that
+    /// misses any of them fails, instead of silently rendering empty values.
+    ///
+    /// [`context`]: DTConfig::context
+    #[serde(default)]
+    pub required_context: Vec<String>,
+
+    /// Whether to fail a syncing run if any warning is logged during it,
+    /// e.g. a source that does not match anything, or a permission that
+    /// could not be set.  Useful for running DT in CI.  Default to `false`.
+    #[serde(default)]
+    pub fail_on_warning: bool,
+
+    /// Whether to warn when a group's [`sources`] expand to the same item
+    /// more than once (e.g. a source listed twice, or overlapping globs),
+    /// naming the duplicated item.  Duplicates are always collapsed; with
+    /// [`fail_on_warning`] set, they fail the run instead.  Default to
+    /// `false`.
+    ///
+    /// [`sources`]: Group::sources
+    /// [`fail_on_warning`]: GlobalConfig::fail_on_warning
+    #[serde(default)]
+    pub warn_on_duplicate_sources: bool,
+
+    /// Number of times to retry writing, symlinking and setting permissions
+    /// of an item when it fails with a transient IO error (e.g. on a flaky
+    /// network filesystem), with a short exponential backoff.  Other errors
+    /// are never retried.  Default to `0`.
+    #[serde(default)]
+    pub fs_retries: u32,
+
+    /// Whether to skip creating the target of a renderable item whose
+    /// rendered contents are empty (e.g. when all of its blocks are
+    /// excluded under current context), instead of writing an empty file.
+    /// Default to `false`.
+    #[serde(default)]
+    pub skip_empty_renders: bool,
+
+    /// Hostname to use in place of current machine's hostname, when
+    /// deciding which host-specific items to sync (see [`hostname_sep`])
+    /// and in the host-related template helpers.  Useful for previewing
+    /// another machine's dotfiles.  The command line's `--hostname` takes
+    /// precedence over this.  Default to `None`, i.e. use the machine's
+    /// hostname.
+    ///
+    /// [`hostname_sep`]: GlobalConfig::hostname_sep
+    #[serde(default)]
+    pub hostname_override: Option<String>,
+
+    /// Path to a file where every source dropped for priority conflicts
+    /// (i.e. another group with a higher [`scope`] or an earlier definition
+    /// syncs to the same target) is appended to, along with the losing
+    /// group, the winning group, and the target, one conflict per line.
+    /// The file is written whenever the config is expanded, including dry
+    /// runs.  Default to `None`, where conflicts are not recorded.
+    ///
+    /// [`scope`]: Group::scope
+    #[serde(default)]
+    pub conflict_log: Option<PathBuf>,
+
+    /// Permission bits to mask out from a source's mode, when DT copies it
+    /// to a target (or a staging file), like a shell's `umask`.  Written as
+    /// an octal integer, e.g. `umask = 0o022`.  Default to `0`, i.e. the
+    /// source's mode is kept as is.
+    #[serde(default)]
+    pub umask: u32,
+
+    /// Encoding of renderable text sources, as a [WHATWG encoding label]
+    /// (e.g. `"latin1"`, `"shift_jis"`).  Such sources are decoded with it
+    /// before rendering, and their rendered contents are encoded back with
+    /// it.  Default to `None`, i.e. UTF-8.
+    ///
+    /// [WHATWG encoding label]: https://encoding.spec.whatwg.org/#names-and-labels
+    #[serde(default)]
+    pub source_encoding: Option<String>,
+
+    /// Whether to copy items to their targets when the target filesystem
+    /// does not support symlinks.  Default to [`Symlink`], i.e. such
+    /// failures abort syncing.
+    ///
+    /// [`Symlink`]: LinkMode::Symlink
+    #[serde(default)]
+    pub link_mode: LinkMode,
+
+    /// Maximum size in bytes of a rendered template, rendering fails once
+    /// it is exceeded.  Default to `None`, i.e. unlimited.
+    #[serde(default)]
+    pub max_render_size: Option<usize>,
+
+    /// Maximum time in milliseconds spent on rendering a template, rendering
+    /// fails once it is exceeded.  Default to `None`, i.e. unlimited.
+    #[serde(default)]
+    pub max_render_time: Option<u64>,
+
+    /// Permission bits every target (or staging file, with the [`Symlink`]
+    /// method) is set to, instead of the source's mode masked by
+    /// [`umask`].  Written as an octal integer, e.g. `force_mode = 0o644`.
+    /// Default to `None`, i.e. the source's mode is mirrored.
+    ///
+    /// [`Symlink`]: SyncMethod::Symlink
+    /// [`umask`]: GlobalConfig::umask
+    #[serde(default)]
+    pub force_mode: Option<u32>,
+
+    /// What to do with a listed source that does not exist or is not
+    /// readable.  Sources given as globs that match nothing are not
+    /// affected, they are always skipped with a warning.  When unset, a
+    /// source that does not exist is skipped with a warning like an
+    /// unmatched glob, and an unreadable source is an [`Error`].
+    ///
+    /// [`Error`]: OnMissingSource::Error
+    #[serde(default)]
+    pub on_missing_source: Option<OnMissingSource>,
+}
+
+impl GlobalConfig {
+    /// Gets the encoding named by [`source_encoding`], falls back to UTF-8
+    /// when it is not set.  Errors if the label is unknown.
+    ///
+    /// [`source_encoding`]: GlobalConfig::source_encoding
+    pub fn get_source_encoding(&self) -> Result<&'static encoding_rs::Encoding> {
+        match &self.source_encoding {
+            Some(label) => encoding_rs::Encoding::for_label(label.as_bytes()).ok_or_else(|| {
+                AppError::ConfigError(format!("unknown source encoding '{}'", label))
+            }),
+            None => Ok(encoding_rs::UTF_8),
+        }
+    }
+
+    /// Gets the hostname DT should consider current machine to have, i.e.
+    /// [`hostname_override`] if set, or the machine's hostname otherwise.
+    ///
+    /// [`hostname_override`]: GlobalConfig::hostname_override
+    pub fn get_hostname(&self) -> String {
+        self.hostname_override.to_owned().unwrap_or_else(hostname)
+    }
+}
+
+/// Formats to dump a [`ContextConfig`] in, see [`ContextConfig::dump`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DumpFormat {
+    /// Dumps as (pretty-printed) JSON, which is how templates see it.
+    Json,
+    /// Dumps as TOML, like the config file's [`context`] section.
+    ///
+    /// [`context`]: DTConfig::context
+    Toml,
+}
+
+impl FromStr for DumpFormat {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "json" => Ok(Self::Json),
+            "toml" => Ok(Self::Toml),
+            _ => Err(AppError::ParseError(format!(
+                "unknown dump format '{}', expected one of `json`, `toml`",
+                s,
+            ))),
+        }
+    }
+}
+
+/// Templating values are defined in this section.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ContextConfig(toml::Value);
+impl Default for ContextConfig {
+    fn default() -> Self {
+        Self(toml::map::Map::new().into())
+    }
+}
+
+impl ContextConfig {
+    /// Gets the value at given dotted path, e.g. `gui.font`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dt_core::{config::DTConfig, error::Error};
+    /// # use std::str::FromStr;
+    /// let config = DTConfig::from_str(
+    ///     r#"
+    /// [context.gui]
+    /// font = "Sarasa Mono SC"
+    /// "#,
+    /// )?;
+    /// assert_eq!(
+    ///     config.context.get("gui.font").and_then(|v| v.as_str()),
+    ///     Some("Sarasa Mono SC"),
+    /// );
+    /// assert!(config.context.get("gui.dpi").is_none());
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn get(&self, dotted_key: &str) -> Option<&toml::Value> {
+        dotted_key
+            .split('.')
+            .try_fold(&self.0, |value, comp| value.get(comp))
+    }
+
+    /// Gets the whole context as a [`toml::Value`].
+    pub fn as_value(&self) -> &toml::Value {
+        &self.0
+    }
+
+    /// Serializes the effective templating values in given format.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dt_core::{config::{DTConfig, DumpFormat}, error::Error};
+    /// # use std::str::FromStr;
+    /// let config = DTConfig::from_str(
+    ///     r#"
+    /// [context.gui]
+    /// font = "Sarasa Mono SC"
+    /// "#,
+    /// )?;
+    /// assert_eq!(
+    ///     config.context.dump(DumpFormat::Json)?,
+    ///     r#"{
+    ///   "gui": {
+    ///     "font": "Sarasa Mono SC"
+    ///   }
+    /// }"#,
+    /// );
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn dump(&self, format: DumpFormat) -> Result<String> {
+        match format {
+            DumpFormat::Json => Ok(serde_json::to_string_pretty(&self.0)?),
+            DumpFormat::Toml => Ok(toml::to_string_pretty(&self.0)?),
+        }
+    }
+
+    /// Substitutes `${key}` placeholders in given string with scalar values
+    /// from this context, where `key` is a dotted path.  Errors on
+    /// unresolved or unterminated placeholders, and on non-scalar values.
+    fn substitute(&self, s: &str) -> Result<String> {
+        let mut ret = String::new();
+        let mut rest = s;
+        while let Some(start) = rest.find("${") {
+            ret.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            let end = after.find('}').ok_or_else(|| {
+                AppError::ConfigError(format!("unterminated placeholder in '{}'", s))
+            })?;
+            let key = &after[..end];
+            let value = self.get(key).ok_or_else(|| {
+                AppError::ConfigError(format!("unresolved placeholder '${{{}}}' in '{}'", key, s))
+            })?;
+            match value {
+                toml::Value::String(v) => ret.push_str(v),
+                toml::Value::Integer(v) => ret.push_str(&v.to_string()),
+                toml::Value::Float(v) => ret.push_str(&v.to_string()),
+                toml::Value::Boolean(v) => ret.push_str(&v.to_string()),
+                _ => {
+                    return Err(AppError::ConfigError(format!(
+                        "placeholder '${{{}}}' in '{}' does not refer to a scalar value",
+                        key, s,
+                    )))
+                }
+            }
+            rest = &after[end + 1..];
+        }
+        ret.push_str(rest);
+        Ok(ret)
+    }
+}
+
+/// Configures how items are grouped.
 #[derive(Default, Clone, Deserialize, Debug)]
 pub struct Group<T>
 where
@@ -560,6 +1548,10 @@ where
     /// Name of this group, used as namespace in staging root directory.
     pub name: GroupName,
 
+    /// (Optional) Human-readable description of this group, only shown when
+    /// listing groups and not used when syncing.
+    pub description: Option<String>,
+
     /// The priority of this group, used to resolve possibly duplicated
     /// items.  See [`DTScope`] for details.
     ///
@@ -636,14 +1628,18 @@ where
     /// Consider the following ignored setting:
     ///
     /// ```toml
-    /// ignored = [".git"]
+    /// ignored = [".git", "*.swp"]
     /// ```
     ///
     /// With this setting, all files or directories with their basename as
-    /// ".git" will be skipped.
+    /// ".git", or ending with ".swp" will be skipped.
+    ///
+    /// Patterns are globs matched against basenames, and cannot contain
+    /// slash.  Patterns from [`global.ignore_patterns`] are prepended to
+    /// them.
     ///
-    /// Cannot contain slash in any of the patterns.
-    pub ignored: Option<RenamingRules>,
+    /// [`global.ignore_patterns`]: GlobalConfig::ignore_patterns
+    pub ignored: Option<Vec<String>>,
 
     /// (Optional) Separator for per-host settings, default to `@@`.
     ///
@@ -729,6 +1725,113 @@ where
     /// [`global.rename`]: GlobalConfig::rename
     #[serde(default)]
     pub rename: RenamingRules,
+
+    /// (Optional) Number of leading components to strip from an item's path
+    /// relative to [`base`] when composing its target path, like `tar
+    /// --strip-components`.  The file name of an item is always kept.
+    /// Renaming rules are applied after stripping.  Default to `0`.
+    ///
+    /// [`base`]: Group::base
+    #[serde(default)]
+    pub strip_components: usize,
+
+    /// (Optional) Maximum depth to descend into directories matched by
+    /// [`sources`], relative to the matched directory.  A depth of `0`
+    /// means only the matched item itself, `1` means its direct children,
+    /// etc..  Directories are descended without limit when this is not set.
+    ///
+    /// [`sources`]: Group::sources
+    pub max_depth: Option<usize>,
+
+    /// (Optional) Name of this group's staging directory under the
+    /// [`staging`] root, used verbatim instead of the group [`name`] padded
+    /// with [`subgroup_prefix`]es.  Like a group name, it should neither
+    /// contain relative components nor start with a slash.
+    ///
+    /// [`staging`]: GlobalConfig::staging
+    /// [`name`]: Group::name
+    /// [`subgroup_prefix`]: Group::subgroup_prefix
+    pub staging_name: Option<PathBuf>,
+
+    /// (Optional) Whether the templating values used by this group are
+    /// sensitive (e.g. API tokens).  When set to `true`, the templating
+    /// context is redacted from log messages of this group.  Default to
+    /// `false`.
+    #[serde(default)]
+    pub sensitive: bool,
+
+    /// (Optional) Whether to only show changes to be made for this group
+    /// without actually syncing its items, even when other groups are
+    /// synced.  Note that this can not force a group to be synced during a
+    /// dry run.  Default to `false`.
+    #[serde(default)]
+    pub dry_run: bool,
+
+    /// (Optional) Whether to descend into symlinked directories when
+    /// expanding [`sources`].  When set to `false`, symlinked directories
+    /// are treated as leaf items, and the links themselves are reproduced
+    /// at the target.  Default to `true`.
+    ///
+    /// [`sources`]: Group::sources
+    pub follow_symlinks: Option<bool>,
+
+    /// (Optional) Whether this group is optional, i.e. whether to skip it
+    /// with a warning when its [`base`] does not exist, e.g. when the base
+    /// only exists on some machines.  Default to `false`.
+    ///
+    /// [`base`]: Group::base
+    #[serde(default)]
+    pub optional: bool,
+
+    /// (Optional) Owner of synced items, either a username or a uid.  Synced
+    /// items keep the owner of the syncing process when this is not set.
+    pub owner: Option<String>,
+
+    /// (Optional) Group of synced items, either a group name or a gid.
+    /// Synced items keep the group of the syncing process when this is not
+    /// set.
+    pub group: Option<String>,
+
+    /// (Optional) Tags of this group, used for selecting groups with
+    /// [`DTConfig::filter_tags`].
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// (Optional) Whether to read back items synced with the [`Copy`]
+    /// method and compare them against the intended contents, erroring when
+    /// they differ.  Default to `false`.
+    ///
+    /// [`Copy`]: SyncMethod::Copy
+    #[serde(default)]
+    pub verify: bool,
+
+    /// (Optional) Whether to rewrite the contents of an existing target in
+    /// place (truncate and write) when syncing with the [`Copy`] method,
+    /// instead of replacing the target file.  This keeps the target's inode,
+    /// and with it any hardlinks or ACLs of the target.  Targets that do not
+    /// exist yet are created as usual.  Default to `false`.
+    ///
+    /// [`Copy`]: SyncMethod::Copy
+    #[serde(default)]
+    pub in_place: bool,
+
+    /// (Optional) Whether to allow [`base`] and [`target`] to be the same
+    /// directory, in which case renderable sources are rendered in place,
+    /// i.e. each source is read, rendered, and written back to itself.
+    /// Only works with the [`Copy`] method.  Default to `false`, where such
+    /// a group is denied.
+    ///
+    /// [`base`]: Group::base
+    /// [`target`]: Group::target
+    /// [`Copy`]: SyncMethod::Copy
+    #[serde(default)]
+    pub allow_same_base_target: bool,
+
+    /// (Optional) Permission bits to mask out from the mode of this group's
+    /// items, overrides [`global.umask`].
+    ///
+    /// [`global.umask`]: GlobalConfig::umask
+    pub umask: Option<u32>,
 }
 
 impl<T> Group<T>
@@ -758,14 +1861,66 @@ where
     }
 
     /// Gets the absolute path to this group's staging directory, with the
-    /// subgroup components padded with configured [`subgroup_prefix`]es.
+    /// subgroup components padded with configured [`subgroup_prefix`]es, or
+    /// named after [`staging_name`] if it is set.
     ///
     /// [`subgroup_prefix`]: Group::subgroup_prefix
+    /// [`staging_name`]: Group::staging_name
     pub fn get_staging_dir(&self) -> PathBuf {
-        self.global
-            .staging
-            .0
-            .join(self.name.with_subgroup_prefix(&self.get_subgroup_prefix()))
+        match &self.staging_name {
+            Some(staging_name) => self.global.staging.0.join(staging_name),
+            None => self
+                .global
+                .staging
+                .0
+                .join(self.name.with_subgroup_prefix(&self.get_subgroup_prefix())),
+        }
+    }
+
+    /// Gets the [`follow_symlinks`] key from a `Group` object, defaults to
+    /// `true`.
+    ///
+    /// [`follow_symlinks`]: Group::follow_symlinks
+    pub fn is_symlink_followed(&self) -> bool {
+        self.follow_symlinks.unwrap_or(true)
+    }
+
+    /// Resolves the [`owner`] key from a `Group` object to a uid.
+    ///
+    /// [`owner`]: Group::owner
+    pub fn get_owner_uid(&self) -> Result<Option<u32>> {
+        match &self.owner {
+            Some(owner) => match owner.parse() {
+                Ok(uid) => Ok(Some(uid)),
+                Err(_) => match users::get_user_by_name(owner) {
+                    Some(user) => Ok(Some(user.uid())),
+                    None => Err(AppError::ConfigError(format!(
+                        "user '{}' does not exist in group '{}'",
+                        owner, self.name,
+                    ))),
+                },
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Resolves the [`group`] key from a `Group` object to a gid.
+    ///
+    /// [`group`]: Group::group
+    pub fn get_group_gid(&self) -> Result<Option<u32>> {
+        match &self.group {
+            Some(group) => match group.parse() {
+                Ok(gid) => Ok(Some(gid)),
+                Err(_) => match users::get_group_by_name(group) {
+                    Some(group) => Ok(Some(group.gid())),
+                    None => Err(AppError::ConfigError(format!(
+                        "group '{}' does not exist in group '{}'",
+                        group, self.name,
+                    ))),
+                },
+            },
+            None => Ok(None),
+        }
     }
 
     /// Gets the [`method`] key from a `Group` object, falls back to the
@@ -801,6 +1956,17 @@ where
         }
     }
 
+    /// Gets the [`umask`] key from a `Group` object, falls back to the
+    /// `umask` from its parent global config.
+    ///
+    /// [`umask`]: Group::umask
+    pub fn get_umask(&self) -> u32 {
+        match self.umask {
+            Some(umask) => umask,
+            _ => self.global.umask,
+        }
+    }
+
     /// Gets the list of [renaming rules] of this group, which is an array
     /// of (REGEX, SUBSTITUTION) tuples composed of [`global.rename`] and
     /// [`group.rename`], used in [`Operate::make_target`] to rename the item.
@@ -822,6 +1988,25 @@ where
         ret
     }
 
+    /// Gets the ignored names of this group, i.e. patterns from
+    /// [`global.ignore_patterns`] followed by the ones from
+    /// [`group.ignored`].
+    ///
+    /// [`global.ignore_patterns`]: GlobalConfig::ignore_patterns
+    /// [`group.ignored`]: Group::ignored
+    pub fn get_ignore_patterns(&self) -> Vec<String> {
+        let mut ret: Vec<String> = Vec::new();
+        for p in &self.global.ignore_patterns {
+            ret.push(p.to_owned());
+        }
+        if let Some(ignored) = &self.ignored {
+            for p in ignored {
+                ret.push(p.to_owned());
+            }
+        }
+        ret
+    }
+
     /// Check if this group is renderable according to the cascaded config
     /// options.
     pub fn is_renderable(&self) -> bool {
@@ -839,13 +2024,19 @@ where
     ///      to the [`staging`] directory, and the structure under the
     ///      [`staging`] directory depends on their original relative path to
     ///      their [`base`])
-    ///   3. TODO: Current group contains unimplemented [`ignored`] field
+    ///   3. Ignored name (see [`get_ignore_patterns`]) containing slashes,
+    ///      or bad globbing pattern
+    ///   4. Invalid [`staging_name`]
+    ///   5. Empty [`hostname_sep`], or [`hostname_sep`] containing slashes
+    ///      or whitespaces
     ///
     /// NOTE: When [`base`] is empty, sources will be looked up in the cwd of
     /// the process.
     ///
-    /// [`ignored`]: Group::ignored
+    /// [`get_ignore_patterns`]: Group::get_ignore_patterns
     /// [`base`]: Group::base
+    /// [`staging_name`]: Group::staging_name
+    /// [`hostname_sep`]: Group::hostname_sep
     fn _validate_no_fs_query(&self) -> Result<()> {
         // 1. Invalid group name
         self.name.validate()?;
@@ -856,9 +2047,58 @@ where
                 self.name,
             )));
         }
-        // 3. Current group contains unimplemented `ignored` field
-        if self.ignored.is_some() {
-            todo!("`ignored` array works poorly and I decided to implement it in the future");
+        // 3. Invalid ignored name
+        for pattern in self.get_ignore_patterns() {
+            if pattern.contains('/') {
+                return Err(AppError::ConfigError(format!(
+                    "ignored name '{}' should not contain slash in group '{}'",
+                    pattern, self.name,
+                )));
+            }
+            glob::Pattern::new(&pattern)?;
+        }
+        // 4. Invalid staging name
+        if let Some(staging_name) = &self.staging_name {
+            if !staging_name
+                .components()
+                .any(|comp| matches!(comp, std::path::Component::Normal(_)))
+            {
+                return Err(AppError::ConfigError(format!(
+                    "staging name should not be empty in group '{}'",
+                    self.name,
+                )));
+            } else if staging_name
+                .components()
+                .any(|comp| comp.as_os_str().to_string_lossy() == "..")
+            {
+                return Err(AppError::ConfigError(format!(
+                    "staging name should not contain relative component in group '{}'",
+                    self.name,
+                )));
+            } else if staging_name.starts_with("/") {
+                return Err(AppError::ConfigError(format!(
+                    "staging name should not start with slash in group '{}'",
+                    self.name,
+                )));
+            }
+        }
+        // 5. Invalid hostname_sep
+        let hostname_sep = self.get_hostname_sep();
+        if hostname_sep.is_empty() {
+            return Err(AppError::ConfigError(format!(
+                "hostname_sep should not be empty in group '{}'",
+                self.name,
+            )));
+        } else if hostname_sep.contains(std::path::is_separator) {
+            return Err(AppError::ConfigError(format!(
+                "hostname_sep should not contain path separators in group '{}'",
+                self.name,
+            )));
+        } else if hostname_sep.contains(char::is_whitespace) {
+            return Err(AppError::ConfigError(format!(
+                "hostname_sep should not contain whitespaces in group '{}'",
+                self.name,
+            )));
         }
 
         Ok(())
@@ -927,18 +2167,21 @@ impl LocalGroup {
     ///
     /// - Checks without querying the filesystem
     ///
-    ///   1. Empty [group name]
+    ///   1. Invalid [group name]
     ///   2. Source item referencing parent (because items are first populated
     ///      to the [`staging`] directory, and the structure under the
     ///      [`staging`] directory depends on their original relative path to
     ///      their [`base`])
-    ///   3. Current group contains unimplemented [`ignored`] field
+    ///   3. Invalid [`ignored`] name
+    ///   4. Invalid [`staging_name`]
+    ///   5. Invalid [`hostname_sep`]
     ///
-    ///   4. Target and base are the same
-    ///   5. Base contains [`hostname_sep`]
-    ///   6. Source item is absolute (same reason as above)
-    ///   7. Source item contains bad globbing pattern
-    ///   8. Source item contains [`hostname_sep`]
+    ///   6. Target and base are the same, unless
+    ///      [`allow_same_base_target`] is set with the [`Copy`] method
+    ///   7. Base contains [`hostname_sep`]
+    ///   8. Source item is absolute (same reason as above)
+    ///   9. Source item contains bad globbing pattern
+    ///   10. Source item contains [`hostname_sep`]
     ///
     /// - Checks that need to query the filesystem
     ///
@@ -950,28 +2193,54 @@ impl LocalGroup {
     ///   4. Path to [`target`] contains readonly parent directory
     ///
     ///   5. Base is unreadable
+    ///   6. Unknown [`owner`] or [`group`] name, so that syncing does not
+    ///      fail partway through
     ///
     /// [group name]: LocalGroup::name
     /// [`base`]: LocalGroup::base
     /// [`target`]: LocalGroup::target
     /// [`staging`]: GlobalConfig::staging
     /// [`ignored`]: Group::ignored
+    /// [`staging_name`]: Group::staging_name
     /// [`hostname_sep`]: LocalGroup::hostname_sep
+    /// [`allow_same_base_target`]: LocalGroup::allow_same_base_target
+    /// [`owner`]: LocalGroup::owner
+    /// [`group`]: LocalGroup::group
+    /// [`Copy`]: SyncMethod::Copy
     /// [`Symlink`]: SyncMethod::Symlink
     pub fn validate(&self) -> Result<()> {
+        self.validate_no_fs()?;
+        self.validate_fs()?;
+
+        Ok(())
+    }
+
+    /// Validates this local group without querying the filesystem, see
+    /// [`validate`] for the denied cases.
+    ///
+    /// [`validate`]: LocalGroup::validate
+    pub fn validate_no_fs(&self) -> Result<()> {
         // - Checks without querying the filesystem --------------------------
-        // 1-4
+        // 1-5
         self._validate_no_fs_query()?;
 
-        // 5. Target and base are the same
+        // 6. Target and base are the same
         if self.base == self.target {
-            return Err(AppError::ConfigError(format!(
-                "base directory and its target are the same in group '{}'",
-                self.name,
-            )));
+            if !self.allow_same_base_target {
+                return Err(AppError::ConfigError(format!(
+                    "base directory and its target are the same in group '{}'",
+                    self.name,
+                )));
+            }
+            if self.get_method() != SyncMethod::Copy {
+                return Err(AppError::ConfigError(format!(
+                    "base directory and its target can only be the same with the `Copy` method in group '{}'",
+                    self.name,
+                )));
+            }
         }
 
-        // 6. Base contains hostname_sep
+        // 7. Base contains hostname_sep
         let hostname_sep = self.get_hostname_sep();
         if self.base.to_string_lossy().contains(&hostname_sep) {
             return Err(AppError::ConfigError(format!(
@@ -980,7 +2249,7 @@ impl LocalGroup {
             )));
         }
 
-        // 7. Source item is absolute
+        // 8. Source item is absolute
         if self
             .sources
             .iter()
@@ -992,7 +2261,7 @@ impl LocalGroup {
             )));
         }
 
-        // 8. Source item contains bad globbing pattern
+        // 9. Source item contains bad globbing pattern
         if self.sources.iter().any(|s| {
             s.to_str()
                 .unwrap()
@@ -1008,7 +2277,7 @@ impl LocalGroup {
             return Err(AppError::ConfigError("bad globbing pattern".to_owned()));
         }
 
-        // 9. Source item contains hostname_sep
+        // 10. Source item contains hostname_sep
         if self.sources.iter().any(|s| {
             let s = s.to_string_lossy();
             s.contains(&hostname_sep)
@@ -1019,6 +2288,14 @@ impl LocalGroup {
             )));
         }
 
+        Ok(())
+    }
+
+    /// Validates this local group via querying the filesystem, see
+    /// [`validate`] for the denied cases.
+    ///
+    /// [`validate`]: LocalGroup::validate
+    pub fn validate_fs(&self) -> Result<()> {
         // - Checks that need to query the filesystem ------------------------
         // 1-4
         self._validate_with_fs_query()?;
@@ -1032,8 +2309,25 @@ impl LocalGroup {
             }
         }
 
+        // 6. Unknown owner or group name
+        self.get_owner_uid()?;
+        self.get_group_gid()?;
+
         Ok(())
     }
+
+    /// Gets the [renaming rules] of given item in this group, i.e. the
+    /// rules from the item's [`Sidecar`] if it has any, or
+    /// [`get_renaming_rules`] otherwise.
+    ///
+    /// [renaming rules]: RenamingRule
+    /// [`get_renaming_rules`]: Group::get_renaming_rules
+    pub fn get_item_renaming_rules(&self, item: &Path) -> Result<Vec<RenamingRule>> {
+        Ok(match Sidecar::of(item)?.rename {
+            Some(RenamingRules(rules)) => rules,
+            None => self.get_renaming_rules(),
+        })
+    }
 }
 
 /// Configures how remote items are grouped.
@@ -1044,13 +2338,25 @@ impl RemoteGroup {
     ///
     /// - Checks without querying the filesystem
     ///
-    ///   1. Empty [group name]
-    ///   2. Empty [`target`]
-    ///   3. Source item referencing parent (because items are first populated
+    ///   1. Invalid [group name]
+    ///   2. Source item referencing parent (because items are first populated
     ///      to the [`staging`] directory, and the structure under the
     ///      [`staging`] directory depends on their original relative path to
     ///      their [`base`])
-    ///   4. Current group contains unimplemented [`ignored`] field
+    ///   3. Invalid [`ignored`] name
+    ///   4. Invalid [`staging_name`]
+    ///   5. Invalid [`hostname_sep`]
+    ///
+    ///   6. [`base`] cannot be a base URL (e.g. `mailto:` or `data:` URLs)
+    ///   7. Source item is not under [`base`], i.e. it does not share the
+    ///      scheme, host and port of [`base`], or its path is not below the
+    ///      path of [`base`]
+    ///   8. Using the [`SymlinkDirect`] method, since a remote item has no
+    ///      local file to link to.  With the [`Symlink`] method, a remote
+    ///      item is downloaded to the [`staging`] directory and linked from
+    ///      there, while with the [`Copy`] method it is written to
+    ///      [`target`] directly
+    ///   9. [`target`] is not absolute
     ///
     /// - Checks that need to query the filesystem
     ///
@@ -1066,52 +2372,274 @@ impl RemoteGroup {
     /// [`target`]: LocalGroup::target
     /// [`staging`]: GlobalConfig::staging
     /// [`ignored`]: Group::ignored
+    /// [`staging_name`]: Group::staging_name
+    /// [`hostname_sep`]: Group::hostname_sep
+    /// [`Copy`]: SyncMethod::Copy
     /// [`Symlink`]: SyncMethod::Symlink
-    fn validate(&self) -> Result<()> {
+    /// [`SymlinkDirect`]: SyncMethod::SymlinkDirect
+    fn validate_no_fs(&self) -> Result<()> {
         // - Checks without querying the filesystem --------------------------
         // 1-5
         self._validate_no_fs_query()?;
 
-        // - Checks that need to query the filesystem ------------------------
-        // 1-4
-        self._validate_with_fs_query()?;
+        // 6. Base cannot be a base URL
+        if self.base.cannot_be_a_base() {
+            return Err(AppError::ConfigError(format!(
+                "base '{}' is not a valid base URL in group '{}'",
+                self.base, self.name,
+            )));
+        }
+
+        // 7. Source item is not under base
+        if let Some(source) = self.sources.iter().find(|s| !is_under(&self.base, s)) {
+            return Err(AppError::ConfigError(format!(
+                "source '{}' is not under base '{}' in group '{}'",
+                source, self.base, self.name,
+            )));
+        }
+
+        // 8. Using the `SymlinkDirect` method
+        if self.get_method() == SyncMethod::SymlinkDirect {
+            return Err(AppError::ConfigError(format!(
+                "remote group '{}' cannot use the `SymlinkDirect` method",
+                self.name,
+            )));
+        }
+
+        // 9. Target is not absolute
+        if !self.target.is_absolute() {
+            return Err(AppError::ConfigError(format!(
+                "target '{}' is not absolute in group '{}'",
+                self.target.display(),
+                self.name,
+            )));
+        }
 
         Ok(())
     }
+
+    /// Validates this remote group via querying the filesystem, see
+    /// [`validate_no_fs`] for the denied cases.
+    ///
+    /// [`validate_no_fs`]: RemoteGroup::validate_no_fs
+    fn validate_fs(&self) -> Result<()> {
+        // - Checks that need to query the filesystem ------------------------
+        // 1-4
+        self._validate_with_fs_query()
+    }
+}
+
+/// Checks whether `url` shares the scheme, host and port of `base`, and its
+/// path is strictly below the path of `base`.
+fn is_under(base: &Url, url: &Url) -> bool {
+    let segments = |u: &Url| -> Vec<String> {
+        u.path_segments()
+            .map(|segs| segs.filter(|s| !s.is_empty()).map(str::to_owned).collect())
+            .unwrap_or_default()
+    };
+    let (base_segments, url_segments) = (segments(base), segments(url));
+    base.scheme() == url.scheme()
+        && base.host_str() == url.host_str()
+        && base.port_or_known_default() == url.port_or_known_default()
+        && url_segments.len() > base_segments.len()
+        && url_segments.starts_with(&base_segments)
 }
 
 #[cfg(test)]
-mod overriding_global {
+mod group_name {
     use std::str::FromStr;
 
-    use super::{DTConfig, SyncMethod};
     use color_eyre::Report;
     use pretty_assertions::assert_eq;
 
+    use super::{DTConfig, GroupName};
+    use crate::error::Error as AppError;
+
     #[test]
-    fn allow_overwrite_no_global() -> Result<(), Report> {
-        let config = DTConfig::from_str(
-            r#"
-[[local]]
-name = "placeholder"
-base = "~"
-sources = ["*"]
-target = "."
-allow_overwrite = true"#,
-        )?;
-        for group in config.local {
-            assert_eq!(group.is_overwrite_allowed(), true);
+    fn depth() {
+        assert_eq!(GroupName("a".into()).depth(), 1);
+        assert_eq!(GroupName("a/b".into()).depth(), 2);
+        assert_eq!(GroupName("a/b/c".into()).depth(), 3);
+        assert_eq!(GroupName("a/b/".into()).depth(), 2);
+    }
+
+    #[test]
+    fn hostile_characters() {
+        for name in ["vim\nrc", "vim\0rc", "vim\trc", "gui\\gtk", "gui:gtk"] {
+            assert!(
+                matches!(
+                    GroupName(name.into()).validate(),
+                    Err(AppError::ConfigError(msg)) if msg.contains("control or path-hostile"),
+                ),
+                "{:?} should be rejected",
+                name,
+            );
         }
-        Ok(())
+        assert!(GroupName("gui/gtk-3.0_#1".into()).validate().is_ok());
     }
 
     #[test]
-    fn allow_overwrite_with_global() -> Result<(), Report> {
+    fn subgroups() {
+        let ab = GroupName("a/b".into());
+        assert!(ab.is_subgroup_of(&GroupName("a".into())));
+        assert!(ab.is_subgroup_of(&ab));
+        assert!(GroupName("a/b/c".into()).is_subgroup_of(&ab));
+        assert!(!GroupName("a".into()).is_subgroup_of(&ab));
+        assert!(!GroupName("abc".into()).is_subgroup_of(&GroupName("a".into())));
+        assert!(!GroupName("abc".into()).is_subgroup_of(&ab));
+        assert!(!GroupName("a/bc".into()).is_subgroup_of(&ab));
+    }
+
+    #[test]
+    fn filter_names_hierarchically() -> Result<(), Report> {
         let config = DTConfig::from_str(
             r#"
-[global]
-method = "Copy"  # Default value because not testing this key
-allow_overwrite = true
+[[local]]
+name = "a/b"
+base = "~"
+sources = []
+target = "."
+
+[[local]]
+name = "a/b/c"
+base = "~"
+sources = []
+target = "."
+
+[[local]]
+name = "a/bcd"
+base = "~"
+sources = []
+target = "."
+
+[[local]]
+name = "abc"
+base = "~"
+sources = []
+target = ".""#,
+        )?;
+        let names = |config: DTConfig| -> Vec<String> {
+            config.local.iter().map(|l| l.name.to_string()).collect()
+        };
+        assert_eq!(
+            names(config.to_owned().filter_names(vec!["a/b".to_owned()])),
+            vec!["a/b", "a/b/c"],
+        );
+        assert_eq!(
+            names(config.filter_names(vec!["a".to_owned()])),
+            vec!["a/b", "a/b/c", "a/bcd"],
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tags {
+    use std::str::FromStr;
+
+    use color_eyre::Report;
+    use pretty_assertions::assert_eq;
+
+    use super::DTConfig;
+
+    #[test]
+    fn filter_tags() -> Result<(), Report> {
+        let config = DTConfig::from_str(
+            r#"
+[[local]]
+name = "gtk"
+base = "~"
+sources = []
+target = "."
+tags = ["gui"]
+
+[[local]]
+name = "ssh"
+base = "~"
+sources = []
+target = "."
+tags = ["work"]
+
+[[local]]
+name = "sway"
+base = "~"
+sources = []
+target = "."
+tags = ["gui", "work"]
+
+[[local]]
+name = "untagged"
+base = "~"
+sources = []
+target = ".""#,
+        )?;
+        let names = |config: DTConfig| -> Vec<String> {
+            config.local.iter().map(|l| l.name.to_string()).collect()
+        };
+        assert_eq!(
+            names(
+                config
+                    .to_owned()
+                    .filter_tags(vec!["gui".to_owned()], vec![])
+            ),
+            vec!["gtk", "sway"],
+        );
+        assert_eq!(
+            names(
+                config
+                    .to_owned()
+                    .filter_tags(vec!["gui".to_owned(), "work".to_owned()], vec![])
+            ),
+            vec!["gtk", "ssh", "sway"],
+        );
+        assert_eq!(
+            names(
+                config
+                    .to_owned()
+                    .filter_tags(vec!["gui".to_owned()], vec!["work".to_owned()])
+            ),
+            vec!["gtk"],
+        );
+        assert_eq!(
+            names(config.filter_tags(vec![], vec!["gui".to_owned()])),
+            vec!["ssh", "untagged"],
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod overriding_global {
+    use std::{path::PathBuf, str::FromStr};
+
+    use super::{DTConfig, SyncMethod};
+    use color_eyre::Report;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn allow_overwrite_no_global() -> Result<(), Report> {
+        let config = DTConfig::from_str(
+            r#"
+[[local]]
+name = "placeholder"
+base = "~"
+sources = ["*"]
+target = "."
+allow_overwrite = true"#,
+        )?;
+        for group in config.local {
+            assert_eq!(group.is_overwrite_allowed(), true);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn allow_overwrite_with_global() -> Result<(), Report> {
+        let config = DTConfig::from_str(
+            r#"
+[global]
+method = "Copy"  # Default value because not testing this key
+allow_overwrite = true
 
 [[local]]
 name = "placeholder"
@@ -1205,83 +2733,673 @@ target = ".""#,
     }
 
     #[test]
-    fn method_no_global() -> Result<(), Report> {
+    fn method_no_global() -> Result<(), Report> {
+        let config = DTConfig::from_str(
+            r#"
+[[local]]
+name = "placeholder"
+base = "~"
+sources = ["*"]
+target = "."
+method = "Copy""#,
+        )?;
+        for group in config.local {
+            assert_eq!(group.get_method(), SyncMethod::Copy)
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn method_with_global() -> Result<(), Report> {
+        let config = DTConfig::from_str(
+            r#"
+[global]
+method = "Copy"
+allow_overwrite = false # Default value because not testing this key
+
+[[local]]
+name = "placeholder"
+base = "~"
+sources = ["*"]
+target = "."
+method = "Symlink""#,
+        )?;
+        for group in config.local {
+            assert_eq!(group.get_method(), SyncMethod::Symlink)
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn staging_name_overrides_group_name() -> Result<(), Report> {
+        let config = DTConfig::from_str(
+            r##"
+[global]
+staging = "/staging"
+subgroup_prefix = "#"
+
+[[local]]
+name = "gui/gtk"
+base = "~"
+sources = []
+target = "."
+
+[[local]]
+name = "gui/gtk"
+base = "~"
+sources = []
+target = "."
+staging_name = "gtk-themes""##,
+        )?;
+        assert_eq!(
+            config.local[0].get_staging_dir(),
+            PathBuf::from_str("/staging/gui/#gtk")?,
+        );
+        assert_eq!(
+            config.local[1].get_staging_dir(),
+            PathBuf::from_str("/staging/gtk-themes")?,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod loading {
+    use std::io::Cursor;
+
+    use color_eyre::Report;
+    use pretty_assertions::assert_eq;
+
+    use super::{DTConfig, SyncMethod};
+
+    #[test]
+    fn from_reader() -> Result<(), Report> {
+        let config = DTConfig::from_reader(Cursor::new(
+            r#"
+[global]
+method = "Copy"
+
+[[local]]
+name = "loaded from a reader"
+base = "~"
+sources = []
+target = ".""#,
+        ))?;
+        assert_eq!(config.global.method, SyncMethod::Copy);
+        assert_eq!(config.local.len(), 1);
+        assert_eq!(config.local[0].name.to_string(), "loaded from a reader");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod templated {
+    use std::str::FromStr;
+
+    use color_eyre::Report;
+    use pretty_assertions::assert_eq;
+
+    use super::DTConfig;
+    use crate::error::Error as AppError;
+
+    #[test]
+    fn marker() -> Result<(), Report> {
+        std::env::set_var("DT_TEMPLATED_CONFIG_TEST", "nvim");
+        let config = DTConfig::from_str(
+            r#"# dt:template
+[[local]]
+name = "{{get_mine}}"
+base = "/tmp/{{env "DT_TEMPLATED_CONFIG_TEST"}}"
+sources = []
+target = "/tmp/{{#if_host "r2d2"}}droid{{else}}human{{/if_host}}""#,
+        )?;
+        assert_eq!(config.local[0].name.to_string(), "r2d2");
+        assert_eq!(config.local[0].base.to_string_lossy(), "/tmp/nvim");
+        assert_eq!(config.local[0].target.to_string_lossy(), "/tmp/droid");
+        Ok(())
+    }
+
+    #[test]
+    fn without_marker() -> Result<(), Report> {
+        let config = DTConfig::from_str_templated(
+            r#"
+[[local]]
+name = "{{get_mine}}"
+base = "/tmp"
+sources = []
+target = "/tmp/target""#,
+        )?;
+        assert_eq!(config.local[0].name.to_string(), "r2d2");
+        Ok(())
+    }
+
+    #[test]
+    fn unresolved() {
+        for template in [
+            r#"name = "{{nothing}}""#,
+            r#"name = "{{if_cmd_succeeds "true"}}""#,
+        ] {
+            let err = DTConfig::from_str(&format!("# dt:template\n{}", template)).unwrap_err();
+            assert!(matches!(err, AppError::ConfigError(_)), "{}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod isolate_staging {
+    use std::str::FromStr;
+
+    use color_eyre::Report;
+    use pretty_assertions::assert_eq;
+
+    use super::DTConfig;
+    use crate::utils::testing::get_testroot;
+
+    fn staging_root(isolate: bool, target: &str) -> Result<std::path::PathBuf, Report> {
+        let config = DTConfig::from_str(&format!(
+            r#"
+[global]
+staging = "/tmp/staging"
+isolate_staging = {}
+
+[[local]]
+name = "nvim"
+base = "/tmp"
+sources = []
+target = "{}""#,
+            isolate, target,
+        ))?;
+        Ok(config.global.staging.0)
+    }
+
+    #[test]
+    fn different_configs() -> Result<(), Report> {
+        let one = staging_root(true, "/tmp/one")?;
+        let another = staging_root(true, "/tmp/another")?;
+        assert_ne!(one, another);
+        assert_eq!(one.parent(), Some("/tmp/staging".as_ref()));
+        assert_eq!(another.parent(), Some("/tmp/staging".as_ref()));
+        assert_eq!(staging_root(true, "/tmp/one")?, one);
+        Ok(())
+    }
+
+    #[test]
+    fn disabled() -> Result<(), Report> {
+        assert_eq!(
+            staging_root(false, "/tmp/one")?,
+            staging_root(false, "/tmp/another")?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn by_config_path() -> Result<(), Report> {
+        let root = get_testroot("isolate_staging").join("by_config_path");
+        let write = |name: &str, target: &str| -> Result<std::path::PathBuf, Report> {
+            let path = root.join(name);
+            std::fs::create_dir_all(&root)?;
+            std::fs::write(
+                &path,
+                format!(
+                    r#"
+[global]
+staging = "/tmp/staging"
+isolate_staging = true
+
+[[local]]
+name = "nvim"
+base = "/tmp"
+sources = []
+target = "{}""#,
+                    target,
+                ),
+            )?;
+            Ok(path)
+        };
+
+        let one = write("one.toml", "/tmp/one")?;
+        let staging = DTConfig::from_path(&one)?.global.staging.0;
+        // Editing a config keeps its staging root
+        write("one.toml", "/tmp/edited")?;
+        assert_eq!(DTConfig::from_path(&one)?.global.staging.0, staging);
+        assert_eq!(
+            DTConfig::from_path_templated(&one)?.global.staging.0,
+            staging,
+        );
+        // Another config file with the same contents stages elsewhere
+        let another = write("another.toml", "/tmp/edited")?;
+        assert_ne!(DTConfig::from_path(another)?.global.staging.0, staging);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod singular_group {
+    use std::str::FromStr;
+
+    use color_eyre::Report;
+    use pretty_assertions::assert_eq;
+
+    use super::DTConfig;
+    use crate::error::Error as AppError;
+
+    #[test]
+    fn equivalent_to_local() -> Result<(), Report> {
+        let singular = DTConfig::from_str(
+            r#"
+[group]
+name = "nvim"
+base = "/tmp/dotfiles/nvim"
+sources = ["init.lua"]
+target = "/tmp/.config/nvim"
+method = "Copy""#,
+        )?;
+        let array = DTConfig::from_str(
+            r#"
+[[local]]
+name = "nvim"
+base = "/tmp/dotfiles/nvim"
+sources = ["init.lua"]
+target = "/tmp/.config/nvim"
+method = "Copy""#,
+        )?;
+        assert_eq!(singular.local.len(), 1);
+        assert_eq!(
+            format!("{:?}", singular.local),
+            format!("{:?}", array.local),
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn mixed_with_local() {
+        assert_eq!(
+            DTConfig::from_str(
+                r#"
+[group]
+name = "nvim"
+base = "/tmp/dotfiles/nvim"
+sources = ["init.lua"]
+target = "/tmp/.config/nvim"
+
+[[local]]
+name = "gtk"
+base = "/tmp/dotfiles/gtk"
+sources = []
+target = "/tmp/.config/gtk-3.0""#,
+            )
+            .err(),
+            Some(AppError::ConfigError(
+                "`group` and `local` should not be both defined".to_owned()
+            )),
+        );
+    }
+}
+
+#[cfg(test)]
+mod group_names {
+    use std::str::FromStr;
+
+    use color_eyre::Report;
+    use pretty_assertions::assert_eq;
+
+    use super::DTConfig;
+
+    #[test]
+    fn local_and_remote() -> Result<(), Report> {
+        let config = DTConfig::from_str(
+            r#"
+[[local]]
+name = "shell/zsh"
+base = "~/dt/zsh"
+sources = []
+target = "~"
+
+[[local]]
+name = "editor"
+base = "~/dt/nvim"
+sources = []
+target = "~/.config/nvim"
+
+[[remote]]
+name = "wallpapers"
+base = "https://example.com/wallpapers"
+sources = []
+target = "~/Pictures""#,
+        )?;
+        assert_eq!(config.group_names(false), vec!["shell/zsh", "editor"]);
+        assert_eq!(
+            config.group_names(true),
+            vec!["shell/zsh", "editor", "wallpapers"],
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn empty() {
+        assert!(DTConfig::default().group_names(true).is_empty());
+    }
+
+    #[test]
+    fn descriptions() -> Result<(), Report> {
+        let config = DTConfig::from_str(
+            r#"
+[[local]]
+name = "shell/zsh"
+description = "Interactive shell, with plugins"
+base = "~/dt/zsh"
+sources = []
+target = "~"
+
+[[remote]]
+name = "wallpapers"
+base = "https://example.com/wallpapers"
+sources = []
+target = "~/Pictures""#,
+        )?;
+        assert_eq!(
+            config.local[0].description.as_deref(),
+            Some("Interactive shell, with plugins"),
+        );
+        assert_eq!(config.remote[0].description, None);
+        assert_eq!(
+            config.list_groups(),
+            vec![
+                (
+                    "shell/zsh".to_owned(),
+                    "Interactive shell, with plugins".to_owned()
+                ),
+                ("wallpapers".to_owned(), "".to_owned()),
+            ],
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod dump_context {
+    use std::str::FromStr;
+
+    use color_eyre::Report;
+    use pretty_assertions::assert_eq;
+
+    use super::{DTConfig, DumpFormat};
+    use crate::error::Error as AppError;
+
+    const CONFIG: &str = r##"
+[context]
+editor.name = "nvim"
+
+[context.gui]
+font = "Sarasa Mono SC"
+
+[context.gui.colors]
+fg = "#c0caf5"
+"##;
+
+    #[test]
+    fn merged_tables() -> Result<(), Report> {
+        let config = DTConfig::from_str(CONFIG)?;
+        let json: serde_json::Value =
+            serde_json::from_str(&config.context.dump(DumpFormat::Json)?)?;
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "editor": { "name": "nvim" },
+                "gui": {
+                    "font": "Sarasa Mono SC",
+                    "colors": { "fg": "#c0caf5" },
+                },
+            }),
+        );
+
+        let toml: toml::Value = toml::from_str(&config.context.dump(DumpFormat::Toml)?)?;
+        assert_eq!(toml["gui"]["colors"]["fg"].as_str(), Some("#c0caf5"));
+        assert_eq!(toml["editor"]["name"].as_str(), Some("nvim"));
+        Ok(())
+    }
+
+    #[test]
+    fn format_from_str() {
+        assert_eq!(DumpFormat::from_str("json"), Ok(DumpFormat::Json));
+        assert_eq!(DumpFormat::from_str("toml"), Ok(DumpFormat::Toml));
+        assert_eq!(
+            DumpFormat::from_str("yaml"),
+            Err(AppError::ParseError(
+                "unknown dump format 'yaml', expected one of `json`, `toml`".to_owned()
+            )),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tilde_expansion {
+    use std::str::FromStr;
+
+    use color_eyre::Report;
+    use pretty_assertions::assert_eq;
+
+    use super::DTConfig;
+
+    #[test]
+    fn all() -> Result<(), Report> {
+        let config = DTConfig::from_str(
+            r#"
+[global]
+staging = "~"
+method = "Symlink"
+allow_overwrite = false
+
+
+[[local]]
+name = "expand tilde in base and target"
+base = "~"
+sources = []
+target = "~/dt/target""#,
+        )?;
+        dbg!(&config.global.staging.0);
+        assert_eq!(Some(config.global.staging.0), dirs::home_dir());
+        config.local.iter().all(|group| {
+            dbg!(&group.base);
+            dbg!(&group.target);
+            assert_eq!(Some(group.to_owned().base), dirs::home_dir());
+            assert_eq!(
+                Some(group.to_owned().target),
+                dirs::home_dir()
+                    .map(|p| p.join("dt"))
+                    .map(|p| p.join("target")),
+            );
+            true
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod ownership {
+    use std::str::FromStr;
+
+    use color_eyre::{eyre::eyre, Report};
+    use pretty_assertions::assert_eq;
+
+    use super::DTConfig;
+    use crate::error::Error as AppError;
+
+    #[test]
+    fn resolve_names_and_ids() -> Result<(), Report> {
+        let config = DTConfig::from_str(
+            r#"
+[[local]]
+name = "by name"
+base = "/tmp"
+sources = []
+target = "/tmp/by-name"
+owner = "root"
+group = "root"
+
+[[local]]
+name = "by id"
+base = "/tmp"
+sources = []
+target = "/tmp/by-id"
+owner = "1000"
+group = "100"
+
+[[local]]
+name = "unset"
+base = "/tmp"
+sources = []
+target = "/tmp/unset""#,
+        )?;
+        assert_eq!(config.local[0].get_owner_uid()?, Some(0));
+        assert_eq!(config.local[0].get_group_gid()?, Some(0));
+        assert_eq!(config.local[1].get_owner_uid()?, Some(1000));
+        assert_eq!(config.local[1].get_group_gid()?, Some(100));
+        assert_eq!(config.local[2].get_owner_uid()?, None);
+        assert_eq!(config.local[2].get_group_gid()?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_names() -> Result<(), Report> {
+        for (key, expected) in [
+            (
+                r#"owner = "no-such-user-dt""#,
+                "user 'no-such-user-dt' does not exist in group 'unknown'",
+            ),
+            (
+                r#"group = "no-such-group-dt""#,
+                "group 'no-such-group-dt' does not exist in group 'unknown'",
+            ),
+        ] {
+            if let Err(err) = DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "unknown"
+base = "/tmp"
+sources = []
+target = "/tmp/unknown"
+{}"#,
+                key,
+            )) {
+                assert_eq!(err, AppError::ConfigError(expected.to_owned()));
+            } else {
+                return Err(eyre!(
+                    "This config should not be loaded because an owner or group name is unknown"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod context_getter {
+    use std::str::FromStr;
+
+    use color_eyre::Report;
+    use pretty_assertions::assert_eq;
+
+    use super::DTConfig;
+
+    #[test]
+    fn nested_keys() -> Result<(), Report> {
         let config = DTConfig::from_str(
             r#"
-[[local]]
-name = "placeholder"
-base = "~"
-sources = ["*"]
-target = "."
-method = "Copy""#,
+[context]
+user = "luke"
+gui.font.name = "Sarasa Mono SC"
+gui.font.size = 12
+"#,
         )?;
-        for group in config.local {
-            assert_eq!(group.get_method(), SyncMethod::Copy)
-        }
+        let context = &config.context;
+        assert_eq!(context.get("user").and_then(|v| v.as_str()), Some("luke"));
+        assert_eq!(
+            context.get("gui.font.name").and_then(|v| v.as_str()),
+            Some("Sarasa Mono SC"),
+        );
+        assert_eq!(
+            context.get("gui.font.size").and_then(|v| v.as_integer()),
+            Some(12),
+        );
+        assert!(context.get("gui.font").unwrap().is_table());
+        assert_eq!(context.get("gui"), context.as_value().get("gui"),);
         Ok(())
     }
 
     #[test]
-    fn method_with_global() -> Result<(), Report> {
+    fn missing_keys() -> Result<(), Report> {
         let config = DTConfig::from_str(
             r#"
-[global]
-method = "Copy"
-allow_overwrite = false # Default value because not testing this key
-
-[[local]]
-name = "placeholder"
-base = "~"
-sources = ["*"]
-target = "."
-method = "Symlink""#,
+[context]
+gui.font = "Sarasa Mono SC"
+"#,
         )?;
-        for group in config.local {
-            assert_eq!(group.get_method(), SyncMethod::Symlink)
-        }
+        assert!(config.context.get("gui.dpi").is_none());
+        assert!(config.context.get("gui.font.name").is_none());
+        assert!(config.context.get("").is_none());
         Ok(())
     }
 }
 
 #[cfg(test)]
-mod tilde_expansion {
+mod placeholders {
     use std::str::FromStr;
 
-    use color_eyre::Report;
+    use color_eyre::{eyre::eyre, Report};
     use pretty_assertions::assert_eq;
 
     use super::DTConfig;
+    use crate::error::Error as AppError;
 
     #[test]
-    fn all() -> Result<(), Report> {
+    fn target_and_name() -> Result<(), Report> {
         let config = DTConfig::from_str(
             r#"
-[global]
-staging = "~"
-method = "Symlink"
-allow_overwrite = false
-
+[context]
+home = "/tmp/luke"
+editor.name = "nvim"
 
 [[local]]
-name = "expand tilde in base and target"
-base = "~"
+name = "${editor.name}/plugins"
+base = "/tmp"
 sources = []
-target = "~/dt/target""#,
+target = "${home}/.config/${editor.name}""#,
         )?;
-        dbg!(&config.global.staging.0);
-        assert_eq!(Some(config.global.staging.0), dirs::home_dir());
-        config.local.iter().all(|group| {
-            dbg!(&group.base);
-            dbg!(&group.target);
-            assert_eq!(Some(group.to_owned().base), dirs::home_dir());
+        assert_eq!(config.local[0].name.to_string(), "nvim/plugins");
+        assert_eq!(
+            config.local[0].target.to_string_lossy(),
+            "/tmp/luke/.config/nvim",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unresolved() -> Result<(), Report> {
+        if let Err(err) = DTConfig::from_str(
+            r#"
+[context]
+home = "/tmp/luke"
+
+[[local]]
+name = "placeholder"
+base = "/tmp"
+sources = []
+target = "${home}/.config/${editor}""#,
+        ) {
             assert_eq!(
-                Some(group.to_owned().target),
-                dirs::home_dir()
-                    .map(|p| p.join("dt"))
-                    .map(|p| p.join("target")),
+                err,
+                AppError::ConfigError(
+                    "unresolved placeholder '${editor}' in '${home}/.config/${editor}'".to_owned()
+                ),
+                "{}",
+                err,
             );
-            true
-        });
+        } else {
+            return Err(eyre!(
+                "This config should not be loaded because a placeholder is unresolved"
+            ));
+        }
         Ok(())
     }
 }
@@ -1344,6 +3462,171 @@ target = ".""#,
         }
     }
 
+    #[test]
+    fn invalid_staging_name() -> Result<(), Report> {
+        for (staging_name, expected) in [
+            ("", "staging name should not be empty in group 'staging'"),
+            (".", "staging name should not be empty in group 'staging'"),
+            (
+                "a/../b",
+                "staging name should not contain relative component in group 'staging'",
+            ),
+            (
+                "/a/b",
+                "staging name should not start with slash in group 'staging'",
+            ),
+        ] {
+            if let Err(err) = DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "staging"
+base = "~"
+sources = []
+target = "."
+staging_name = "{}""#,
+                staging_name,
+            )) {
+                assert_eq!(err, AppError::ConfigError(expected.to_owned()), "{}", err);
+            } else {
+                return Err(eyre!(
+                    "This config should not be loaded because a group's staging name is invalid"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn shared_staging_name() -> Result<(), Report> {
+        for (first, second) in [
+            (r#"staging_name = "shared""#, r#"staging_name = "shared""#),
+            ("", r#"staging_name = "first""#),
+        ] {
+            if let Err(err) = DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "first"
+base = "~"
+sources = []
+target = "."
+{}
+
+[[local]]
+name = "second"
+base = "~"
+sources = []
+target = "."
+{}"#,
+                first, second,
+            )) {
+                assert!(
+                    matches!(&err, AppError::ConfigError(msg)
+                        if msg.starts_with("groups 'first' and 'second' share the same staging directory")),
+                    "{}",
+                    err,
+                );
+            } else {
+                return Err(eyre!(
+                    "This config should not be loaded because two groups share a staging directory"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_hostname_sep() -> Result<(), Report> {
+        for (hostname_sep, expected) in [
+            ("", "hostname_sep should not be empty in group 'host'"),
+            (
+                "@/@",
+                "hostname_sep should not contain path separators in group 'host'",
+            ),
+            (
+                "@ @",
+                "hostname_sep should not contain whitespaces in group 'host'",
+            ),
+        ] {
+            if let Err(err) = DTConfig::from_str(&format!(
+                r#"
+[global]
+hostname_sep = "{}"
+
+[[local]]
+name = "host"
+base = "~"
+sources = []
+target = ".""#,
+                hostname_sep,
+            )) {
+                assert_eq!(err, AppError::ConfigError(expected.to_owned()), "{}", err);
+            } else {
+                return Err(eyre!(
+                    "This config should not be loaded because hostname_sep is invalid"
+                ));
+            }
+        }
+        if let Err(err) = DTConfig::from_str(
+            r#"
+[[local]]
+name = "host"
+base = "~"
+sources = []
+target = "."
+hostname_sep = """#,
+        ) {
+            assert_eq!(
+                err,
+                AppError::ConfigError(
+                    "hostname_sep should not be empty in group 'host'".to_owned()
+                ),
+                "{}",
+                err,
+            );
+            Ok(())
+        } else {
+            Err(eyre!(
+                "This config should not be loaded because a group's hostname_sep is empty"
+            ))
+        }
+    }
+
+    #[test]
+    fn required_context() -> Result<(), Report> {
+        DTConfig::from_str(
+            r#"
+[global]
+required_context = ["gui.font", "editor"]
+
+[context]
+gui.font = "Iosevka"
+editor = "nvim""#,
+        )?;
+
+        if let Err(err) = DTConfig::from_str(
+            r#"
+[global]
+required_context = ["gui.font", "gui.dpi", "editor"]
+
+[context]
+gui.font = "Iosevka""#,
+        ) {
+            assert_eq!(
+                err,
+                AppError::ConfigError(
+                    "required context key(s) missing: gui.dpi, editor".to_owned()
+                ),
+                "{}",
+                err,
+            );
+            Ok(())
+        } else {
+            Err(eyre!(
+                "This config should not be loaded because required context keys are missing"
+            ))
+        }
+    }
+
     #[test]
     fn empty_group_name() -> Result<(), Report> {
         if let Err(err) = DTConfig::from_str(
@@ -1395,6 +3678,32 @@ target = "~""#,
         }
     }
 
+    #[test]
+    fn same_base_target_requires_copy() -> Result<(), Report> {
+        let config = |method: &str| {
+            DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "render in place"
+base = "~"
+sources = []
+target = "~"
+method = "{}"
+allow_same_base_target = true"#,
+                method,
+            ))
+        };
+        assert!(config("Copy").is_ok());
+        assert_eq!(
+            config("Symlink").unwrap_err(),
+            AppError::ConfigError(
+                "base directory and its target can only be the same with the `Copy` method in group 'render in place'"
+                    .to_owned(),
+            ),
+        );
+        Ok(())
+    }
+
     #[test]
     fn base_contains_hostname_sep() -> Result<(), Report> {
         if let Err(err) = DTConfig::from_str(
@@ -1525,6 +3834,134 @@ target = ".""#,
             ))
         }
     }
+
+    #[test]
+    fn remote_group() -> Result<(), Report> {
+        let config = DTConfig::from_str(
+            r#"
+[[remote]]
+name = "wallpapers"
+base = "https://example.com/wallpapers/"
+sources = ["https://example.com/wallpapers/forest.png"]
+target = "~/Pictures""#,
+        )?;
+        assert_eq!(config.remote[0].sources.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn remote_source_malformed_url() {
+        let err = DTConfig::from_str(
+            r#"
+[[remote]]
+name = "wallpapers"
+base = "https://example.com/wallpapers/"
+sources = ["https://[example.com/forest.png"]
+target = "~/Pictures""#,
+        )
+        .unwrap_err();
+        assert!(matches!(err, AppError::ParseError(_)), "{}", err);
+    }
+
+    #[test]
+    fn remote_source_not_under_base() -> Result<(), Report> {
+        if let Err(err) = DTConfig::from_str(
+            r#"
+[[remote]]
+name = "wallpapers"
+base = "https://example.com/wallpapers"
+sources = ["https://example.com/wallpapers/../secrets.txt"]
+target = "~/Pictures""#,
+        ) {
+            assert_eq!(
+                err,
+                AppError::ConfigError(
+                    "source 'https://example.com/secrets.txt' is not under base 'https://example.com/wallpapers' in group 'wallpapers'"
+                        .to_owned()
+                ),
+                "{}",
+                err,
+            );
+            Ok(())
+        } else {
+            Err(eyre!(
+                "This config should not be loaded because a remote source is not under its base"
+            ))
+        }
+    }
+
+    #[test]
+    fn remote_symlink_direct() -> Result<(), Report> {
+        if let Err(err) = DTConfig::from_str(
+            r#"
+[[remote]]
+name = "wallpapers"
+base = "https://example.com/wallpapers"
+sources = []
+target = "~/Pictures"
+method = "SymlinkDirect""#,
+        ) {
+            assert_eq!(
+                err,
+                AppError::ConfigError(
+                    "remote group 'wallpapers' cannot use the `SymlinkDirect` method".to_owned()
+                ),
+                "{}",
+                err,
+            );
+            Ok(())
+        } else {
+            Err(eyre!(
+                "This config should not be loaded because a remote group uses SymlinkDirect"
+            ))
+        }
+    }
+
+    #[test]
+    fn remote_relative_target() -> Result<(), Report> {
+        let config = DTConfig::from_str(
+            r#"
+[[remote]]
+name = "wallpapers"
+base = "https://example.com/wallpapers/"
+sources = ["https://example.com/wallpapers/forest.png"]
+target = "Pictures""#,
+        )?;
+        assert_eq!(
+            config.remote[0].target,
+            std::env::current_dir()?.join("Pictures"),
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn remote_target_not_absolute() -> Result<(), Report> {
+        let mut config = DTConfig::from_str(
+            r#"
+[[remote]]
+name = "wallpapers"
+base = "https://example.com/wallpapers/"
+sources = []
+target = "~/Pictures""#,
+        )?;
+        // Loading would have made it absolute
+        config.remote[0].target = "Pictures".into();
+        if let Err(err) = config.with_global(|_| {}) {
+            assert_eq!(
+                err,
+                AppError::ConfigError(
+                    "target 'Pictures' is not absolute in group 'wallpapers'".to_owned()
+                ),
+                "{}",
+                err,
+            );
+            Ok(())
+        } else {
+            Err(eyre!(
+                "This config should not be validated because a remote target is not absolute"
+            ))
+        }
+    }
 }
 
 #[cfg(test)]
@@ -17,6 +17,95 @@ pub enum Error {
     SyncingError(String),
     /// Errors that occur while registering templates
     TemplatingError(String),
+    /// Errors that occur while fetching remote items.
+    NetworkError(String),
+    /// Multiple errors collected across items, in the order they occur.
+    MultipleErrors(Vec<Error>),
+}
+
+impl Error {
+    /// Attaches `context` to the error message, keeping the error variant
+    /// unchanged.  The context is prepended to the original message,
+    /// separated with a colon.  For [`MultipleErrors`], the context is
+    /// attached to each of the contained errors.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dt_core::error::Error;
+    /// let err = Error::IoError("Permission denied".to_owned());
+    /// assert_eq!(
+    ///     err.context("Group [nvim], source '/tmp/init.lua'"),
+    ///     Error::IoError(
+    ///         "Group [nvim], source '/tmp/init.lua': Permission denied".to_owned()
+    ///     ),
+    /// );
+    /// ```
+    ///
+    /// [`MultipleErrors`]: Error::MultipleErrors
+    pub fn context<C: fmt::Display>(self, context: C) -> Self {
+        let context = context.to_string();
+        let attach = |msg: String| format!("{}: {}", context, msg);
+        match self {
+            Error::ConfigError(msg) => Error::ConfigError(attach(msg)),
+            Error::IoError(msg) => Error::IoError(attach(msg)),
+            Error::ParseError(msg) => Error::ParseError(attach(msg)),
+            Error::PathError(msg) => Error::PathError(attach(msg)),
+            Error::RenderingError(msg) => Error::RenderingError(attach(msg)),
+            Error::SyncingError(msg) => Error::SyncingError(attach(msg)),
+            Error::TemplatingError(msg) => Error::TemplatingError(attach(msg)),
+            Error::NetworkError(msg) => Error::NetworkError(attach(msg)),
+            Error::MultipleErrors(errors) => {
+                Error::MultipleErrors(errors.into_iter().map(|e| e.context(&context)).collect())
+            }
+        }
+    }
+
+    /// Aggregates given errors into a single [`MultipleErrors`].  Nested
+    /// [`MultipleErrors`] are flattened, so that each contained error is
+    /// listed on its own line when displayed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dt_core::error::Error;
+    /// let err = Error::multiple(vec![
+    ///     Error::IoError("Permission denied".to_owned()),
+    ///     Error::multiple(vec![Error::RenderingError(
+    ///         "Variable \"name\" not found".to_owned(),
+    ///     )]),
+    /// ]);
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     r#"2 errors occurred:
+    ///   - IO Error: Permission denied
+    ///   - Rendering Error: Variable "name" not found"#,
+    /// );
+    /// ```
+    ///
+    /// [`MultipleErrors`]: Error::MultipleErrors
+    pub fn multiple<I: IntoIterator<Item = Error>>(errors: I) -> Self {
+        Error::MultipleErrors(
+            errors
+                .into_iter()
+                .flat_map(|e| match e {
+                    Error::MultipleErrors(errors) => errors,
+                    e => vec![e],
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns the contained errors of a [`MultipleErrors`], or a slice
+    /// containing only this error otherwise.
+    ///
+    /// [`MultipleErrors`]: Error::MultipleErrors
+    pub fn errors(&self) -> &[Error] {
+        match self {
+            Error::MultipleErrors(errors) => errors,
+            e => std::slice::from_ref(e),
+        }
+    }
 }
 
 /// `Result` type to use across the library.
@@ -48,6 +137,17 @@ impl fmt::Display for Error {
             Error::TemplatingError(ref msg) => {
                 write!(f, "Templating Error: {}", msg)
             }
+            Error::NetworkError(ref msg) => {
+                write!(f, "Network Error: {}", msg)
+            }
+            Error::MultipleErrors(ref errors) => {
+                write!(f, "{} errors occurred:", errors.len())?;
+                for e in errors {
+                    // Indent continuation lines of multi-line messages
+                    write!(f, "\n  - {}", e.to_string().replace('\n', "\n    "))?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -62,6 +162,16 @@ impl From<toml::de::Error> for Error {
         Self::ParseError(err.to_string())
     }
 }
+impl From<toml::ser::Error> for Error {
+    fn from(err: toml::ser::Error) -> Self {
+        Self::ParseError(err.to_string())
+    }
+}
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Self::ParseError(err.to_string())
+    }
+}
 impl From<std::path::StripPrefixError> for Error {
     fn from(err: std::path::StripPrefixError) -> Self {
         Self::PathError(err.to_string())
@@ -1,14 +1,20 @@
 use std::{
+    fs::Permissions,
     os::unix::prelude::PermissionsExt,
     path::{Path, PathBuf},
     rc::Rc,
 };
 
+#[cfg(not(test))]
+use std::os::unix::fs::symlink;
+
 use path_clean::PathClean;
 use url::Url;
 
+#[cfg(test)]
+use crate::utils::testing::symlink;
 use crate::{
-    config::{Group, LocalGroup, RenamingRule, SyncMethod},
+    config::{Group, LinkMode, LocalGroup, RemoteGroup, RenamingRule, Sidecar, SyncMethod},
     error::{Error as AppError, Result},
     registry::Register,
     utils,
@@ -22,17 +28,19 @@ pub trait Operate
 where
     Self: Sized,
 {
-    /// Checks if the item is for another machine.
-    fn is_for_other_host(&self, hostname_sep: &str) -> bool {
+    /// Checks if the item is for another machine than the one with given
+    /// `hostname`.
+    fn is_for_other_host(&self, hostname_sep: &str, hostname: &str) -> bool {
         unimplemented!()
     }
     /// Gets the absolute location of `self`, if applicable.
     fn absolute(self) -> Result<Self> {
         unimplemented!()
     }
-    /// Gets the host-specific counterpart of `self`, if applicable.  If
-    /// `self` is already host-specific, returns `self` directly.
-    fn host_specific(self, hostname_sep: &str) -> Self {
+    /// Gets the host-specific counterpart of `self` for the machine with
+    /// given `hostname`, if applicable.  If `self` is already host-specific,
+    /// returns `self` directly.
+    fn host_specific(self, hostname_sep: &str, hostname: &str) -> Self {
         unimplemented!()
     }
     /// Gets the non-host-specific counterpart of `self`, if applicable.  If
@@ -56,15 +64,17 @@ where
     fn is_twisted(&self) -> bool {
         unimplemented!()
     }
-    /// Given a `hostname_sep`, a `base`, a `targetbase`, and optionally a
-    /// list of [renaming rule]s, creates the path where `self` would be
-    /// synced to.  Renaming rules are applied after host-specific suffixes
+    /// Given a `hostname_sep`, a `base`, a `targetbase`, a number of
+    /// leading components to strip, and optionally a list of [renaming
+    /// rule]s, creates the path where `self` would be synced to.  Renaming
+    /// rules are applied after host-specific suffixes and leading components
     /// are stripped.
     fn make_target<P>(
         self,
         hostname_sep: &str,
         base: &Self,
         targetbase: P,
+        strip_components: usize,
         renaming_rules: Vec<RenamingRule>,
     ) -> Result<Self>
     where
@@ -80,6 +90,16 @@ where
     ) -> Result<Vec<u8>> {
         unimplemented!()
     }
+    /// Checks whether the target of this item is already up to date with
+    /// given rendered contents, under the syncing method of given group.
+    fn target_matches(&self, group: &Group<Self>, rendered: &[u8]) -> Result<bool> {
+        unimplemented!()
+    }
+    /// Gets the expected and actual permission bits of the target of this
+    /// item, if they differ.
+    fn target_mode_drift(&self, group: &Group<Self>) -> Result<Option<(u32, u32)>> {
+        unimplemented!()
+    }
     /// Populate this item with given group config.  The given group config is
     /// expected to be the group where this item belongs to.
     fn populate<T: Register>(&self, group: Rc<Group<Self>>, registry: Rc<T>) -> Result<()> {
@@ -93,12 +113,150 @@ where
     }
 }
 
+/// Gets the syncing method of `path` in `group`.  Items with binary contents
+/// may use a different syncing method than their group, as configured by
+/// `global.default_method_for_binary`, while a method in the item's sidecar
+/// overrides both.  An item synced in place is always copied, since linking
+/// it to itself would lose its contents.
+fn sync_method(path: &Path, group: &LocalGroup) -> Result<SyncMethod> {
+    let tpath = path.to_owned().make_target(
+        &group.get_hostname_sep(),
+        &group.base,
+        &group.target,
+        group.strip_components,
+        group.get_item_renaming_rules(path)?,
+    )?;
+    if tpath == path {
+        return Ok(SyncMethod::Copy);
+    }
+    if let Some(method) = Sidecar::of(path)?.method {
+        return Ok(method);
+    }
+    Ok(match group.global.default_method_for_binary {
+        Some(method) if utils::is_binary(path)? => method,
+        _ => group.get_method(),
+    })
+}
+
+/// Gets the permissions of the target (or staging file) of `path` in
+/// `group`, i.e. the permissions of `path` masked by [`umask`], or
+/// [`force_mode`] when it is set.
+///
+/// [`umask`]: crate::config::Group::umask
+/// [`force_mode`]: crate::config::GlobalConfig::force_mode
+fn target_permissions(path: &Path, group: &LocalGroup) -> Result<Permissions> {
+    let mode = path.metadata()?.permissions().mode();
+    Ok(Permissions::from_mode(match group.global.force_mode {
+        // Keep the file type bits
+        Some(force_mode) => mode & !0o7777 | force_mode,
+        None => mode & !group.get_umask(),
+    }))
+}
+
+/// Logs the rendered contents of item `path` in `group`, or a redacted
+/// placeholder if the group is [`sensitive`].
+///
+/// [`sensitive`]: crate::config::Group::sensitive
+fn log_rendered(path: &Path, group: &LocalGroup, content: &[u8]) {
+    if group.sensitive {
+        log::trace!(
+            target: &group.name.log_target(),
+            "RENDER [{}]> '{}' renders to: <redacted>",
+            group.name,
+            path.display(),
+        );
+    } else {
+        log::trace!(
+            target: &group.name.log_target(),
+            "RENDER [{}]> '{}' renders to: {:?}",
+            group.name,
+            path.display(),
+            String::from_utf8_lossy(content),
+        );
+    }
+}
+
+/// Checks whether directory `dir` only holds items synced from `source` one
+/// by one, i.e. whether every entry under `dir`, other than directories,
+/// is either a symlink to its counterpart at the same relative path under
+/// `source` or `staged` (the staging directory of `source`), or a file with
+/// the same contents as either counterpart.  Entries that were changed
+/// after syncing do not count as synced.
+fn mirrors(dir: &Path, source: &Path, staged: &Path) -> Result<bool> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap();
+        let (counterpart, staged_counterpart) = (source.join(name), staged.join(name));
+        let synced = if path.is_dir() && !path.is_symlink() {
+            mirrors(&path, &counterpart, &staged_counterpart)?
+        } else if path.is_symlink() {
+            std::fs::read_link(&path)? == staged_counterpart
+                || matches!(
+                    (path.canonicalize(), counterpart.canonicalize()),
+                    (Ok(dest), Ok(counterpart)) if dest == counterpart
+                )
+        } else {
+            let content = std::fs::read(&path)?;
+            [&counterpart, &staged_counterpart]
+                .iter()
+                .any(|p| std::fs::read(p).is_ok_and(|c| c == content))
+        };
+        if !synced {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Checks whether `tpath` is a regular file copied from `src` by [`link`]
+/// instead of linking to it, i.e. [`link_mode`] is [`CopyFallback`] and both
+/// have the same contents.
+///
+/// [`link_mode`]: crate::config::GlobalConfig::link_mode
+/// [`CopyFallback`]: LinkMode::CopyFallback
+fn copied_from<T: Operate>(tpath: &Path, src: &Path, group: &Group<T>) -> bool {
+    group.global.link_mode == LinkMode::CopyFallback
+        && !tpath.is_symlink()
+        && matches!(
+            (std::fs::read(tpath), std::fs::read(src)),
+            (Ok(copied), Ok(content)) if copied == content
+        )
+}
+
+/// Creates a symlink at `tpath` pointing to `src`.  When the filesystem
+/// does not support symlinks and [`link_mode`] is [`CopyFallback`], copies
+/// `src` to `tpath` instead.
+///
+/// [`link_mode`]: crate::config::GlobalConfig::link_mode
+/// [`CopyFallback`]: LinkMode::CopyFallback
+fn link<T: Operate>(src: &Path, tpath: &Path, group: &Group<T>) -> std::io::Result<()> {
+    match utils::retry_transient(group.global.fs_retries, || symlink(src, tpath)) {
+        Err(e)
+            if group.global.link_mode == LinkMode::CopyFallback
+                && (e.kind() == std::io::ErrorKind::Unsupported
+                    // FAT filesystems reject symlinks with `EPERM`
+                    || e.raw_os_error() == Some(1)) =>
+        {
+            log::info!(
+                target: &group.name.log_target(),
+                "SYNC::LINK::FALLBACK [{}]> Could not symlink '{}' => '{}' ({}), copying instead",
+                group.name,
+                src.display(),
+                tpath.display(),
+                e,
+            );
+            std::fs::copy(src, tpath).map(|_| ())
+        }
+        result => result,
+    }
+}
+
 impl Operate for PathBuf {
     /// Checks if the item is for another machine (by checking its name).
     ///
     /// A host-specific item is considered for another machine, when its
     /// filename contains only 1 [`hostname_sep`], and after the
-    /// [`hostname_sep`] should not be current machine's hostname.
+    /// [`hostname_sep`] should not be given `hostname`.
     ///
     /// A non-host-specific item is always considered **not** for another
     /// machine (because it is non-host-specific, i.e. for all machines).
@@ -107,7 +265,7 @@ impl Operate for PathBuf {
     /// this function to panic.
     ///
     /// [`hostname_sep`]: crate::config::GlobalConfig::hostname_sep
-    fn is_for_other_host(&self, hostname_sep: &str) -> bool {
+    fn is_for_other_host(&self, hostname_sep: &str, hostname: &str) -> bool {
         let filename = self
             .file_name()
             .unwrap_or_else(
@@ -135,7 +293,7 @@ impl Operate for PathBuf {
             self.display(),
         );
 
-        split.len() > 1 && *split.last().unwrap() != gethostname::gethostname().to_string_lossy()
+        split.len() > 1 && *split.last().unwrap() != hostname
     }
 
     /// Gets the absolute path of `self`, **without** traversing symlinks.
@@ -152,10 +310,11 @@ impl Operate for PathBuf {
         Ok(absolute_path)
     }
 
-    /// Gets the host-specific counterpart of `self`.  If `self` is already
-    /// host-specific, returns `self` directly.
-    fn host_specific(self, hostname_sep: &str) -> Self {
-        if self.ends_with(utils::host_specific_suffix(hostname_sep)) {
+    /// Gets the host-specific counterpart of `self` for the machine with
+    /// given `hostname`.  If `self` is already host-specific, returns `self`
+    /// directly.
+    fn host_specific(self, hostname_sep: &str, hostname: &str) -> Self {
+        if self.ends_with(utils::host_specific_suffix(hostname_sep, hostname)) {
             self
         } else {
             let hs_filename = self
@@ -171,7 +330,7 @@ impl Operate for PathBuf {
                     )
                 })
                 .to_owned()
-                + &utils::host_specific_suffix(hostname_sep);
+                + &utils::host_specific_suffix(hostname_sep, hostname);
 
             self.with_file_name(hs_filename)
         }
@@ -280,7 +439,7 @@ impl Operate for PathBuf {
     /// let targetbase: PathBuf = "/path/to/target".into();
     ///
     /// assert_eq!(
-    ///     itm.make_target("@@", &base, &targetbase, vec![])?,
+    ///     itm.make_target("@@", &base, &targetbase, 0, vec![])?,
     ///     PathBuf::from_str("/path/to/target/item").unwrap(),
     /// );
     /// # Ok::<(), AppError>(())
@@ -307,7 +466,7 @@ impl Operate for PathBuf {
     /// ];
     ///
     /// assert_eq!(
-    ///     itm.make_target("@@", &base, &targetbase, rules)?,
+    ///     itm.make_target("@@", &base, &targetbase, 0, rules)?,
     ///     PathBuf::from_str("/path/to/target/.item").unwrap(),
     /// );
     /// # Ok::<(), AppError>(())
@@ -341,7 +500,7 @@ impl Operate for PathBuf {
     /// ];
     ///
     /// assert_eq!(
-    ///     itm.make_target("@@", &base, &targetbase, rules)?,
+    ///     itm.make_target("@@", &base, &targetbase, 0, rules)?,
     ///     PathBuf::from_str("/path/to/target/_dotted_item.ext").unwrap(),
     /// );
     /// # Ok::<(), AppError>(())
@@ -372,6 +531,7 @@ impl Operate for PathBuf {
     ///         "@@",
     ///         &base,
     ///         &targetbase,
+    ///         0,
     ///         vec![named_capture]
     ///     )?,
     ///     PathBuf::from_str("/path/to/target/.dot.item.ext").unwrap(),
@@ -388,6 +548,7 @@ impl Operate for PathBuf {
     ///         "@@",
     ///         &base,
     ///         &targetbase,
+    ///         0,
     ///         vec![numbered_capture]
     ///     )?,
     ///     PathBuf::from_str("/path/to/target/_dot_item_ext_.ext").unwrap(),
@@ -395,12 +556,67 @@ impl Operate for PathBuf {
     /// # Ok::<(), AppError>(())
     /// ```
     ///
+    /// ## Stripping leading components
+    ///
+    /// Leading components of the path relative to `base` are stripped
+    /// before renaming rules are applied, the file name is always kept.
+    ///
+    /// ```rust
+    /// # use dt_core::{
+    /// #   config::RenamingRule,
+    /// #   error::Error as AppError,
+    /// #   item::Operate
+    /// # };
+    /// # use std::path::PathBuf;
+    /// # use std::str::FromStr;
+    /// let itm: PathBuf = "/path/to/source/nvim/lua/_dot_item".into();
+    /// let base: PathBuf = "/path/to/source".into();
+    /// let targetbase: PathBuf = "/path/to/target".into();
+    /// let rules = vec![
+    ///     RenamingRule{
+    ///         pattern: regex::Regex::new("^_dot_").unwrap(),
+    ///         substitution: ".".into(),
+    ///     },
+    /// ];
+    ///
+    /// assert_eq!(
+    ///     itm.to_owned().make_target("@@", &base, &targetbase, 1, rules)?,
+    ///     PathBuf::from_str("/path/to/target/lua/.item").unwrap(),
+    /// );
+    /// assert_eq!(
+    ///     itm.make_target("@@", &base, &targetbase, 5, vec![])?,
+    ///     PathBuf::from_str("/path/to/target/_dot_item").unwrap(),
+    /// );
+    /// # Ok::<(), AppError>(())
+    /// ```
+    ///
+    /// ## Source equal to base
+    ///
+    /// ```rust
+    /// # use dt_core::{
+    /// #   error::Error as AppError,
+    /// #   item::Operate
+    /// # };
+    /// # use std::path::PathBuf;
+    /// let itm: PathBuf = "/path/to/source@@john".into();
+    /// let base: PathBuf = "/path/to/source".into();
+    /// let targetbase: PathBuf = "/path/to/target".into();
+    ///
+    /// match itm.make_target("@@", &base, &targetbase, 0, vec![]) {
+    ///     Err(AppError::PathError(msg)) => {
+    ///         assert!(msg.contains("is its base directory"), "{}", msg)
+    ///     }
+    ///     other => panic!("unexpected result: {:?}", other),
+    /// }
+    /// ```
+    ///
     /// [renaming rule]: crate::config::RenamingRule
     fn make_target<P: AsRef<Path>>(
         self,
         hostname_sep: &str,
         base: &Self,
         targetbase: P,
+        strip_components: usize,
         renaming_rules: Vec<RenamingRule>,
     ) -> Result<Self> {
         // Get non-host-specific counterpart of `self`
@@ -411,7 +627,22 @@ impl Operate for PathBuf {
 
         // The tail of the target path, which is the non-host-specific `self`
         // without its `base` prefix path
-        let mut tail = nhself.strip_prefix(base)?.to_owned();
+        let mut tail = nhself.strip_prefix(&base)?.to_owned();
+        if tail.as_os_str().is_empty() {
+            return Err(AppError::PathError(format!(
+                "source '{}' is its base directory '{}' itself, thus has no target path",
+                nhself.display(),
+                base.display(),
+            )));
+        }
+
+        // Strip leading components of the tail, keeping at least its last
+        // component
+        let strip = std::cmp::min(
+            strip_components,
+            tail.components().count().saturating_sub(1),
+        );
+        tail = tail.components().skip(strip).collect();
 
         // Apply renaming rules to the tail component
         for rr in renaming_rules {
@@ -451,16 +682,113 @@ impl Operate for PathBuf {
         }
     }
 
+    /// Checks whether the target of this item is already up to date with
+    /// given rendered contents, i.e.:
+    ///
+    /// - [`Copy`]: the target is a regular file with the same contents;
+    /// - [`Symlink`]: the target is a symlink to the staging file, and the
+    ///   staging file has the same contents;
+    /// - [`SymlinkDirect`]: the target is a symlink to this item, the
+    ///   contents are not checked.
+    ///
+    /// With [`CopyFallback`], a regular file with the same contents as the
+    /// file the target would link to also counts as up to date.
+    ///
+    /// [`Copy`]: SyncMethod::Copy
+    /// [`Symlink`]: SyncMethod::Symlink
+    /// [`SymlinkDirect`]: SyncMethod::SymlinkDirect
+    /// [`CopyFallback`]: LinkMode::CopyFallback
+    fn target_matches(&self, group: &LocalGroup, rendered: &[u8]) -> Result<bool> {
+        let tpath = self.to_owned().make_target(
+            &group.get_hostname_sep(),
+            &group.base,
+            &group.target,
+            group.strip_components,
+            group.get_item_renaming_rules(self)?,
+        )?;
+        let has_content =
+            |path: &Path| std::fs::read(path).is_ok_and(|content| content == rendered);
+        let links_to = |dest: &Path| {
+            std::fs::read_link(&tpath).is_ok_and(|existing| existing == dest)
+                || copied_from(&tpath, dest, group)
+        };
+        Ok(match sync_method(self, group)? {
+            SyncMethod::Copy => !tpath.is_symlink() && has_content(&tpath),
+            SyncMethod::Symlink => {
+                let staging_path = self.to_owned().make_target(
+                    &group.get_hostname_sep(),
+                    &group.base,
+                    group.get_staging_dir(),
+                    0,
+                    Vec::new(),
+                )?;
+                links_to(&staging_path) && has_content(&staging_path)
+            }
+            SyncMethod::SymlinkDirect => links_to(&self.to_owned().absolute()?),
+        })
+    }
+
+    /// Gets the expected and actual permission bits of the target of this
+    /// item, if they differ.  The staging file is checked instead with the
+    /// [`Symlink`] method, and nothing is checked with the
+    /// [`SymlinkDirect`] method.
+    ///
+    /// [`Symlink`]: SyncMethod::Symlink
+    /// [`SymlinkDirect`]: SyncMethod::SymlinkDirect
+    fn target_mode_drift(&self, group: &LocalGroup) -> Result<Option<(u32, u32)>> {
+        let path = match sync_method(self, group)? {
+            SyncMethod::Copy => self.to_owned().make_target(
+                &group.get_hostname_sep(),
+                &group.base,
+                &group.target,
+                group.strip_components,
+                group.get_item_renaming_rules(self)?,
+            )?,
+            SyncMethod::Symlink => self.to_owned().make_target(
+                &group.get_hostname_sep(),
+                &group.base,
+                group.get_staging_dir(),
+                0,
+                Vec::new(),
+            )?,
+            SyncMethod::SymlinkDirect => return Ok(None),
+        };
+        let expected = target_permissions(self, group)?.mode() & 0o7777;
+        let actual = path.metadata()?.permissions().mode() & 0o7777;
+        Ok((expected != actual).then_some((expected, actual)))
+    }
+
     /// Populate this item with given group config.  The given group config is
     /// expected to be the group where this item belongs to.
     fn populate<T: Register>(&self, group: Rc<LocalGroup>, registry: Rc<T>) -> Result<()> {
+        let log_target = group.name.log_target();
+        let retries = group.global.fs_retries;
+
         // Create possibly missing parent directories along target's path.
         let tpath = self.to_owned().make_target(
             &group.get_hostname_sep(),
             &group.base,
             &group.target,
-            group.get_renaming_rules(),
+            group.strip_components,
+            group.get_item_renaming_rules(self)?,
         )?;
+
+        // Skip items that render to nothing when asked to.
+        if group.global.skip_empty_renders
+            && group.is_renderable()
+            && self.is_file()
+            && self.get_content(&registry, &group)?.is_empty()
+        {
+            log::info!(
+                target: &log_target,
+                "SYNC::SKIP [{}]> '{}' renders to empty contents, not creating '{}'",
+                group.name,
+                self.display(),
+                tpath.display(),
+            );
+            return Ok(());
+        }
+
         let tparent = tpath.parent().unwrap().to_owned();
         if tparent.has_file_as_parent() {
             return Err(AppError::PathError(format!(
@@ -469,14 +797,85 @@ impl Operate for PathBuf {
             )));
         }
         std::fs::create_dir_all(tparent)?;
-        if group.target.canonicalize()? == group.base.canonicalize()? {
+        if !group.allow_same_base_target
+            && group.target.canonicalize()? == group.base.canonicalize()?
+        {
             return Err(AppError::PathError(format!(
                 "base directory and its target point to the same path in group '{}'",
                 group.name,
             )));
         }
 
-        match group.get_method() {
+        // A symlinked directory is only expanded as an item when its group
+        // does not follow symlinks, in which case the link itself is
+        // reproduced at the target regardless of the syncing method.
+        if self.is_dir() && self.is_symlink() {
+            let dest = self.canonicalize()?;
+            if let Ok(existing) = std::fs::read_link(&tpath) {
+                if existing == dest {
+                    log::debug!(
+                        target: &log_target,
+                        "SYNC::LINK::SKIP [{}]> '{}' is already a symlink pointing to '{}'",
+                        group.name,
+                        tpath.display(),
+                        dest.display(),
+                    );
+                    return Ok(());
+                }
+                std::fs::remove_file(&tpath)?;
+            } else if tpath.exists() {
+                if !group.is_overwrite_allowed() {
+                    utils::warning!(
+                        target: &log_target,
+                        "SYNC::SKIP [{}]> Target path ('{}') exists while `allow_overwrite` is set to false",
+                        group.name,
+                        tpath.display(),
+                    );
+                    return Ok(());
+                }
+                if tpath.is_dir() {
+                    // The directory may hold items synced one by one from
+                    // this symlinked directory while its group followed
+                    // symlinks, replace them with the link.
+                    let staged = self.to_owned().make_target(
+                        &group.get_hostname_sep(),
+                        &group.base,
+                        group.get_staging_dir(),
+                        0,
+                        Vec::new(),
+                    )?;
+                    if !mirrors(&tpath, &dest, &staged)? {
+                        return Err(AppError::SyncingError(format!(
+                            "a directory '{}' exists at the target path of a symlinked directory '{}'",
+                            tpath.display(),
+                            self.display(),
+                        )));
+                    }
+                    log::debug!(
+                        target: &log_target,
+                        "SYNC::LINK::RECONCILE [{}]> '{}' holds items synced from '{}' one by one, removing it",
+                        group.name,
+                        tpath.display(),
+                        dest.display(),
+                    );
+                    std::fs::remove_dir_all(&tpath)?;
+                } else {
+                    std::fs::remove_file(&tpath)?;
+                }
+            }
+            log::debug!(
+                target: &log_target,
+                "SYNC::LINK [{}]> '{}' => '{}'",
+                group.name,
+                dest.display(),
+                tpath.display(),
+            );
+            utils::retry_transient(retries, || symlink(&dest, &tpath))?;
+            return Ok(());
+        }
+
+        let method = sync_method(self, &group)?;
+        match method {
             SyncMethod::Copy => {
                 // `self` is _always_ a file.  If its target path `tpath` is a
                 // directory, we should return an error.
@@ -489,6 +888,7 @@ impl Operate for PathBuf {
                 }
                 if tpath.is_symlink() {
                     log::debug!(
+                        target: &log_target,
                         "SYNC::COPY [{}]> '{}' is a symlink, removing it",
                         group.name,
                         tpath.display(),
@@ -498,78 +898,120 @@ impl Operate for PathBuf {
 
                 // Get content of this item
                 let src_content: Vec<u8> = self.get_content(&registry, &group)?;
+                log_rendered(self, &group, &src_content);
+                let write = |tpath: &PathBuf| {
+                    if group.in_place {
+                        // Truncates and rewrites the existing file node,
+                        // keeping its inode.
+                        std::fs::write(tpath, &src_content)
+                    } else {
+                        utils::write_atomically(tpath, &src_content)
+                    }
+                };
 
-                if let Ok(dest_content) = std::fs::read(&tpath) {
-                    // Check target file's contents, if it has identical
-                    // contents as self, there is no need to write to it.
-                    if src_content == dest_content {
-                        log::debug!(
-                            "SYNC::COPY::SKIP [{}]> '{}' has identical content as '{}'",
-                            group.name,
-                            tpath.display(),
-                            self.display(),
-                        );
-                    } else if std::fs::write(&tpath, &src_content).is_err() {
+                // Check target file's contents, if it has identical contents
+                // as self, there is no need to write to it.
+                if self.target_matches(&group, &src_content)? {
+                    log::debug!(
+                        target: &log_target,
+                        "SYNC::COPY::SKIP [{}]> '{}' has identical content as '{}'",
+                        group.name,
+                        tpath.display(),
+                        self.display(),
+                    );
+                } else if std::fs::File::open(&tpath).is_ok() {
+                    if utils::retry_transient(retries, || write(&tpath)).is_err() {
                         // Contents of target file differs from content of
                         // self, but writing to it failed.  It might be due to
                         // target file being readonly. Attempt to remove it
                         // and try again.
-                        log::warn!(
+                        utils::warning!(
+                            target: &log_target,
                             "SYNC::COPY::OVERWRITE [{}]> '{}' seems to be readonly, trying to remove it first ..",
                             group.name,
                             tpath.display(),
                         );
                         std::fs::remove_file(&tpath)?;
                         log::debug!(
+                            target: &log_target,
                             "SYNC::COPY::OVERWRITE [{}]> '{}' => '{}'",
                             group.name,
                             self.display(),
                             tpath.display(),
                         );
-                        std::fs::write(&tpath, src_content)?;
+                        utils::retry_transient(retries, || write(&tpath))?;
                     }
                 } else if tpath.exists() {
                     // If read of target file failed but it does exist, then
                     // the target file is probably unreadable. Attempt to
                     // remove it first, then write contents to `tpath`.
-                    log::warn!(
+                    utils::warning!(
+                        target: &log_target,
                         "SYNC::COPY::OVERWRITE [{}]> Could not read content of target file ('{}'), trying to remove it first ..",
                         group.name,
                         tpath.display(),
                     );
                     std::fs::remove_file(&tpath)?;
                     log::debug!(
+                        target: &log_target,
                         "SYNC::COPY::OVERWRITE [{}]> '{}' => '{}'",
                         group.name,
                         self.display(),
                         tpath.display(),
                     );
-                    std::fs::write(&tpath, src_content)?;
+                    utils::retry_transient(retries, || write(&tpath))?;
                 }
                 // If the target file does not exist --- this is the simplest
                 // case --- we just write the contents to `tpath`.
                 else {
                     log::debug!(
+                        target: &log_target,
                         "SYNC::COPY [{}]> '{}' => '{}'",
                         group.name,
                         self.display(),
                         tpath.display(),
                     );
-                    std::fs::write(&tpath, src_content)?;
+                    utils::retry_transient(retries, || write(&tpath))?;
                 }
 
-                // Copy permissions to target if permission bits do not match.
-                let src_perm = self.metadata()?.permissions();
+                // Read back the target to guard against silent corruption.
+                if group.verify {
+                    log::debug!(
+                        target: &log_target,
+                        "SYNC::COPY::VERIFY [{}]> '{}'",
+                        group.name,
+                        tpath.display(),
+                    );
+                    if std::fs::read(&tpath)? != src_content {
+                        return Err(AppError::SyncingError(format!(
+                            "content of target '{}' differs from source '{}' after writing",
+                            tpath.display(),
+                            self.display(),
+                        )));
+                    }
+                }
+
+                // Copy permissions (masked by umask, or forced) to target if
+                // permission bits do not match.
+                let src_perm = target_permissions(self, &group)?;
                 let dest_perm = tpath.metadata()?.permissions();
                 if dest_perm != src_perm {
                     log::debug!(
+                        target: &log_target,
                         "SYNC::COPY::SETPERM [{}]> source('{:o}') => target('{:o}')",
                         group.name,
                         src_perm.mode(),
                         dest_perm.mode()
                     );
-                    if let Err(e) = std::fs::set_permissions(tpath, src_perm) {
-                        log::warn!("'{}': Could not set permission: {}", self.display(), e,);
+                    if let Err(e) = utils::retry_transient(retries, || {
+                        std::fs::set_permissions(&tpath, src_perm.clone())
+                    }) {
+                        utils::warning!(
+                            target: &log_target,
+                            "'{}': Could not set permission: {}",
+                            self.display(),
+                            e,
+                        );
                     }
                 }
             }
@@ -577,28 +1019,35 @@ impl Operate for PathBuf {
                 let staging_path = self.to_owned().make_target(
                     &group.get_hostname_sep(),
                     &group.base,
-                    &group.get_staging_dir(),
+                    group.get_staging_dir(),
+                    0,          // Do not strip components on staging path
                     Vec::new(), // Do not apply renaming on staging path
                 )?;
-                let sparent = staging_path.parent().unwrap().to_owned();
-                if sparent.has_file_as_parent() {
-                    return Err(AppError::PathError(format!(
-                        "staging path's parent '{}' contains one or more file component thus can not be created as a directory",
-                        sparent.display()
-                    )));
-                }
-                std::fs::create_dir_all(sparent)?;
-                if group.global.staging.0.canonicalize()? == group.base.canonicalize()? {
-                    return Err(AppError::PathError(format!(
-                        "base directory and its target point to the same path in group '{}'",
-                        group.name,
-                    )));
-                }
-                if group.global.staging.0.canonicalize()? == group.target.canonicalize()? {
-                    return Err(AppError::PathError(format!(
-                        "target directory and staging directory point to the same path in group '{}'",
-                        group.name,
-                    )));
+                // Staging is deferred when the staging directory does not
+                // exist (e.g. a volume that is not mounted yet) and dangling
+                // links are allowed, the link is created regardless.
+                let deferred = group.global.symlink_broken_ok && !group.global.staging.0.exists();
+                if !deferred {
+                    let sparent = staging_path.parent().unwrap().to_owned();
+                    if sparent.has_file_as_parent() {
+                        return Err(AppError::PathError(format!(
+                            "staging path's parent '{}' contains one or more file component thus can not be created as a directory",
+                            sparent.display()
+                        )));
+                    }
+                    std::fs::create_dir_all(sparent)?;
+                    if group.global.staging.0.canonicalize()? == group.base.canonicalize()? {
+                        return Err(AppError::PathError(format!(
+                            "base directory and its target point to the same path in group '{}'",
+                            group.name,
+                        )));
+                    }
+                    if group.global.staging.0.canonicalize()? == group.target.canonicalize()? {
+                        return Err(AppError::PathError(format!(
+                            "target directory and staging directory point to the same path in group '{}'",
+                            group.name,
+                        )));
+                    }
                 }
 
                 // `self` is _always_ a file.  If its target path `tpath` is a
@@ -612,11 +1061,13 @@ impl Operate for PathBuf {
                 }
 
                 if tpath.exists() && !group.is_overwrite_allowed() {
-                    log::warn!(
+                    utils::warning!(
+                        target: &log_target,
                         "SYNC::SKIP [{}]> Target path ('{}') exists while `allow_overwrite` is set to false",
                         group.name,
                         tpath.display(),
                     );
+                    return Ok(());
                 } else {
                     // In this block, either:
                     //
@@ -630,85 +1081,122 @@ impl Operate for PathBuf {
                     // Check if the content of destination is already the
                     // same as source first.  When the file is large, this
                     // operation is significantly faster than copying to an
-                    // existing target file.
+                    // existing target file.  Staging files are written
+                    // atomically, so that the symlink never points to a
+                    // partially written file.
 
                     // Get content of this item
-                    let src_content: Vec<u8> = self.get_content(&registry, &group)?;
+                    if deferred {
+                        log::debug!(
+                            target: &log_target,
+                            "SYNC::STAGE::DEFER [{}]> staging directory '{}' does not exist, not staging '{}'",
+                            group.name,
+                            group.global.staging.0.display(),
+                            self.display(),
+                        );
+                    } else {
+                        let src_content: Vec<u8> = self.get_content(&registry, &group)?;
+                        log_rendered(self, &group, &src_content);
 
-                    if let Ok(dest_content) = std::fs::read(&staging_path) {
-                        // Check staging file's contents, if it has identical
-                        // contents as self, there is no need to write to it.
-                        if src_content == dest_content {
-                            log::debug!(
-                                "SYNC::STAGE::SKIP [{}]> '{}' has identical content as '{}'",
+                        if let Ok(dest_content) = std::fs::read(&staging_path) {
+                            // Check staging file's contents, if it has identical
+                            // contents as self, there is no need to write to it.
+                            if src_content == dest_content {
+                                log::debug!(
+                                    target: &log_target,
+                                    "SYNC::STAGE::SKIP [{}]> '{}' has identical content as '{}'",
+                                    group.name,
+                                    staging_path.display(),
+                                    self.display(),
+                                );
+                            } else if utils::retry_transient(retries, || {
+                                utils::write_atomically(&staging_path, &src_content)
+                            })
+                            .is_err()
+                            {
+                                // Contents of staging file differs from content
+                                // of self, but writing to it failed.  It might be
+                                // due to staging file being readonly. Attempt to
+                                // remove it and try again.
+                                utils::warning!(
+                                    target: &log_target,
+                                    "SYNC::STAGE::OVERWRITE [{}]> '{}' seems to be readonly, trying to remove it first ..",
+                                    group.name,
+                                    staging_path.display(),
+                                );
+                                std::fs::remove_file(&staging_path)?;
+                                log::debug!(
+                                    target: &log_target,
+                                    "SYNC::STAGE [{}]> '{}' => '{}'",
+                                    group.name,
+                                    self.display(),
+                                    staging_path.display(),
+                                );
+                                utils::retry_transient(retries, || {
+                                    utils::write_atomically(&staging_path, &src_content)
+                                })?;
+                            }
+                        } else if staging_path.exists() {
+                            // If read of staging file failed but it does exist,
+                            // then the staging file is probably unreadable.
+                            // Attempt to remove it first, then write contents to
+                            // `staging_path`.
+                            utils::warning!(
+                                target: &log_target,
+                                "SYNC::STAGE::OVERWRITE [{}]> Could not read content of staging file ('{}'), trying to remove it first ..",
                                 group.name,
                                 staging_path.display(),
-                                self.display(),
                             );
-                        } else if std::fs::write(&staging_path, &src_content).is_err() {
-                            // Contents of staging file differs from content
-                            // of self, but writing to it failed.  It might be
-                            // due to staging file being readonly. Attempt to
-                            // remove it and try again.
-                            log::warn!(
-                                "SYNC::STAGE::OVERWRITE [{}]> '{}' seems to be readonly, trying to remove it first ..",
+                            std::fs::remove_file(&staging_path)?;
+                            log::debug!(
+                                target: &log_target,
+                                "SYNC::STAGE::OVERWRITE [{}]> '{}' => '{}'",
                                 group.name,
+                                self.display(),
                                 staging_path.display(),
                             );
-                            std::fs::remove_file(&staging_path)?;
+                            utils::retry_transient(retries, || {
+                                utils::write_atomically(&staging_path, &src_content)
+                            })?;
+                        }
+                        // If the staging file does not exist --- this is the
+                        // simplest case --- we just write the contents to
+                        // `staging_path`.
+                        else {
                             log::debug!(
+                                target: &log_target,
                                 "SYNC::STAGE [{}]> '{}' => '{}'",
                                 group.name,
                                 self.display(),
                                 staging_path.display(),
                             );
-                            std::fs::write(&staging_path, src_content)?;
+                            utils::retry_transient(retries, || {
+                                utils::write_atomically(&staging_path, &src_content)
+                            })?;
                         }
-                    } else if staging_path.exists() {
-                        // If read of staging file failed but it does exist,
-                        // then the staging file is probably unreadable.
-                        // Attempt to remove it first, then write contents to
-                        // `staging_path`.
-                        log::warn!(
-                            "SYNC::STAGE::OVERWRITE [{}]> Could not read content of staging file ('{}'), trying to remove it first ..",
-                            group.name,
-                            staging_path.display(),
-                        );
-                        std::fs::remove_file(&staging_path)?;
-                        log::debug!(
-                            "SYNC::STAGE::OVERWRITE [{}]> '{}' => '{}'",
-                            group.name,
-                            self.display(),
-                            staging_path.display(),
-                        );
-                        std::fs::write(&staging_path, src_content)?;
-                    }
-                    // If the staging file does not exist --- this is the
-                    // simplest case --- we just write the contents to
-                    // `staging_path`.
-                    else {
-                        log::debug!(
-                            "SYNC::STAGE [{}]> '{}' => '{}'",
-                            group.name,
-                            self.display(),
-                            staging_path.display(),
-                        );
-                        std::fs::write(&staging_path, src_content)?;
-                    }
 
-                    // Copy permissions to staging file if permission bits do
-                    // not match.
-                    let src_perm = self.metadata()?.permissions();
-                    let dest_perm = staging_path.metadata()?.permissions();
-                    if dest_perm != src_perm {
-                        log::debug!(
-                            "SYNC::STAGE::SETPERM [{}]> source('{:o}') => staging('{:o}')",
-                            group.name,
-                            src_perm.mode(),
-                            dest_perm.mode()
-                        );
-                        if let Err(e) = std::fs::set_permissions(&staging_path, src_perm) {
-                            log::warn!("'{}': Could not set permission: {}", self.display(), e,);
+                        // Copy permissions (masked by umask, or forced) to staging
+                        // file if permission bits do not match.
+                        let src_perm = target_permissions(self, &group)?;
+                        let dest_perm = staging_path.metadata()?.permissions();
+                        if dest_perm != src_perm {
+                            log::debug!(
+                                target: &log_target,
+                                "SYNC::STAGE::SETPERM [{}]> source('{:o}') => staging('{:o}')",
+                                group.name,
+                                src_perm.mode(),
+                                dest_perm.mode()
+                            );
+                            if let Err(e) = utils::retry_transient(retries, || {
+                                std::fs::set_permissions(&staging_path, src_perm.clone())
+                            }) {
+                                utils::warning!(
+                                    target: &log_target,
+                                    "'{}': Could not set permission: {}",
+                                    self.display(),
+                                    e,
+                                );
+                            }
                         }
                     }
 
@@ -719,6 +1207,7 @@ impl Operate for PathBuf {
                     if let Ok(dest) = std::fs::read_link(&tpath) {
                         if dest == staging_path {
                             log::debug!(
+                                target: &log_target,
                                 "SYNC::SYMLINK::SKIP [{}]> '{}' is already a symlink pointing to '{}'",
                                 group.name,
                                 tpath.display(),
@@ -726,13 +1215,14 @@ impl Operate for PathBuf {
                             );
                         } else {
                             log::debug!(
+                                target: &log_target,
                                 "SYNC::SYMLINK::OVERWRITE [{}]> '{}' => '{}'",
                                 group.name,
                                 staging_path.display(),
                                 tpath.display(),
                             );
                             std::fs::remove_file(&tpath)?;
-                            std::os::unix::fs::symlink(&staging_path, &tpath)?;
+                            link(&staging_path, &tpath, &group)?;
                         }
                     }
                     // If target file exists but is not a symlink, try to
@@ -740,25 +1230,120 @@ impl Operate for PathBuf {
                     // `staging_path` to `tpath`.
                     else if tpath.exists() {
                         log::debug!(
+                            target: &log_target,
                             "SYNC::SYMLINK::OVERWRITE [{}]> '{}' => '{}'",
                             group.name,
                             staging_path.display(),
                             tpath.display(),
                         );
                         std::fs::remove_file(&tpath)?;
-                        std::os::unix::fs::symlink(&staging_path, &tpath)?;
+                        link(&staging_path, &tpath, &group)?;
                     }
                     // The final case is that when `tpath` does not exist
                     // yet, we can then directly create a symlink.
                     else {
                         log::debug!(
+                            target: &log_target,
                             "SYNC::SYMLINK [{}]> '{}' => '{}'",
                             group.name,
                             staging_path.display(),
                             tpath.display(),
                         );
-                        std::os::unix::fs::symlink(&staging_path, &tpath)?;
+                        link(&staging_path, &tpath, &group)?;
+                    }
+                }
+            }
+            SyncMethod::SymlinkDirect => {
+                // `self` is _always_ a file.  If its target path `tpath` is a
+                // directory, we should return an error.
+                if tpath.is_dir() {
+                    return Err(AppError::SyncingError(format!(
+                        "a directory '{}' exists at the target path of a source file '{}'",
+                        tpath.display(),
+                        self.display(),
+                    )));
+                }
+
+                let src = self.to_owned().absolute()?;
+                if let Ok(dest) = std::fs::read_link(&tpath) {
+                    if dest == src {
+                        log::debug!(
+                            target: &log_target,
+                            "SYNC::SYMLINK::SKIP [{}]> '{}' is already a symlink pointing to '{}'",
+                            group.name,
+                            tpath.display(),
+                            src.display(),
+                        );
+                        return Ok(());
+                    }
+                }
+                if tpath.exists() || tpath.is_symlink() {
+                    if !group.is_overwrite_allowed() {
+                        utils::warning!(
+                            target: &log_target,
+                            "SYNC::SKIP [{}]> Target path ('{}') exists while `allow_overwrite` is set to false",
+                            group.name,
+                            tpath.display(),
+                        );
+                        return Ok(());
                     }
+                    log::debug!(
+                        target: &log_target,
+                        "SYNC::SYMLINK::OVERWRITE [{}]> '{}' => '{}'",
+                        group.name,
+                        src.display(),
+                        tpath.display(),
+                    );
+                    std::fs::remove_file(&tpath)?;
+                } else {
+                    log::debug!(
+                        target: &log_target,
+                        "SYNC::SYMLINK [{}]> '{}' => '{}'",
+                        group.name,
+                        src.display(),
+                        tpath.display(),
+                    );
+                }
+                link(&src, &tpath, &group)?;
+            }
+        }
+
+        // Apply configured ownership to the synced item.  With the `Symlink`
+        // method, the staged file is changed as well, while the source file
+        // is left untouched with the `SymlinkDirect` method.
+        let (uid, gid) = (group.get_owner_uid()?, group.get_group_gid()?);
+        if uid.is_some() || gid.is_some() {
+            let mut paths = vec![tpath.to_owned()];
+            // `tpath` is a copy instead of a symlink when symlinking fell
+            // back to copying, see `link`.
+            if method == SyncMethod::Symlink {
+                // The link dangles when staging was deferred, see
+                // `symlink_broken_ok`.
+                match std::fs::read_link(&tpath) {
+                    Ok(staged) if staged.exists() => paths.push(staged),
+                    _ => {}
+                }
+            }
+            for p in paths {
+                log::debug!(
+                    target: &log_target,
+                    "SYNC::CHOWN [{}]> '{}' => uid({:?}), gid({:?})",
+                    group.name,
+                    p.display(),
+                    uid,
+                    gid,
+                );
+                match std::os::unix::fs::lchown(&p, uid, gid) {
+                    Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+                        utils::warning!(
+                            target: &log_target,
+                            "SYNC::CHOWN::SKIP [{}]> Could not change ownership of '{}': {}",
+                            group.name,
+                            p.display(),
+                            e,
+                        );
+                    }
+                    result => result?,
                 }
             }
         }
@@ -770,16 +1355,20 @@ impl Operate for PathBuf {
     /// group config.  The given group config is expected to be the group
     /// where this item belongs to.
     fn populate_dry(&self, group: Rc<LocalGroup>) -> Result<()> {
+        let log_target = group.name.log_target();
+
         let tpath = self.to_owned().make_target(
             &group.get_hostname_sep(),
             &group.base,
             &group.target,
-            group.get_renaming_rules(),
+            group.strip_components,
+            group.get_item_renaming_rules(self)?,
         )?;
         if tpath.exists() {
             if group.is_overwrite_allowed() {
                 if tpath.is_dir() {
                     log::error!(
+                        target: &log_target,
                         "DRYRUN [{}]> A directory ('{}') exists at the target path of a source file ('{}')",
                         group.name,
                         tpath.display(),
@@ -787,6 +1376,7 @@ impl Operate for PathBuf {
                     );
                 } else {
                     log::debug!(
+                        target: &log_target,
                         "DRYRUN [{}]> '{}' -> '{}'",
                         group.name,
                         self.display(),
@@ -795,6 +1385,7 @@ impl Operate for PathBuf {
                 }
             } else {
                 log::error!(
+                    target: &log_target,
                     "DRYRUN [{}]> Target path ('{}') exists while `allow_overwrite` is set to false",
                     group.name,
                     tpath.display(),
@@ -802,6 +1393,7 @@ impl Operate for PathBuf {
             }
         } else {
             log::debug!(
+                target: &log_target,
                 "DRYRUN [{}]> '{}' -> '{}'",
                 group.name,
                 self.display(),
@@ -813,7 +1405,297 @@ impl Operate for PathBuf {
     }
 }
 
-impl Operate for Url {}
+/// Name of the directory under the staging root where remote items are
+/// downloaded to, see [`download_path`].
+pub const DOWNLOAD_DIRNAME: &str = ".remote";
+
+/// Gets the path where the remote item at `url` is downloaded to before it
+/// is rendered, i.e. the host (and port) and path of `url` under the
+/// [`DOWNLOAD_DIRNAME`] directory of the `staging` root.
+///
+/// # Example
+///
+/// ```rust
+/// # use dt_core::item::download_path;
+/// # use std::path::PathBuf;
+/// # use url::Url;
+/// let url = Url::parse("https://example.com:8443/dotfiles/vimrc").unwrap();
+/// assert_eq!(
+///     download_path(&url, "/tmp/staging".as_ref()),
+///     PathBuf::from("/tmp/staging/.remote/example.com:8443/dotfiles/vimrc"),
+/// );
+/// ```
+pub fn download_path(url: &Url, staging: &Path) -> PathBuf {
+    let host = match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_owned(),
+        (None, _) => "localhost".to_owned(),
+    };
+    staging
+        .join(DOWNLOAD_DIRNAME)
+        .join(host)
+        .join(url.path().trim_start_matches('/'))
+}
+
+/// Converts a target `url`, as returned from [`Operate::make_target`], back
+/// to a local path.
+pub(crate) fn file_path(url: &Url) -> Result<PathBuf> {
+    url.to_file_path()
+        .map_err(|_| AppError::PathError(format!("target '{}' is not a local file path", url)))
+}
+
+/// Writes `content` to `path` atomically, creating its parent directories,
+/// unless `path` is a regular file that already has the same contents.
+/// Returns whether `path` is written.
+fn write_if_changed(path: &Path, content: &[u8], retries: u32) -> Result<bool> {
+    if !path.is_symlink() && std::fs::read(path).is_ok_and(|existing| existing == content) {
+        return Ok(false);
+    }
+    if path.is_symlink() {
+        std::fs::remove_file(path)?;
+    }
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    utils::retry_transient(retries, || utils::write_atomically(path, content))?;
+    Ok(true)
+}
+
+/// Maps the path of `url` with `f`, leaving other parts unchanged.
+fn map_url_path<F: FnOnce(PathBuf) -> PathBuf>(mut url: Url, f: F) -> Url {
+    let path = f(PathBuf::from(url.path()));
+    url.set_path(&path.to_string_lossy());
+    url
+}
+
+impl Operate for Url {
+    /// Checks if the item is for another machine, by checking the last
+    /// segment of its path, see the [`PathBuf`] implementation.
+    fn is_for_other_host(&self, hostname_sep: &str, hostname: &str) -> bool {
+        PathBuf::from(self.path()).is_for_other_host(hostname_sep, hostname)
+    }
+
+    /// Gets the host-specific counterpart of this URL, by appending the
+    /// host-specific suffix to the last segment of its path.
+    fn host_specific(self, hostname_sep: &str, hostname: &str) -> Self {
+        map_url_path(self, |p| p.host_specific(hostname_sep, hostname))
+    }
+
+    /// Gets the non-host-specific counterpart of this URL, by converting
+    /// every segment of its path to a non-host-specific one.
+    fn non_host_specific(self, hostname_sep: &str) -> Self {
+        map_url_path(self, |p| p.non_host_specific(hostname_sep))
+    }
+
+    /// Checks whether any of the path segments references its parent.
+    fn is_twisted(&self) -> bool {
+        self.path_segments()
+            .is_some_and(|mut segments| segments.any(|seg| seg == ".."))
+    }
+
+    /// Creates the path where this URL would be synced to, in the same way
+    /// as the [`PathBuf`] implementation does with the paths of this URL and
+    /// `base`.  The path is returned as a `file:` URL.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use dt_core::{error::Error as AppError, item::Operate};
+    /// # use url::Url;
+    /// let itm = Url::parse("https://example.com/dotfiles/nvim/init.lua@@r2d2").unwrap();
+    /// let base = Url::parse("https://example.com/dotfiles/").unwrap();
+    ///
+    /// assert_eq!(
+    ///     itm.make_target("@@", &base, "/home/luke/.config", 0, vec![])?
+    ///         .to_file_path()
+    ///         .unwrap(),
+    ///     std::path::PathBuf::from("/home/luke/.config/nvim/init.lua"),
+    /// );
+    /// # Ok::<(), AppError>(())
+    /// ```
+    fn make_target<P: AsRef<Path>>(
+        self,
+        hostname_sep: &str,
+        base: &Self,
+        targetbase: P,
+        strip_components: usize,
+        renaming_rules: Vec<RenamingRule>,
+    ) -> Result<Self> {
+        let target = PathBuf::from(self.path()).make_target(
+            hostname_sep,
+            &PathBuf::from(base.path()),
+            targetbase,
+            strip_components,
+            renaming_rules,
+        )?;
+        Url::from_file_path(&target).map_err(|_| {
+            AppError::PathError(format!(
+                "target path '{}' of '{}' is not absolute",
+                target.display(),
+                self,
+            ))
+        })
+    }
+
+    /// Gets the contents of this item downloaded to [`download_path`],
+    /// rendered if the group is renderable.
+    fn get_content<R: Register, O: Operate>(
+        &self,
+        registry: &Rc<R>,
+        group: &Rc<Group<O>>,
+    ) -> Result<Vec<u8>> {
+        let dpath = download_path(self, &group.global.staging.0);
+        if group.is_renderable() {
+            registry.get(&dpath.to_string_lossy())
+        } else {
+            Ok(std::fs::read(dpath)?)
+        }
+    }
+
+    /// Checks whether the target of this item is already up to date with
+    /// given rendered contents, like the [`PathBuf`] implementation does.
+    fn target_matches(&self, group: &RemoteGroup, rendered: &[u8]) -> Result<bool> {
+        let hostname_sep = group.get_hostname_sep();
+        let tpath = file_path(&self.to_owned().make_target(
+            &hostname_sep,
+            &group.base,
+            &group.target,
+            group.strip_components,
+            group.get_renaming_rules(),
+        )?)?;
+        let has_content =
+            |path: &Path| std::fs::read(path).is_ok_and(|content| content == rendered);
+        Ok(match group.get_method() {
+            SyncMethod::Copy => !tpath.is_symlink() && has_content(&tpath),
+            SyncMethod::Symlink => {
+                let staging_path = file_path(&self.to_owned().make_target(
+                    &hostname_sep,
+                    &group.base,
+                    group.get_staging_dir(),
+                    0,
+                    Vec::new(),
+                )?)?;
+                (std::fs::read_link(&tpath).is_ok_and(|dest| dest == staging_path)
+                    || copied_from(&tpath, &staging_path, group))
+                    && has_content(&staging_path)
+            }
+            SyncMethod::SymlinkDirect => false,
+        })
+    }
+
+    /// Populates this item, which is expected to be downloaded to
+    /// [`download_path`] already, with given group config.  With the
+    /// [`Copy`] method, its contents are written to its target directly;
+    /// with the [`Symlink`] method, its contents are written to the staging
+    /// directory, and the target is linked to the staged file.
+    ///
+    /// [`Copy`]: SyncMethod::Copy
+    /// [`Symlink`]: SyncMethod::Symlink
+    fn populate<T: Register>(&self, group: Rc<RemoteGroup>, registry: Rc<T>) -> Result<()> {
+        let log_target = group.name.log_target();
+        let retries = group.global.fs_retries;
+        let hostname_sep = group.get_hostname_sep();
+        let content = self.get_content(&registry, &group)?;
+        let tpath = file_path(&self.to_owned().make_target(
+            &hostname_sep,
+            &group.base,
+            &group.target,
+            group.strip_components,
+            group.get_renaming_rules(),
+        )?)?;
+
+        if tpath.is_dir() {
+            return Err(AppError::SyncingError(format!(
+                "a directory '{}' exists at the target path of a remote item '{}'",
+                tpath.display(),
+                self,
+            )));
+        }
+        let skip_existing = || {
+            utils::warning!(
+                target: &log_target,
+                "SYNC::SKIP [{}]> Target path ('{}') exists while `allow_overwrite` is set to false",
+                group.name,
+                tpath.display(),
+            );
+        };
+
+        match group.get_method() {
+            SyncMethod::Copy => {
+                if !tpath.is_symlink() && std::fs::read(&tpath).is_ok_and(|c| c == content) {
+                    log::debug!(
+                        target: &log_target,
+                        "SYNC::COPY::SKIP [{}]> '{}' has identical content as '{}'",
+                        group.name,
+                        tpath.display(),
+                        self,
+                    );
+                } else if (tpath.exists() || tpath.is_symlink()) && !group.is_overwrite_allowed() {
+                    skip_existing();
+                } else {
+                    log::debug!(
+                        target: &log_target,
+                        "SYNC::COPY [{}]> '{}' => '{}'",
+                        group.name,
+                        self,
+                        tpath.display(),
+                    );
+                    write_if_changed(&tpath, &content, retries)?;
+                }
+            }
+            SyncMethod::Symlink => {
+                let staging_path = file_path(&self.to_owned().make_target(
+                    &hostname_sep,
+                    &group.base,
+                    group.get_staging_dir(),
+                    0,          // Do not strip components on staging path
+                    Vec::new(), // Do not apply renaming on staging path
+                )?)?;
+                if write_if_changed(&staging_path, &content, retries)? {
+                    log::debug!(
+                        target: &log_target,
+                        "SYNC::STAGE [{}]> '{}' => '{}'",
+                        group.name,
+                        self,
+                        staging_path.display(),
+                    );
+                }
+                if std::fs::read_link(&tpath).is_ok_and(|dest| dest == staging_path) {
+                    log::debug!(
+                        target: &log_target,
+                        "SYNC::SYMLINK::SKIP [{}]> '{}' is already a symlink pointing to '{}'",
+                        group.name,
+                        tpath.display(),
+                        staging_path.display(),
+                    );
+                } else if (tpath.exists() || tpath.is_symlink()) && !group.is_overwrite_allowed() {
+                    skip_existing();
+                } else {
+                    log::debug!(
+                        target: &log_target,
+                        "SYNC::SYMLINK [{}]> '{}' => '{}'",
+                        group.name,
+                        staging_path.display(),
+                        tpath.display(),
+                    );
+                    if tpath.exists() || tpath.is_symlink() {
+                        std::fs::remove_file(&tpath)?;
+                    }
+                    std::fs::create_dir_all(tpath.parent().unwrap())?;
+                    link(&staging_path, &tpath, &group)?;
+                }
+            }
+            // Denied when validating remote groups, but groups can be
+            // constructed without validating
+            SyncMethod::SymlinkDirect => {
+                return Err(AppError::ConfigError(format!(
+                    "remote group '{}' cannot use the `SymlinkDirect` method",
+                    group.name,
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
 
 // Author: Blurgy <gy@blurgy.xyz>
 // Date:   Oct 29 2021, 22:56 [CST]
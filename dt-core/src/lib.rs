@@ -118,30 +118,13 @@ sources = ["{}"]
             );
             Ok(())
         }
-    }
-}
-
-#[cfg(test)]
-mod block_helpers {
-    mod user {
-        use std::str::FromStr;
-
-        use crate::{
-            config::DTConfig,
-            registry::{Register, Registry},
-            syncing::expand,
-            utils::testing::{get_testroot, prepare_directory, prepare_file},
-        };
-
-        use color_eyre::Report;
-        use pretty_assertions::assert_eq;
 
         #[test]
-        fn if_user_exact() -> Result<(), Report> {
+        fn lookup_string_path() -> Result<(), Report> {
             let base = prepare_directory(
-                get_testroot("block_helpers")
-                    .join("user")
-                    .join("if_user_exact"),
+                get_testroot("inline_helpers")
+                    .join("get_mine")
+                    .join("lookup_string_path"),
                 0o755,
             )?;
             let src_name = "template";
@@ -149,8 +132,14 @@ mod block_helpers {
             let target = prepare_directory(base.join("target"), 0o755)?;
             let config = expand(DTConfig::from_str(&format!(
                 r#"
+[context.testing_group]
+origin.HAL9000 = "2001: a Space Odyssey"
+origin.r2d2 = "Star Wars"
+which = "testing_group.origin"
+"origin/escaped~".r2d2 = "Escaped"
+
 [[local]]
-name = "user"
+name = "testing_group"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -159,49 +148,59 @@ sources = ["{}"]
                 target.display(),
                 src_name,
             ))?)?;
+
+            // Path given as a string literal
             std::fs::write(
                 &template_path,
-                r#"Hi, {{#if_user "luke"}}Luke Skywalker{{else}}random person{{/if_user}}!"#,
+                r#"The name r2d2 comes from _{{get_mine "testing_group.origin" "None"}}_"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "Hi, Luke Skywalker!",
+                "The name r2d2 comes from _Star Wars_",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
 
-            let config = expand(DTConfig::from_str(&format!(
-                r#"
-[context.user]
-name = "luke"
+            // Path taken from another context value
+            std::fs::write(
+                &template_path,
+                r#"The name r2d2 comes from _{{get_mine testing_group.which "None"}}_"#,
+            )?;
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "The name r2d2 comes from _Star Wars_",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
 
-[[local]]
-name = "user"
-base = "{}"
-target = "{}"
-sources = ["{}"]
-"#,
-                base.display(),
-                target.display(),
-                src_name,
-            ))?)?;
+            // Non-existing path falls back to the default value
             std::fs::write(
                 &template_path,
-                r#"Welcome back, {{#if_user user.name}}Luke Skywalker{{else}}random person{{/if_user}}!"#,
+                r#"The name r2d2 comes from _{{get_mine "testing_group.nothing" "None"}}_"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "Welcome back, Luke Skywalker!",
+                "The name r2d2 comes from _None_",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+
+            // Segments containing `~` or `/`
+            std::fs::write(
+                &template_path,
+                r#"{{get_mine "testing_group.origin/escaped~" "None"}}"#,
+            )?;
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "Escaped",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
             Ok(())
         }
 
         #[test]
-        fn if_user_any() -> Result<(), Report> {
+        fn hostname_override() -> Result<(), Report> {
             let base = prepare_directory(
-                get_testroot("block_helpers")
-                    .join("user")
-                    .join("if_user_any"),
+                get_testroot("inline_helpers")
+                    .join("get_mine")
+                    .join("hostname_override"),
                 0o755,
             )?;
             let src_name = "template";
@@ -209,8 +208,15 @@ sources = ["{}"]
             let target = prepare_directory(base.join("target"), 0o755)?;
             let config = expand(DTConfig::from_str(&format!(
                 r#"
+[global]
+hostname_override = "tatooine"
+
+[context.testing_group]
+origin.r2d2 = "Star Wars"
+origin.tatooine = "A Desert Planet"
+
 [[local]]
-name = "user"
+name = "testing_group"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -219,27 +225,52 @@ sources = ["{}"]
                 target.display(),
                 src_name,
             ))?)?;
-
             std::fs::write(
                 &template_path,
-                r#"Hi, {{#if_user "luke,skywalker"}}Luke Skywalker{{else}}random person{{/if_user}}!"#,
+                r#"{{get_mine}}: {{get_mine testing_group.origin "None"}}"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "Hi, Luke Skywalker!",
+                "tatooine: A Desert Planet",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
+            Ok(())
+        }
+    }
+
+    mod coalesce {
+        use std::str::FromStr;
+
+        use crate::{
+            config::DTConfig,
+            registry::{Register, Registry},
+            syncing::expand,
+            utils::testing::{get_testroot, prepare_directory, prepare_file},
+        };
 
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn first_non_empty() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("inline_helpers")
+                    .join("coalesce")
+                    .join("first_non_empty"),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
             let config = expand(DTConfig::from_str(&format!(
                 r#"
-[context.user]
-allowed_names = [
-    "skywalker",
-    "luke",
-]
+[context.coalesce]
+empty = ""
+font = "Iosevka"
+fallback = "monospace"
 
 [[local]]
-name = "user"
+name = "coalesce"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -250,22 +281,22 @@ sources = ["{}"]
             ))?)?;
             std::fs::write(
                 &template_path,
-                "Welcome back, {{#if_user user.allowed_names}}Luke Skywalker{{else}}random person{{/if_user}}!",
+                r#"{{coalesce coalesce.empty coalesce.undefined coalesce.font coalesce.fallback}}"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "Welcome back, Luke Skywalker!",
+                "Iosevka",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
             Ok(())
         }
 
         #[test]
-        fn unless_user_exact() -> Result<(), Report> {
+        fn all_empty() -> Result<(), Report> {
             let base = prepare_directory(
-                get_testroot("block_helpers")
-                    .join("user")
-                    .join("unless_user_exact"),
+                get_testroot("inline_helpers")
+                    .join("coalesce")
+                    .join("all_empty"),
                 0o755,
             )?;
             let src_name = "template";
@@ -273,8 +304,11 @@ sources = ["{}"]
             let target = prepare_directory(base.join("target"), 0o755)?;
             let config = expand(DTConfig::from_str(&format!(
                 r#"
+[context.coalesce]
+empty = ""
+
 [[local]]
-name = "user"
+name = "coalesce"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -285,21 +319,52 @@ sources = ["{}"]
             ))?)?;
             std::fs::write(
                 &template_path,
-                r#"Hi, {{#unless_user "luke"}}random person{{else}}Luke Skywalker{{/unless_user}}!"#,
+                r#"[{{coalesce coalesce.empty coalesce.undefined ""}}][{{coalesce}}]"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "Hi, Luke Skywalker!",
+                "[][]",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
+            Ok(())
+        }
+    }
+
+    mod env {
+        use std::str::FromStr;
+
+        use crate::{
+            config::DTConfig,
+            registry::{Register, Registry},
+            syncing::expand,
+            utils::{
+                load_env_file,
+                testing::{get_testroot, prepare_directory, prepare_file},
+            },
+        };
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn from_env_file() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("inline_helpers")
+                    .join("env")
+                    .join("from_env_file"),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let env_file = prepare_file(base.join(".env"), 0o644)?;
+            std::fs::write(&env_file, "DT_TEST_ENV_FILE_THEME=gruvbox\n")?;
+            load_env_file(&env_file)?;
 
             let config = expand(DTConfig::from_str(&format!(
                 r#"
-[context.user]
-name = "luke"
-
 [[local]]
-name = "user"
+name = "env"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -310,31 +375,30 @@ sources = ["{}"]
             ))?)?;
             std::fs::write(
                 &template_path,
-                "Welcome back, {{#unless_user user.name}}random person{{else}}Luke Skywalker{{/unless_user}}!",
+                r#"theme={{env "DT_TEST_ENV_FILE_THEME"}} font={{env "DT_TEST_ENV_FILE_UNSET" "mono"}}"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "Welcome back, Luke Skywalker!",
+                "theme=gruvbox font=mono",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
             Ok(())
         }
 
         #[test]
-        fn unless_user_any() -> Result<(), Report> {
+        fn get_env() -> Result<(), Report> {
             let base = prepare_directory(
-                get_testroot("block_helpers")
-                    .join("user")
-                    .join("unless_user_any"),
+                get_testroot("inline_helpers").join("env").join("get_env"),
                 0o755,
             )?;
             let src_name = "template";
             let template_path = prepare_file(base.join(src_name), 0o644)?;
             let target = prepare_directory(base.join("target"), 0o755)?;
+            std::env::set_var("DT_TEST_GET_ENV_EDITOR", "nvim");
             let config = expand(DTConfig::from_str(&format!(
                 r#"
 [[local]]
-name = "user"
+name = "env"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -343,49 +407,254 @@ sources = ["{}"]
                 target.display(),
                 src_name,
             ))?)?;
-
             std::fs::write(
                 &template_path,
-                r#"Hi, {{#unless_user "luke,skywalker"}}random person{{else}}Luke Skywalker{{/unless_user}}!"#,
+                r#"{{get_env "DT_TEST_GET_ENV_EDITOR" "vim"}} {{get_env "DT_TEST_GET_ENV_UNSET" "vim"}} [{{get_env "DT_TEST_GET_ENV_UNSET"}}]"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "Hi, Luke Skywalker!",
+                "nvim vim []",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
+            Ok(())
+        }
+    }
+}
 
-            let config = expand(DTConfig::from_str(&format!(
+#[cfg(test)]
+mod user_defined_helpers {
+    use std::str::FromStr;
+
+    use crate::{
+        config::DTConfig,
+        error::Error as AppError,
+        registry::{Register, Registry},
+        syncing::expand,
+        utils::testing::{get_testroot, prepare_directory, prepare_file},
+    };
+
+    use color_eyre::Report;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn declared_in_config() -> Result<(), Report> {
+        let base = prepare_directory(
+            get_testroot("user_defined_helpers").join("declared_in_config"),
+            0o755,
+        )?;
+        let src_name = "template";
+        let template_path = prepare_file(base.join(src_name), 0o644)?;
+        let target = prepare_directory(base.join("target"), 0o755)?;
+        let config = |helpers: &str| {
+            DTConfig::from_str(&format!(
                 r#"
-[context.user]
-allowed_names = [
-    "luke",
-    "skywalker"
-]
+[context]
+name = "Luke"
+
+[helpers]
+{}
 
 [[local]]
-name = "user"
+name = "user defined helpers"
 base = "{}"
 target = "{}"
 sources = ["{}"]
 "#,
+                helpers,
                 base.display(),
                 target.display(),
                 src_name,
-            ))?)?;
-            std::fs::write(
-                &template_path,
-                r#"Welcome back, {{#unless_user user.allowed_names}}random person{{else}}Luke Skywalker{{/unless_user}}!"#,
-            )?;
-            let reg = Registry::default().register_helpers()?.load(&config)?;
-            assert_eq!(
-                "Welcome back, Luke Skywalker!",
-                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
-            );
-            Ok(())
+            ))
+        };
+        std::fs::write(&template_path, "{{> greeting}}, welcome!")?;
+        let reg = Registry::default()
+            .register_helpers()?
+            .load(&expand(config(r#"greeting = "Hi {{name}}""#)?)?)?;
+        assert_eq!(
+            "Hi Luke, welcome!",
+            std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+        );
+
+        assert_eq!(
+            Registry::default()
+                .register_helpers()?
+                .load(&expand(config(r#"if_user = "Hi {{name}}""#)?)?)
+                .err(),
+            Some(AppError::TemplatingError(
+                "user-defined helper 'if_user' clashes with a built-in helper".to_owned()
+            )),
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod list_helpers {
+    use std::str::FromStr;
+
+    use crate::{config::DTConfig, registry::list_helpers};
+
+    use color_eyre::Report;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn builtin_and_user_defined() -> Result<(), Report> {
+        let config = DTConfig::from_str(
+            r#"
+[helpers]
+signature = "-- {{name}}"
+greeting = "Hi {{name}}""#,
+        )?;
+        let helpers = list_helpers(&config);
+        let names: Vec<&str> = helpers.iter().map(|(name, _)| name.as_str()).collect();
+        for builtin in ["get_mine", "if_host", "unless_tty", "repeat"] {
+            assert!(names.contains(&builtin), "'{}' is not listed", builtin);
         }
+        assert!(helpers
+            .iter()
+            .all(|(_, description)| !description.is_empty()));
+        assert_eq!(names[names.len() - 2..], ["greeting", "signature"]);
+        assert_eq!(
+            helpers.last().unwrap().1,
+            "User-defined snippet, used as `{{> signature}}`",
+        );
+        Ok(())
     }
+}
 
-    mod uid {
+#[cfg(test)]
+mod render_with_overlay {
+    use std::str::FromStr;
+
+    use crate::{
+        config::DTConfig,
+        registry::{Register, Registry},
+        syncing::expand,
+        utils::testing::{get_testroot, prepare_directory, prepare_file},
+    };
+
+    use color_eyre::Report;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn different_overlays() -> Result<(), Report> {
+        let base = prepare_directory(
+            get_testroot("render_with_overlay").join("different_overlays"),
+            0o755,
+        )?;
+        let src_name = "template";
+        let template_path = prepare_file(base.join(src_name), 0o644)?;
+        let target = prepare_directory(base.join("target"), 0o755)?;
+        let config = expand(DTConfig::from_str(&format!(
+            r#"
+[context.theme]
+name = "gruvbox"
+variant = "dark"
+
+[[local]]
+name = "theme"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+            base.display(),
+            target.display(),
+            src_name,
+        ))?)?;
+        std::fs::write(&template_path, "{{theme.name}}-{{theme.variant}}")?;
+        let name = template_path.to_string_lossy();
+        let reg = Registry::default().register_helpers()?.load(&config)?;
+
+        assert_eq!(
+            reg.render_with_overlay(&name, &serde_json::json!({"theme": {"variant": "light"}}))?,
+            b"gruvbox-light",
+        );
+        assert_eq!(
+            reg.render_with_overlay(&name, &serde_json::json!({"theme": {"name": "nord"}}))?,
+            b"nord-dark",
+        );
+        // The cached contents stay untouched
+        assert_eq!(reg.get(&name)?, b"gruvbox-dark");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod render_limits {
+    use std::str::FromStr;
+
+    use crate::{
+        config::DTConfig,
+        error::Error as AppError,
+        registry::{Register, Registry},
+        syncing::expand,
+        utils::testing::{get_testroot, prepare_directory, prepare_file},
+    };
+
+    use color_eyre::Report;
+    use pretty_assertions::assert_eq;
+
+    fn load(
+        name: &str,
+        template: &str,
+        limit: usize,
+    ) -> Result<Result<Registry<'static>, AppError>, Report> {
+        let base = prepare_directory(get_testroot("render_limits").join(name), 0o755)?;
+        let template_path = prepare_file(base.join("template"), 0o644)?;
+        let target = prepare_directory(base.join("target"), 0o755)?;
+        let config = expand(DTConfig::from_str(&format!(
+            r#"
+[global]
+max_render_size = {}
+
+[[local]]
+name = "limited"
+base = "{}"
+target = "{}"
+sources = ["template"]
+"#,
+            limit,
+            base.display(),
+            target.display(),
+        ))?)?;
+        std::fs::write(template_path, template)?;
+        Ok(Registry::default().register_helpers()?.load(&config))
+    }
+
+    #[test]
+    fn within_size() -> Result<(), Report> {
+        let base = get_testroot("render_limits").join("within_size");
+        let reg = load("within_size", "{{#repeat 3}}ab{{/repeat}}", 6)??;
+        assert_eq!(
+            reg.get(&base.join("template").to_string_lossy())?,
+            b"ababab",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn exceeds_size() -> Result<(), Report> {
+        let base = get_testroot("render_limits").join("exceeds_size");
+        let err = load(
+            "exceeds_size",
+            "{{#repeat 1000000000000}}runaway{{/repeat}}",
+            1024,
+        )?
+        .unwrap_err();
+        assert_eq!(
+            err,
+            AppError::RenderingError(format!(
+                "rendering '{}' exceeds the maximum size of 1024 bytes",
+                base.join("template").display(),
+            )),
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod block_helpers {
+    mod user {
         use std::str::FromStr;
 
         use crate::{
@@ -399,11 +668,11 @@ sources = ["{}"]
         use pretty_assertions::assert_eq;
 
         #[test]
-        fn if_uid_exact() -> Result<(), Report> {
+        fn if_user_exact() -> Result<(), Report> {
             let base = prepare_directory(
                 get_testroot("block_helpers")
-                    .join("uid")
-                    .join("if_uid_exact"),
+                    .join("user")
+                    .join("if_user_exact"),
                 0o755,
             )?;
             let src_name = "template";
@@ -412,7 +681,7 @@ sources = ["{}"]
             let config = expand(DTConfig::from_str(&format!(
                 r#"
 [[local]]
-name = "uid"
+name = "user"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -421,24 +690,23 @@ sources = ["{}"]
                 target.display(),
                 src_name,
             ))?)?;
-
             std::fs::write(
                 &template_path,
-                "Hi, {{#if_uid 418}}teapot{{else}}random user{{/if_uid}}",
+                r#"Hi, {{#if_user "luke"}}Luke Skywalker{{else}}random person{{/if_user}}!"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "Hi, teapot",
+                "Hi, Luke Skywalker!",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
 
             let config = expand(DTConfig::from_str(&format!(
                 r#"
-[context.uid]
-number = 418
+[context.user]
+name = "luke"
 
 [[local]]
-name = "uid"
+name = "user"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -449,20 +717,22 @@ sources = ["{}"]
             ))?)?;
             std::fs::write(
                 &template_path,
-                r#"Hello {{#if_uid uid.number}}teapot{{else}}there{{/if_uid}}"#,
+                r#"Welcome back, {{#if_user user.name}}Luke Skywalker{{else}}random person{{/if_user}}!"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "Hello teapot",
+                "Welcome back, Luke Skywalker!",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
             Ok(())
         }
 
         #[test]
-        fn if_uid_any() -> Result<(), Report> {
+        fn if_user_any() -> Result<(), Report> {
             let base = prepare_directory(
-                get_testroot("block_helpers").join("uid").join("if_uid_any"),
+                get_testroot("block_helpers")
+                    .join("user")
+                    .join("if_user_any"),
                 0o755,
             )?;
             let src_name = "template";
@@ -470,11 +740,8 @@ sources = ["{}"]
             let target = prepare_directory(base.join("target"), 0o755)?;
             let config = expand(DTConfig::from_str(&format!(
                 r#"
-[context]
-uid = true
-
 [[local]]
-name = "uid"
+name = "user"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -486,22 +753,24 @@ sources = ["{}"]
 
             std::fs::write(
                 &template_path,
-                r#"Hi, {{#if_uid "410,412,418"}}user#410/412/418{{else}}random person{{/if_uid}}!"#,
+                r#"Hi, {{#if_user "luke,skywalker"}}Luke Skywalker{{else}}random person{{/if_user}}!"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "Hi, user#410/412/418!",
+                "Hi, Luke Skywalker!",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
 
-            // Match inverse block
             let config = expand(DTConfig::from_str(&format!(
                 r#"
-[context.uid]
-allowed_numbers = [1000, 1001]
+[context.user]
+allowed_names = [
+    "skywalker",
+    "luke",
+]
 
 [[local]]
-name = "uid"
+name = "user"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -512,22 +781,22 @@ sources = ["{}"]
             ))?)?;
             std::fs::write(
                 &template_path,
-                r#"You are {{#if_uid uid.allowed_numbers}}{{else}}not {{/if_uid}}user#1000/1001"#,
+                "Welcome back, {{#if_user user.allowed_names}}Luke Skywalker{{else}}random person{{/if_user}}!",
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "You are not user#1000/1001",
+                "Welcome back, Luke Skywalker!",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
             Ok(())
         }
 
         #[test]
-        fn unless_uid_exact() -> Result<(), Report> {
+        fn unless_user_exact() -> Result<(), Report> {
             let base = prepare_directory(
                 get_testroot("block_helpers")
-                    .join("uid")
-                    .join("unless_uid_exact"),
+                    .join("user")
+                    .join("unless_user_exact"),
                 0o755,
             )?;
             let src_name = "template";
@@ -536,7 +805,7 @@ sources = ["{}"]
             let config = expand(DTConfig::from_str(&format!(
                 r#"
 [[local]]
-name = "uid"
+name = "user"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -545,24 +814,23 @@ sources = ["{}"]
                 target.display(),
                 src_name,
             ))?)?;
-
             std::fs::write(
                 &template_path,
-                "Hi, {{#unless_uid 418}}random user{{else}}teapot{{/unless_uid}}",
+                r#"Hi, {{#unless_user "luke"}}random person{{else}}Luke Skywalker{{/unless_user}}!"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "Hi, teapot",
+                "Hi, Luke Skywalker!",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
 
             let config = expand(DTConfig::from_str(&format!(
                 r#"
-[context.uid]
-number = 418
+[context.user]
+name = "luke"
 
 [[local]]
-name = "uid"
+name = "user"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -573,22 +841,22 @@ sources = ["{}"]
             ))?)?;
             std::fs::write(
                 &template_path,
-                r#"Hello {{#if_uid uid.number}}teapot{{else}}there{{/if_uid}}"#,
+                "Welcome back, {{#unless_user user.name}}random person{{else}}Luke Skywalker{{/unless_user}}!",
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "Hello teapot",
+                "Welcome back, Luke Skywalker!",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
             Ok(())
         }
 
         #[test]
-        fn unless_uid_any() -> Result<(), Report> {
+        fn unless_user_any() -> Result<(), Report> {
             let base = prepare_directory(
                 get_testroot("block_helpers")
-                    .join("uid")
-                    .join("unless_uid_any"),
+                    .join("user")
+                    .join("unless_user_any"),
                 0o755,
             )?;
             let src_name = "template";
@@ -597,7 +865,7 @@ sources = ["{}"]
             let config = expand(DTConfig::from_str(&format!(
                 r#"
 [[local]]
-name = "uid"
+name = "user"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -609,21 +877,24 @@ sources = ["{}"]
 
             std::fs::write(
                 &template_path,
-                r#"You {{#unless_uid "410,412,418"}}can't{{else}}might{{/unless_uid}} be a teapot"#,
+                r#"Hi, {{#unless_user "luke,skywalker"}}random person{{else}}Luke Skywalker{{/unless_user}}!"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "You might be a teapot",
+                "Hi, Luke Skywalker!",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
 
             let config = expand(DTConfig::from_str(&format!(
                 r#"
-[context.uid]
-allowed_numbers = [410, 418, 412, 416]
+[context.user]
+allowed_names = [
+    "luke",
+    "skywalker"
+]
 
 [[local]]
-name = "uid"
+name = "user"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -634,18 +905,18 @@ sources = ["{}"]
             ))?)?;
             std::fs::write(
                 &template_path,
-                r#"You {{#unless_uid uid.allowed_numbers}}can't{{else}}might{{/unless_uid}} be a teapot"#,
+                r#"Welcome back, {{#unless_user user.allowed_names}}random person{{else}}Luke Skywalker{{/unless_user}}!"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "You might be a teapot",
+                "Welcome back, Luke Skywalker!",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
             Ok(())
         }
     }
 
-    mod host {
+    mod uid {
         use std::str::FromStr;
 
         use crate::{
@@ -659,11 +930,11 @@ sources = ["{}"]
         use pretty_assertions::assert_eq;
 
         #[test]
-        fn if_host_exact() -> Result<(), Report> {
+        fn if_uid_exact() -> Result<(), Report> {
             let base = prepare_directory(
                 get_testroot("block_helpers")
-                    .join("host")
-                    .join("if_host_exact"),
+                    .join("uid")
+                    .join("if_uid_exact"),
                 0o755,
             )?;
             let src_name = "template";
@@ -672,7 +943,7 @@ sources = ["{}"]
             let config = expand(DTConfig::from_str(&format!(
                 r#"
 [[local]]
-name = "host"
+name = "uid"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -684,21 +955,21 @@ sources = ["{}"]
 
             std::fs::write(
                 &template_path,
-                r#"I have {{#if_host "c-3po"}}a bad{{else}}beep boop bop{{/if_host}} feeling about this"#,
+                "Hi, {{#if_uid 418}}teapot{{else}}random user{{/if_uid}}",
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "I have beep boop bop feeling about this",
+                "Hi, teapot",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
 
             let config = expand(DTConfig::from_str(&format!(
                 r#"
-[context.host]
-name = "r2d2"
+[context.uid]
+number = 418
 
 [[local]]
-name = "host"
+name = "uid"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -709,22 +980,20 @@ sources = ["{}"]
             ))?)?;
             std::fs::write(
                 &template_path,
-                r#"This is a {{#if_host host.name}}blue-white{{else}}golden{{/if_host}} one"#,
+                r#"Hello {{#if_uid uid.number}}teapot{{else}}there{{/if_uid}}"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "This is a blue-white one",
+                "Hello teapot",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
             Ok(())
         }
 
         #[test]
-        fn if_host_any() -> Result<(), Report> {
+        fn if_uid_any() -> Result<(), Report> {
             let base = prepare_directory(
-                get_testroot("block_helpers")
-                    .join("host")
-                    .join("if_host_any"),
+                get_testroot("block_helpers").join("uid").join("if_uid_any"),
                 0o755,
             )?;
             let src_name = "template";
@@ -732,8 +1001,11 @@ sources = ["{}"]
             let target = prepare_directory(base.join("target"), 0o755)?;
             let config = expand(DTConfig::from_str(&format!(
                 r#"
+[context]
+uid = true
+
 [[local]]
-name = "host"
+name = "uid"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -745,24 +1017,22 @@ sources = ["{}"]
 
             std::fs::write(
                 &template_path,
-                r#"I {{#if_host "c-3po,r2d2"}}{{else}}don't {{/if_host}}know Luke"#,
+                r#"Hi, {{#if_uid "410,412,418"}}user#410/412/418{{else}}random person{{/if_uid}}!"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "I know Luke",
+                "Hi, user#410/412/418!",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
 
+            // Match inverse block
             let config = expand(DTConfig::from_str(&format!(
                 r#"
-[context.host]
-allowed_names = [
-    "r2d2",
-    "bb8",
-]
+[context.uid]
+allowed_numbers = [1000, 1001]
 
 [[local]]
-name = "host"
+name = "uid"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -773,22 +1043,22 @@ sources = ["{}"]
             ))?)?;
             std::fs::write(
                 &template_path,
-                r#"I have {{#if_host host.allowed_names}}beep boop bop{{else}}a bad{{/if_host}} feeling about this"#,
+                r#"You are {{#if_uid uid.allowed_numbers}}{{else}}not {{/if_uid}}user#1000/1001"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "I have beep boop bop feeling about this",
+                "You are not user#1000/1001",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
             Ok(())
         }
 
         #[test]
-        fn unless_host_exact() -> Result<(), Report> {
+        fn unless_uid_exact() -> Result<(), Report> {
             let base = prepare_directory(
                 get_testroot("block_helpers")
-                    .join("host")
-                    .join("unless_host_exact"),
+                    .join("uid")
+                    .join("unless_uid_exact"),
                 0o755,
             )?;
             let src_name = "template";
@@ -797,7 +1067,7 @@ sources = ["{}"]
             let config = expand(DTConfig::from_str(&format!(
                 r#"
 [[local]]
-name = "host"
+name = "uid"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -809,21 +1079,21 @@ sources = ["{}"]
 
             std::fs::write(
                 &template_path,
-                r#"I have {{#unless_host "c-3po"}}beep boop bop{{else}}a bad{{/unless_host}} feeling about this"#,
+                "Hi, {{#unless_uid 418}}random user{{else}}teapot{{/unless_uid}}",
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "I have beep boop bop feeling about this",
+                "Hi, teapot",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
 
             let config = expand(DTConfig::from_str(&format!(
                 r#"
-[context.host]
-name = "r2d2"
+[context.uid]
+number = 418
 
 [[local]]
-name = "host"
+name = "uid"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -834,22 +1104,22 @@ sources = ["{}"]
             ))?)?;
             std::fs::write(
                 &template_path,
-                r#"This is a {{#unless_host host.name}}golden{{else}}blue-white{{/unless_host}} one"#,
+                r#"Hello {{#if_uid uid.number}}teapot{{else}}there{{/if_uid}}"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "This is a blue-white one",
+                "Hello teapot",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
             Ok(())
         }
 
         #[test]
-        fn unless_host_any() -> Result<(), Report> {
+        fn unless_uid_any() -> Result<(), Report> {
             let base = prepare_directory(
                 get_testroot("block_helpers")
-                    .join("host")
-                    .join("unless_host_any"),
+                    .join("uid")
+                    .join("unless_uid_any"),
                 0o755,
             )?;
             let src_name = "template";
@@ -858,7 +1128,7 @@ sources = ["{}"]
             let config = expand(DTConfig::from_str(&format!(
                 r#"
 [[local]]
-name = "host"
+name = "uid"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -870,24 +1140,21 @@ sources = ["{}"]
 
             std::fs::write(
                 &template_path,
-                r#"I {{#unless_host "c-3po,r2d2"}}don't {{/unless_host}}know Luke"#,
+                r#"You {{#unless_uid "410,412,418"}}can't{{else}}might{{/unless_uid}} be a teapot"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "I know Luke",
+                "You might be a teapot",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
 
             let config = expand(DTConfig::from_str(&format!(
                 r#"
-[context.host]
-allowed_names = [
-    "r2d2",
-    "bb8",
-]
+[context.uid]
+allowed_numbers = [410, 418, 412, 416]
 
 [[local]]
-name = "host"
+name = "uid"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -898,19 +1165,18 @@ sources = ["{}"]
             ))?)?;
             std::fs::write(
                 &template_path,
-                r#"I have {{#unless_host host.allowed_names}}a bad{{else}}beep boop bop{{/unless_host}} feeling about this"#,
+                r#"You {{#unless_uid uid.allowed_numbers}}can't{{else}}might{{/unless_uid}} be a teapot"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
-            println!("{:?}", crate::utils::testing::gethostname());
             assert_eq!(
-                "I have beep boop bop feeling about this",
+                "You might be a teapot",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
             Ok(())
         }
     }
 
-    mod os {
+    mod error_location {
         use std::str::FromStr;
 
         use crate::{
@@ -920,22 +1186,23 @@ sources = ["{}"]
             utils::testing::{get_testroot, prepare_directory, prepare_file},
         };
 
-        use color_eyre::Report;
-        use pretty_assertions::assert_eq;
+        use color_eyre::{eyre::eyre, Report};
 
         #[test]
-        fn if_os_exact() -> Result<(), Report> {
+        fn nested_misuse_names_template() -> Result<(), Report> {
             let base = prepare_directory(
-                get_testroot("block_helpers").join("os").join("if_os_exact"),
+                get_testroot("block_helpers")
+                    .join("error_location")
+                    .join("nested_misuse_names_template"),
                 0o755,
             )?;
-            let src_name = "template";
+            let src_name = "misused";
             let template_path = prepare_file(base.join(src_name), 0o644)?;
             let target = prepare_directory(base.join("target"), 0o755)?;
             let config = expand(DTConfig::from_str(&format!(
                 r#"
 [[local]]
-name = "os"
+name = "error location"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -944,24 +1211,64 @@ sources = ["{}"]
                 target.display(),
                 src_name,
             ))?)?;
-
             std::fs::write(
                 &template_path,
-                r#"{{#if_os "id" "dt"}}It works{{else}}Nope it's not working{{/if_os}}"#,
+                r#"first line
+{{#if_user "luke"}}
+{{#if_os}}..foo..{{/if_os}}
+{{/if_user}}"#,
             )?;
-            let reg = Registry::default().register_helpers()?.load(&config)?;
-            assert_eq!(
-                "It works",
-                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
-            );
+            match Registry::default().register_helpers()?.load(&config) {
+                Ok(_) => Err(eyre!(
+                    "Misused helper in a nested block should fail the rendering"
+                )),
+                Err(e) => {
+                    let msg = e.to_string();
+                    assert!(
+                        msg.contains(&format!("\"{}\"", template_path.display())),
+                        "{}",
+                        msg,
+                    );
+                    assert!(msg.contains("line 3"), "{}", msg);
+                    assert!(msg.contains("Block helper `#if_os`"), "{}", msg);
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    mod int_cmp {
+        use std::str::FromStr;
+
+        use crate::{
+            config::DTConfig,
+            registry::{Register, Registry},
+            syncing::expand,
+            utils::testing::{get_testroot, prepare_directory, prepare_file},
+        };
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
 
+        #[test]
+        fn if_int_cmp() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers")
+                    .join("int_cmp")
+                    .join("if_int_cmp"),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
             let config = expand(DTConfig::from_str(&format!(
                 r#"
-[context.os]
-version = "latest"
+[context.version]
+major = 3
+minor = "14"
 
 [[local]]
-name = "host"
+name = "int_cmp"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -972,20 +1279,51 @@ sources = ["{}"]
             ))?)?;
             std::fs::write(
                 &template_path,
-                r#"{{#if_os "version" os.version}}It works{{else}}Not working{{/if_os}}"#,
+                r#"{{#if_int_cmp version.major ">" 2}}yes{{else}}no{{/if_int_cmp}}
+{{#if_int_cmp version.major ">" 3}}yes{{else}}no{{/if_int_cmp}}
+{{#if_int_cmp version.major ">=" 3}}yes{{else}}no{{/if_int_cmp}}
+{{#if_int_cmp version.major ">=" 4}}yes{{else}}no{{/if_int_cmp}}
+{{#if_int_cmp version.minor "<" 15}}yes{{else}}no{{/if_int_cmp}}
+{{#if_int_cmp version.minor "<" 14}}yes{{else}}no{{/if_int_cmp}}
+{{#if_int_cmp version.minor "<=" 14}}yes{{else}}no{{/if_int_cmp}}
+{{#if_int_cmp version.minor "<=" 13}}yes{{else}}no{{/if_int_cmp}}
+{{#if_int_cmp uid "==" 418}}yes{{else}}no{{/if_int_cmp}}
+{{#if_int_cmp uid "==" 0}}yes{{else}}no{{/if_int_cmp}}
+{{#if_int_cmp version.major "!=" version.minor}}yes{{else}}no{{/if_int_cmp}}
+{{#if_int_cmp 3 "!=" version.major}}yes{{else}}no{{/if_int_cmp}}"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "It works",
+                "yes\nno\nyes\nno\nyes\nno\nyes\nno\nyes\nno\nyes\nno",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
+
+            std::fs::write(
+                &template_path,
+                r#"{{#if_int_cmp "teapot" ">" 1}}yes{{/if_int_cmp}}"#,
+            )?;
+            assert!(Registry::default()
+                .register_helpers()?
+                .load(&config)
+                .is_err());
+
+            std::fs::write(
+                &template_path,
+                r#"{{#if_int_cmp 2 "=~" 1}}yes{{/if_int_cmp}}"#,
+            )?;
+            assert!(Registry::default()
+                .register_helpers()?
+                .load(&config)
+                .is_err());
             Ok(())
         }
 
         #[test]
-        fn if_os_any() -> Result<(), Report> {
+        fn unless_int_cmp() -> Result<(), Report> {
             let base = prepare_directory(
-                get_testroot("block_helpers").join("os").join("if_os_any"),
+                get_testroot("block_helpers")
+                    .join("int_cmp")
+                    .join("unless_int_cmp"),
                 0o755,
             )?;
             let src_name = "template";
@@ -994,7 +1332,7 @@ sources = ["{}"]
             let config = expand(DTConfig::from_str(&format!(
                 r#"
 [[local]]
-name = "os"
+name = "int_cmp"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -1003,24 +1341,63 @@ sources = ["{}"]
                 target.display(),
                 src_name,
             ))?)?;
-
             std::fs::write(
                 &template_path,
-                r#"{{#if_os "id" "dummy-version,dt"}}It works{{else}}Nope it's not working{{/if_os}}"#,
+                r#"{{#unless_int_cmp 2 ">" 1}}yes{{else}}no{{/unless_int_cmp}}
+{{#unless_int_cmp 1 ">=" 2}}yes{{else}}no{{/unless_int_cmp}}
+{{#unless_int_cmp 1 "<" 2}}yes{{else}}no{{/unless_int_cmp}}
+{{#unless_int_cmp 2 "<=" 1}}yes{{else}}no{{/unless_int_cmp}}
+{{#unless_int_cmp uid "==" 418}}yes{{else}}no{{/unless_int_cmp}}
+{{#unless_int_cmp uid "!=" 418}}yes{{else}}no{{/unless_int_cmp}}"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "It works",
+                "no\nyes\nno\nyes\nno\nyes",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
 
+            std::fs::write(
+                &template_path,
+                r#"{{#unless_int_cmp 1 "<"}}yes{{/unless_int_cmp}}"#,
+            )?;
+            assert!(Registry::default()
+                .register_helpers()?
+                .load(&config)
+                .is_err());
+            Ok(())
+        }
+    }
+
+    mod range {
+        use std::str::FromStr;
+
+        use crate::{
+            config::DTConfig,
+            registry::{Register, Registry},
+            syncing::expand,
+            utils::testing::{get_testroot, prepare_directory, prepare_file, set_effective_uid},
+        };
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn if_range() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers").join("range").join("if_range"),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
             let config = expand(DTConfig::from_str(&format!(
                 r#"
-[context.os]
-version = ["0.99.99", "99.0.0"]
+[context.range]
+low = 400
+high = 500
 
 [[local]]
-name = "host"
+name = "range"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -1031,22 +1408,26 @@ sources = ["{}"]
             ))?)?;
             std::fs::write(
                 &template_path,
-                r#"{{#if_os "version_id" os.version}}It works{{else}}Not working{{/if_os}}"#,
+                r#"{{#if_range uid 400 500}}in{{else}}out{{/if_range}}
+{{#if_range uid 419 500}}in{{else}}out{{/if_range}}
+{{#if_range "uid" 1 417}}in{{else}}out{{/if_range}}
+{{#if_range uid range.low range.high}}in{{else}}out{{/if_range}}
+{{#if_range 418 418 "418"}}in{{else}}out{{/if_range}}"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "It works",
+                "in\nout\nout\nin\nin",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
             Ok(())
         }
 
         #[test]
-        fn unless_os_exact() -> Result<(), Report> {
+        fn unless_range() -> Result<(), Report> {
             let base = prepare_directory(
                 get_testroot("block_helpers")
-                    .join("os")
-                    .join("unless_os_exact"),
+                    .join("range")
+                    .join("unless_range"),
                 0o755,
             )?;
             let src_name = "template";
@@ -1055,7 +1436,7 @@ sources = ["{}"]
             let config = expand(DTConfig::from_str(&format!(
                 r#"
 [[local]]
-name = "os"
+name = "range"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -1064,21 +1445,119 @@ sources = ["{}"]
                 target.display(),
                 src_name,
             ))?)?;
-
             std::fs::write(
                 &template_path,
-                r##"{{#unless_os "build_id" "#somethingsomething"}}Nope it's not working properly{{else}}It's working{{/unless_os}}"##,
+                r#"{{#unless_range uid 400 500}}out{{else}}in{{/unless_range}}
+{{#unless_range uid 419 500}}out{{else}}in{{/unless_range}}
+{{#unless_range uid 1 417}}out{{else}}in{{/unless_range}}"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "It's working",
+                "in\nout\nout",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
 
-            let config = expand(DTConfig::from_str(&format!(
-                r#"
-[context.os]
-logo = "Buzz Lightyear"
+            std::fs::write(
+                &template_path,
+                r#"{{#unless_range "teapot" 1 417}}out{{/unless_range}}"#,
+            )?;
+            assert!(Registry::default()
+                .register_helpers()?
+                .load(&config)
+                .is_err());
+            Ok(())
+        }
+
+        #[test]
+        fn effective_uid() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers")
+                    .join("range")
+                    .join("effective_uid"),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "range"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(
+                &template_path,
+                r#"{{#if_range uid 0 0}}in{{else}}out{{/if_range}}
+{{#unless_range uid 400 500}}out{{else}}in{{/unless_range}}
+{{#if_int_cmp uid "==" 0}}yes{{else}}no{{/if_int_cmp}}"#,
+            )?;
+            set_effective_uid(0);
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "in\nout\nyes",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+            Ok(())
+        }
+    }
+
+    mod host {
+        use std::str::FromStr;
+
+        use crate::{
+            config::DTConfig,
+            registry::{Register, Registry},
+            syncing::expand,
+            utils::testing::{get_testroot, prepare_directory, prepare_file},
+        };
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn if_host_exact() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers")
+                    .join("host")
+                    .join("if_host_exact"),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "host"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+
+            std::fs::write(
+                &template_path,
+                r#"I have {{#if_host "c-3po"}}a bad{{else}}beep boop bop{{/if_host}} feeling about this"#,
+            )?;
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "I have beep boop bop feeling about this",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[context.host]
+name = "r2d2"
 
 [[local]]
 name = "host"
@@ -1092,22 +1571,22 @@ sources = ["{}"]
             ))?)?;
             std::fs::write(
                 &template_path,
-                r#"{{#unless_os "logo" os.logo}}Broken{{else}}Up and running{{/unless_os}}"#,
+                r#"This is a {{#if_host host.name}}blue-white{{else}}golden{{/if_host}} one"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "Up and running",
+                "This is a blue-white one",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
             Ok(())
         }
 
         #[test]
-        fn unless_os_any() -> Result<(), Report> {
+        fn if_host_any() -> Result<(), Report> {
             let base = prepare_directory(
                 get_testroot("block_helpers")
-                    .join("os")
-                    .join("unless_os_any"),
+                    .join("host")
+                    .join("if_host_any"),
                 0o755,
             )?;
             let src_name = "template";
@@ -1116,7 +1595,7 @@ sources = ["{}"]
             let config = expand(DTConfig::from_str(&format!(
                 r#"
 [[local]]
-name = "os"
+name = "host"
 base = "{}"
 target = "{}"
 sources = ["{}"]
@@ -1128,18 +1607,21 @@ sources = ["{}"]
 
             std::fs::write(
                 &template_path,
-                r##"{{#unless_os "home_url" "https://example.com/,https://github.com/blurgyy/dt/"}}Nope it's not working properly{{else}}It's working{{/unless_os}}"##,
+                r#"I {{#if_host "c-3po,r2d2"}}{{else}}don't {{/if_host}}know Luke"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "It's working",
+                "I know Luke",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
 
             let config = expand(DTConfig::from_str(&format!(
                 r#"
-[context.os.documentation]
-url = ["https://dt.cli.rs/", "https://github.com/blurgyy/dt/wiki/"]
+[context.host]
+allowed_names = [
+    "r2d2",
+    "bb8",
+]
 
 [[local]]
 name = "host"
@@ -1153,11 +1635,1467 @@ sources = ["{}"]
             ))?)?;
             std::fs::write(
                 &template_path,
-                r#"{{#unless_os "documentation_url" os.documentation.url}}xxxBroken{{else}}Up and running{{/unless_os}}"#,
+                r#"I have {{#if_host host.allowed_names}}beep boop bop{{else}}a bad{{/if_host}} feeling about this"#,
             )?;
             let reg = Registry::default().register_helpers()?.load(&config)?;
             assert_eq!(
-                "Up and running",
+                "I have beep boop bop feeling about this",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn unless_host_exact() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers")
+                    .join("host")
+                    .join("unless_host_exact"),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "host"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+
+            std::fs::write(
+                &template_path,
+                r#"I have {{#unless_host "c-3po"}}beep boop bop{{else}}a bad{{/unless_host}} feeling about this"#,
+            )?;
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "I have beep boop bop feeling about this",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[context.host]
+name = "r2d2"
+
+[[local]]
+name = "host"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(
+                &template_path,
+                r#"This is a {{#unless_host host.name}}golden{{else}}blue-white{{/unless_host}} one"#,
+            )?;
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "This is a blue-white one",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn unless_host_any() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers")
+                    .join("host")
+                    .join("unless_host_any"),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "host"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+
+            std::fs::write(
+                &template_path,
+                r#"I {{#unless_host "c-3po,r2d2"}}don't {{/unless_host}}know Luke"#,
+            )?;
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "I know Luke",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[context.host]
+allowed_names = [
+    "r2d2",
+    "bb8",
+]
+
+[[local]]
+name = "host"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(
+                &template_path,
+                r#"I have {{#unless_host host.allowed_names}}a bad{{else}}beep boop bop{{/unless_host}} feeling about this"#,
+            )?;
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            println!("{:?}", crate::utils::testing::gethostname());
+            assert_eq!(
+                "I have beep boop bop feeling about this",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+            Ok(())
+        }
+    }
+
+    mod os {
+        use std::str::FromStr;
+
+        use crate::{
+            config::DTConfig,
+            registry::{Register, Registry},
+            syncing::expand,
+            utils::testing::{get_testroot, prepare_directory, prepare_file},
+        };
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn if_os_exact() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers").join("os").join("if_os_exact"),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "os"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+
+            std::fs::write(
+                &template_path,
+                r#"{{#if_os "id" "dt"}}It works{{else}}Nope it's not working{{/if_os}}"#,
+            )?;
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "It works",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[context.os]
+version = "latest"
+
+[[local]]
+name = "host"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(
+                &template_path,
+                r#"{{#if_os "version" os.version}}It works{{else}}Not working{{/if_os}}"#,
+            )?;
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "It works",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn if_os_any() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers").join("os").join("if_os_any"),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "os"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+
+            std::fs::write(
+                &template_path,
+                r#"{{#if_os "id" "dummy-version,dt"}}It works{{else}}Nope it's not working{{/if_os}}"#,
+            )?;
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "It works",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[context.os]
+version = ["0.99.99", "99.0.0"]
+
+[[local]]
+name = "host"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(
+                &template_path,
+                r#"{{#if_os "version_id" os.version}}It works{{else}}Not working{{/if_os}}"#,
+            )?;
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "It works",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn unless_os_exact() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers")
+                    .join("os")
+                    .join("unless_os_exact"),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "os"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+
+            std::fs::write(
+                &template_path,
+                r##"{{#unless_os "build_id" "#somethingsomething"}}Nope it's not working properly{{else}}It's working{{/unless_os}}"##,
+            )?;
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "It's working",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[context.os]
+logo = "Buzz Lightyear"
+
+[[local]]
+name = "host"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(
+                &template_path,
+                r#"{{#unless_os "logo" os.logo}}Broken{{else}}Up and running{{/unless_os}}"#,
+            )?;
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "Up and running",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn unless_os_any() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers")
+                    .join("os")
+                    .join("unless_os_any"),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "os"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+
+            std::fs::write(
+                &template_path,
+                r##"{{#unless_os "home_url" "https://example.com/,https://github.com/blurgyy/dt/"}}Nope it's not working properly{{else}}It's working{{/unless_os}}"##,
+            )?;
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "It's working",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[context.os.documentation]
+url = ["https://dt.cli.rs/", "https://github.com/blurgyy/dt/wiki/"]
+
+[[local]]
+name = "host"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(
+                &template_path,
+                r#"{{#unless_os "documentation_url" os.documentation.url}}xxxBroken{{else}}Up and running{{/unless_os}}"#,
+            )?;
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "Up and running",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn if_os_family() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers")
+                    .join("os")
+                    .join("if_os_family"),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[context.os]
+families = ["arch", "DotfileTemplater"]
+
+[[local]]
+name = "os"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(
+                &template_path,
+                r#"{{#if_os_family "debian,DotfileTemplater"}}like{{else}}unlike{{/if_os_family}}
+{{#if_os_family "dt"}}like{{else}}unlike{{/if_os_family}}
+{{#if_os_family "debian,arch"}}like{{else}}unlike{{/if_os_family}}
+{{#if_os_family os.families}}like{{else}}unlike{{/if_os_family}}"#,
+            )?;
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "like\nlike\nunlike\nlike",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn unless_os_family() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers")
+                    .join("os")
+                    .join("unless_os_family"),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "os"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(
+                &template_path,
+                r#"{{#unless_os_family "DotfileTemplater"}}unlike{{else}}like{{/unless_os_family}}
+{{#unless_os_family "debian,arch"}}unlike{{else}}like{{/unless_os_family}}"#,
+            )?;
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "like\nunlike",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+            Ok(())
+        }
+    }
+
+    mod tty {
+        use std::str::FromStr;
+
+        use crate::{
+            config::DTConfig,
+            registry::{Register, Registry},
+            syncing::expand,
+            utils::testing::{get_testroot, prepare_directory, prepare_file, set_stdout_tty},
+        };
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn if_tty() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers").join("tty").join("if_tty"),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "tty"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(
+                &template_path,
+                r#"{{#if_tty}}colorful{{else}}plain{{/if_tty}}"#,
+            )?;
+
+            set_stdout_tty(true);
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "colorful",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+
+            set_stdout_tty(false);
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "plain",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn unless_tty() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers").join("tty").join("unless_tty"),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "tty"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(
+                &template_path,
+                r#"{{#unless_tty}}plain{{else}}colorful{{/unless_tty}}"#,
+            )?;
+
+            set_stdout_tty(true);
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "colorful",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+
+            set_stdout_tty(false);
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "plain",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+            Ok(())
+        }
+    }
+
+    mod container_runtime {
+        use std::str::FromStr;
+
+        use crate::{
+            config::DTConfig,
+            registry::{Register, Registry},
+            syncing::expand,
+            utils::testing::{
+                get_testroot, prepare_directory, prepare_file, set_container_runtime,
+            },
+        };
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        fn render(name: &str, template: &str, runtime: Option<&str>) -> Result<String, Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers")
+                    .join("container_runtime")
+                    .join(name),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[context.container]
+runtimes = ["podman", "docker"]
+
+[[local]]
+name = "container"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(&template_path, template)?;
+            set_container_runtime(runtime);
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            Ok(std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?.to_owned())
+        }
+
+        #[test]
+        fn if_container_runtime() -> Result<(), Report> {
+            let template = r#"{{#if_container_runtime "podman, docker"}}oci{{else}}other{{/if_container_runtime}}"#;
+            assert_eq!(render("if_podman", template, Some("podman"))?, "oci");
+            assert_eq!(render("if_docker", template, Some("docker"))?, "oci");
+            assert_eq!(render("if_lxc", template, Some("lxc"))?, "other");
+            assert_eq!(render("if_bare_metal", template, None)?, "other");
+
+            let template = r#"{{#if_container_runtime container.runtimes}}oci{{else}}other{{/if_container_runtime}}"#;
+            assert_eq!(render("if_array", template, Some("docker"))?, "oci");
+            Ok(())
+        }
+
+        #[test]
+        fn unless_container_runtime() -> Result<(), Report> {
+            let template = r#"{{#unless_container_runtime "lxc"}}not lxc{{else}}lxc{{/unless_container_runtime}}"#;
+            assert_eq!(render("unless_lxc", template, Some("lxc"))?, "lxc");
+            assert_eq!(
+                render("unless_podman", template, Some("podman"))?,
+                "not lxc"
+            );
+            assert_eq!(render("unless_bare_metal", template, None)?, "not lxc");
+            Ok(())
+        }
+    }
+
+    mod network {
+        use std::str::FromStr;
+
+        use crate::{
+            config::DTConfig,
+            registry::{Register, Registry},
+            syncing::expand,
+            utils::testing::{get_testroot, prepare_directory, prepare_file, set_reachable},
+        };
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        fn render(name: &str, template: &str, reachable: bool) -> Result<String, Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers").join("network").join(name),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "network"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(&template_path, template)?;
+            set_reachable(reachable);
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            Ok(std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?.to_owned())
+        }
+
+        #[test]
+        fn if_network() -> Result<(), Report> {
+            let template = r#"{{#if_network}}online{{else}}offline{{/if_network}}"#;
+            assert_eq!(render("if_online", template, true)?, "online");
+            assert_eq!(render("if_offline", template, false)?, "offline");
+
+            let template =
+                r#"{{#if_network "example.com:443" 300}}online{{else}}offline{{/if_network}}"#;
+            assert_eq!(render("if_probe_target", template, true)?, "online");
+            Ok(())
+        }
+
+        #[test]
+        fn unless_network() -> Result<(), Report> {
+            let template = r#"{{#unless_network}}offline{{else}}online{{/unless_network}}"#;
+            assert_eq!(render("unless_online", template, true)?, "online");
+            assert_eq!(render("unless_offline", template, false)?, "offline");
+            Ok(())
+        }
+
+        #[test]
+        fn invalid_timeout() {
+            assert!(render(
+                "invalid_timeout",
+                r#"{{#if_network "example.com:443" "soon"}}online{{/if_network}}"#,
+                true,
+            )
+            .is_err());
+        }
+    }
+
+    mod battery {
+        use std::str::FromStr;
+
+        use crate::{
+            config::DTConfig,
+            registry::{Register, Registry},
+            syncing::expand,
+            utils::testing::{get_testroot, prepare_directory, prepare_file, set_on_battery},
+        };
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        fn render(name: &str, template: &str, on_battery: bool) -> Result<String, Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers").join("battery").join(name),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "battery"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(&template_path, template)?;
+            set_on_battery(on_battery);
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            Ok(std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?.to_owned())
+        }
+
+        #[test]
+        fn if_on_battery() -> Result<(), Report> {
+            let template = r#"interval={{#if_on_battery}}30{{else}}5{{/if_on_battery}}"#;
+            assert_eq!(render("if_battery", template, true)?, "interval=30");
+            assert_eq!(render("if_ac", template, false)?, "interval=5");
+            Ok(())
+        }
+
+        #[test]
+        fn unless_on_battery() -> Result<(), Report> {
+            let template = r#"{{#unless_on_battery}}animations{{/unless_on_battery}}"#;
+            assert_eq!(render("unless_battery", template, true)?, "");
+            assert_eq!(render("unless_ac", template, false)?, "animations");
+            Ok(())
+        }
+    }
+
+    mod desktop {
+        use std::{str::FromStr, sync::Mutex};
+
+        use crate::{
+            config::DTConfig,
+            registry::{Register, Registry},
+            syncing::expand,
+            utils::testing::{get_testroot, prepare_directory, prepare_file},
+        };
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        /// Serializes tests that modify the desktop environment variables.
+        static ENV: Mutex<()> = Mutex::new(());
+
+        fn render(
+            name: &str,
+            template: &str,
+            xdg_current_desktop: Option<&str>,
+        ) -> Result<String, Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers").join("desktop").join(name),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "desktop"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(&template_path, template)?;
+            let _guard = ENV.lock().unwrap_or_else(|e| e.into_inner());
+            std::env::remove_var("DESKTOP_SESSION");
+            match xdg_current_desktop {
+                Some(desktop) => std::env::set_var("XDG_CURRENT_DESKTOP", desktop),
+                None => std::env::remove_var("XDG_CURRENT_DESKTOP"),
+            }
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            Ok(std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?.to_owned())
+        }
+
+        #[test]
+        fn if_desktop() -> Result<(), Report> {
+            let template = r#"{{#if_desktop "gnome,kde"}}gtk{{else}}other{{/if_desktop}}"#;
+            assert_eq!(render("if_gnome", template, Some("GNOME"))?, "gtk");
+            assert_eq!(render("if_ubuntu", template, Some("ubuntu:GNOME"))?, "gtk");
+            assert_eq!(render("if_sway", template, Some("sway"))?, "other");
+            assert_eq!(render("if_none", template, None)?, "other");
+            Ok(())
+        }
+
+        #[test]
+        fn unless_desktop() -> Result<(), Report> {
+            let template = r#"{{#unless_desktop "KDE"}}no-plasma{{/unless_desktop}}"#;
+            assert_eq!(render("unless_kde", template, Some("kde"))?, "");
+            assert_eq!(render("unless_sway", template, Some("sway"))?, "no-plasma");
+            Ok(())
+        }
+    }
+
+    mod env {
+        use std::str::FromStr;
+
+        use crate::{
+            config::DTConfig,
+            registry::{Register, Registry},
+            syncing::expand,
+            utils::testing::{get_testroot, prepare_directory, prepare_file},
+        };
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        /// Renders `template` with the environment variable `var` set to
+        /// `value`, or unset when `value` is `None`.  Each test uses its own
+        /// variable, so that tests running in parallel do not interfere.
+        fn render(
+            name: &str,
+            template: &str,
+            var: &str,
+            value: Option<&str>,
+        ) -> Result<String, Report> {
+            let base =
+                prepare_directory(get_testroot("block_helpers").join("env").join(name), 0o755)?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "env"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(&template_path, template)?;
+            match value {
+                Some(value) => std::env::set_var(var, value),
+                None => std::env::remove_var(var),
+            }
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            Ok(std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?.to_owned())
+        }
+
+        #[test]
+        fn if_env() -> Result<(), Report> {
+            let var = "DT_TEST_IF_ENV_TERM";
+            let template = r#"{{#if_env "DT_TEST_IF_ENV_TERM" "xterm-kitty, foot"}}truecolor{{else}}256{{/if_env}}"#;
+            assert_eq!(
+                render("if_kitty", template, var, Some("xterm-kitty"))?,
+                "truecolor"
+            );
+            assert_eq!(render("if_foot", template, var, Some("foot"))?, "truecolor");
+            assert_eq!(render("if_xterm", template, var, Some("xterm"))?, "256");
+            assert_eq!(render("if_unset", template, var, None)?, "256");
+            Ok(())
+        }
+
+        #[test]
+        fn unless_env() -> Result<(), Report> {
+            let var = "DT_TEST_UNLESS_ENV_TERM";
+            let template =
+                r#"{{#unless_env "DT_TEST_UNLESS_ENV_TERM" "linux"}}unicode{{/unless_env}}"#;
+            assert_eq!(render("unless_linux", template, var, Some("linux"))?, "");
+            assert_eq!(
+                render("unless_foot", template, var, Some("foot"))?,
+                "unicode"
+            );
+            assert_eq!(render("unless_unset", template, var, None)?, "unicode");
+            Ok(())
+        }
+
+        #[test]
+        fn wrong_arguments() -> Result<(), Report> {
+            let var = "DT_TEST_IF_ENV_WRONG";
+            let err = render(
+                "wrong_arguments",
+                r#"{{#if_env "DT_TEST_IF_ENV_WRONG"}}x{{/if_env}}"#,
+                var,
+                None,
+            )
+            .unwrap_err();
+            assert!(err
+                .to_string()
+                .contains("expected exactly 2 arguments, 1 found"));
+            Ok(())
+        }
+    }
+
+    mod locale {
+        use std::{str::FromStr, sync::Mutex};
+
+        use crate::{
+            config::DTConfig,
+            registry::{Register, Registry},
+            syncing::expand,
+            utils::testing::{get_testroot, prepare_directory, prepare_file},
+        };
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        /// Serializes tests that modify the locale environment variables.
+        static ENV: Mutex<()> = Mutex::new(());
+
+        fn render(
+            name: &str,
+            template: &str,
+            lc_all: Option<&str>,
+            lang: Option<&str>,
+        ) -> Result<String, Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers").join("locale").join(name),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "locale"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(&template_path, template)?;
+            let _guard = ENV.lock().unwrap_or_else(|e| e.into_inner());
+            for (var, value) in [("LC_ALL", lc_all), ("LANG", lang)] {
+                match value {
+                    Some(value) => std::env::set_var(var, value),
+                    None => std::env::remove_var(var),
+                }
+            }
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            Ok(std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?.to_owned())
+        }
+
+        #[test]
+        fn if_locale() -> Result<(), Report> {
+            let template = r#"{{#if_locale "en_US,en_GB"}}mdy{{else}}other{{/if_locale}}"#;
+            assert_eq!(render("if_us", template, None, Some("en_US.UTF-8"))?, "mdy");
+            assert_eq!(render("if_gb", template, None, Some("en_GB"))?, "mdy");
+            assert_eq!(
+                render("if_au", template, None, Some("en_AU.UTF-8"))?,
+                "other"
+            );
+            assert_eq!(render("if_none", template, None, None)?, "other");
+            Ok(())
+        }
+
+        #[test]
+        fn prefix() -> Result<(), Report> {
+            let template = r#"{{#if_locale "en"}}english{{else}}other{{/if_locale}}"#;
+            assert_eq!(render("prefix_en", template, None, Some("en"))?, "english");
+            assert_eq!(
+                render("prefix_en_us", template, None, Some("en_US.UTF-8"))?,
+                "english",
+            );
+            assert_eq!(
+                render("prefix_eo", template, None, Some("eo.UTF-8"))?,
+                "other"
+            );
+            // Only whole components are matched
+            assert_eq!(render("prefix_enx", template, None, Some("enx"))?, "other");
+            Ok(())
+        }
+
+        #[test]
+        fn lc_all_precedence() -> Result<(), Report> {
+            let template = r#"{{#if_locale "de"}}german{{else}}other{{/if_locale}}"#;
+            assert_eq!(
+                render(
+                    "lc_all_set",
+                    template,
+                    Some("de_DE.UTF-8"),
+                    Some("en_US.UTF-8")
+                )?,
+                "german",
+            );
+            assert_eq!(
+                render("lc_all_empty", template, Some(""), Some("de_DE.UTF-8"))?,
+                "german",
+            );
+            assert_eq!(
+                render("lc_all_other", template, Some("C"), Some("de_DE.UTF-8"))?,
+                "other",
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn unless_locale() -> Result<(), Report> {
+            let template = r#"{{#unless_locale "zh"}}latin{{/unless_locale}}"#;
+            assert_eq!(
+                render("unless_zh", template, None, Some("zh_CN.UTF-8"))?,
+                ""
+            );
+            assert_eq!(render("unless_fr", template, None, Some("fr_FR"))?, "latin");
+            Ok(())
+        }
+    }
+
+    mod root {
+        use std::str::FromStr;
+
+        use crate::{
+            config::DTConfig,
+            registry::{Register, Registry},
+            syncing::expand,
+            utils::testing::{get_testroot, prepare_directory, prepare_file, set_effective_uid},
+        };
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        fn render(name: &str, template: &str, euid: users::uid_t) -> Result<String, Report> {
+            let base =
+                prepare_directory(get_testroot("block_helpers").join("root").join(name), 0o755)?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "root"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(&template_path, template)?;
+            set_effective_uid(euid);
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            Ok(std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?.to_owned())
+        }
+
+        #[test]
+        fn if_root() -> Result<(), Report> {
+            let template = r#"{{#if_root}}/etc{{else}}~/.config{{/if_root}}"#;
+            assert_eq!(render("if_root", template, 0)?, "/etc");
+            assert_eq!(render("if_user", template, 418)?, "~/.config");
+            Ok(())
+        }
+
+        #[test]
+        fn unless_root() -> Result<(), Report> {
+            let template = r#"{{#unless_root}}user-only{{/unless_root}}"#;
+            assert_eq!(render("unless_root", template, 0)?, "");
+            assert_eq!(render("unless_user", template, 418)?, "user-only");
+            Ok(())
+        }
+    }
+
+    mod kernel {
+        use std::str::FromStr;
+
+        use crate::{
+            config::DTConfig,
+            registry::{Register, Registry},
+            syncing::expand,
+            utils::testing::{get_testroot, prepare_directory, prepare_file, set_kernel_release},
+        };
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        fn render(name: &str, template: &str, release: Option<&str>) -> Result<String, Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers").join("kernel").join(name),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "kernel"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(&template_path, template)?;
+            set_kernel_release(release);
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            Ok(std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?.to_owned())
+        }
+
+        #[test]
+        fn if_kernel() -> Result<(), Report> {
+            let template = r#"{{#if_kernel ">=6.1"}}new{{else}}old{{/if_kernel}}"#;
+            assert_eq!(render("if_newer", template, Some("6.5.9-arch2-1"))?, "new");
+            assert_eq!(render("if_same", template, Some("6.1"))?, "new");
+            assert_eq!(
+                render("if_older", template, Some("5.15.0-91-generic"))?,
+                "old"
+            );
+            assert_eq!(render("if_unknown", template, None)?, "old");
+            assert_eq!(
+                render(
+                    "if_default_op",
+                    r#"{{#if_kernel "6.1.2"}}yes{{/if_kernel}}"#,
+                    Some("6.1.2")
+                )?,
+                "yes",
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn unless_kernel() -> Result<(), Report> {
+            let template = r#"{{#unless_kernel ">=6.1"}}legacy{{/unless_kernel}}"#;
+            assert_eq!(render("unless_newer", template, Some("6.1.12"))?, "");
+            assert_eq!(render("unless_older", template, Some("6.0.19"))?, "legacy");
+            Ok(())
+        }
+    }
+
+    mod passwd {
+        use std::str::FromStr;
+
+        use crate::{
+            config::DTConfig,
+            registry::{Register, Registry},
+            syncing::expand,
+            utils::testing::{get_testroot, prepare_directory, prepare_file, set_passwd_field},
+        };
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        fn render(name: &str, template: &str, shell: &str) -> Result<String, Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers").join("passwd").join(name),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "passwd"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(&template_path, template)?;
+            set_passwd_field("shell", shell);
+            set_passwd_field("home", "/home/luke");
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            Ok(std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?.to_owned())
+        }
+
+        #[test]
+        fn if_passwd() -> Result<(), Report> {
+            let template = r#"{{#if_passwd "shell" "/bin/zsh"}}zsh{{else}}other{{/if_passwd}}"#;
+            assert_eq!(render("if_matching", template, "/bin/zsh")?, "zsh");
+            assert_eq!(render("if_not_matching", template, "/bin/bash")?, "other");
+            assert_eq!(
+                render(
+                    "if_home",
+                    r#"{{#if_passwd "home" "/home/luke"}}luke{{/if_passwd}}"#,
+                    "/bin/zsh",
+                )?,
+                "luke",
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn unless_passwd() -> Result<(), Report> {
+            let template =
+                r#"{{#unless_passwd "shell" "/bin/zsh"}}other{{else}}zsh{{/unless_passwd}}"#;
+            assert_eq!(render("unless_matching", template, "/bin/zsh")?, "zsh");
+            assert_eq!(
+                render("unless_not_matching", template, "/bin/bash")?,
+                "other"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn unsupported_field() {
+            assert!(render(
+                "unsupported_field",
+                r#"{{#if_passwd "uid" "0"}}root{{/if_passwd}}"#,
+                "/bin/zsh",
+            )
+            .is_err());
+        }
+    }
+
+    mod repeat {
+        use std::str::FromStr;
+
+        use crate::{
+            config::DTConfig,
+            registry::{Register, Registry},
+            syncing::expand,
+            utils::testing::{get_testroot, prepare_directory, prepare_file},
+        };
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn repeat_with_index() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers")
+                    .join("repeat")
+                    .join("repeat_with_index"),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[context.wm]
+workspaces = 3
+name = "ws"
+
+[[local]]
+name = "wm"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(
+                &template_path,
+                r#"{{#repeat wm.workspaces}}[{{wm.name}}{{@index}}]{{/repeat}}
+{{#repeat 0}}zero{{/repeat}}{{#repeat -2}}negative{{/repeat}}"#,
+            )?;
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "[ws0][ws1][ws2]\n",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+            Ok(())
+        }
+    }
+
+    mod with_host_fallback {
+        use std::str::FromStr;
+
+        use crate::{
+            config::DTConfig,
+            registry::{Register, Registry},
+            syncing::expand,
+            utils::testing::{get_testroot, prepare_directory, prepare_file},
+        };
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        fn render(name: &str, template: &str) -> Result<String, Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers")
+                    .join("with_host_fallback")
+                    .join(name),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[context.droids]
+origin.c-3po = "Star Wars"
+origin.r2d2 = "Star Wars"
+maker.c-3po = {{ name = "Anakin Skywalker" }}
+
+[[local]]
+name = "droids"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(&template_path, template)?;
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            Ok(std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?.to_owned())
+        }
+
+        #[test]
+        fn found() -> Result<(), Report> {
+            assert_eq!(
+                render(
+                    "found",
+                    r#"{{#with_host_fallback droids.origin "None"}}Origin is {{this}}{{/with_host_fallback}}"#,
+                )?,
+                "Origin is Star Wars",
+            );
+            assert_eq!(
+                render(
+                    "found_string_path",
+                    r#"{{#with_host_fallback "droids.origin" "None"}}Origin is {{this}}{{/with_host_fallback}}"#,
+                )?,
+                "Origin is Star Wars",
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn fallback() -> Result<(), Report> {
+            assert_eq!(
+                render(
+                    "fallback",
+                    r#"{{#with_host_fallback droids.maker "unknown"}}Made by {{this}}{{/with_host_fallback}}"#,
+                )?,
+                "Made by unknown",
+            );
+            Ok(())
+        }
+    }
+
+    mod cmd_succeeds {
+        use std::str::FromStr;
+
+        use crate::{
+            config::DTConfig,
+            registry::{Register, Registry},
+            syncing::expand,
+            utils::testing::{get_testroot, prepare_directory, prepare_file},
+        };
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn if_cmd_succeeds() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers")
+                    .join("cmd_succeeds")
+                    .join("if_cmd_succeeds"),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "cmd"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(
+                &template_path,
+                r#"{{#if_cmd_succeeds "true"}}succeeded{{else}}failed{{/if_cmd_succeeds}}
+{{#if_cmd_succeeds "false"}}succeeded{{else}}failed{{/if_cmd_succeeds}}
+{{#if_cmd_succeeds "echo noise; echo noise >&2"}}quiet{{/if_cmd_succeeds}}"#,
+            )?;
+
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "succeeded\nfailed\nquiet",
+                std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn unless_cmd_succeeds() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("block_helpers")
+                    .join("cmd_succeeds")
+                    .join("unless_cmd_succeeds"),
+                0o755,
+            )?;
+            let src_name = "template";
+            let template_path = prepare_file(base.join(src_name), 0o644)?;
+            let target = prepare_directory(base.join("target"), 0o755)?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "cmd"
+base = "{}"
+target = "{}"
+sources = ["{}"]
+"#,
+                base.display(),
+                target.display(),
+                src_name,
+            ))?)?;
+            std::fs::write(
+                &template_path,
+                r#"{{#unless_cmd_succeeds "true"}}failed{{else}}succeeded{{/unless_cmd_succeeds}}
+{{#unless_cmd_succeeds "false"}}failed{{else}}succeeded{{/unless_cmd_succeeds}}"#,
+            )?;
+
+            let reg = Registry::default().register_helpers()?.load(&config)?;
+            assert_eq!(
+                "succeeded\nfailed",
                 std::str::from_utf8(&reg.get(&template_path.to_string_lossy())?)?,
             );
             Ok(())
@@ -1,15 +1,23 @@
 use std::{
     collections::HashMap,
-    io::{Read, Seek},
+    io::Write,
+    time::{Duration, Instant},
 };
 
-use content_inspector::inspect;
-use handlebars::Handlebars;
+use encoding_rs::Encoding;
+use handlebars::{
+    to_json, Context, Handlebars, Helper, HelperDef, HelperResult, JsonValue, Output, RenderContext,
+};
 use serde::Serialize;
 
+#[cfg(not(test))]
+use crate::utils::hostname;
+#[cfg(test)]
+use crate::utils::testing::hostname;
 use crate::{
     config::DTConfig,
     error::{Error as AppError, Result},
+    utils::is_binary,
 };
 
 #[allow(unused_variables)]
@@ -27,9 +35,11 @@ where
     }
     /// Load templates and render them into cached storage, items that are not
     /// templated (see [`renderable`]) will not be registered into templates
-    /// but directly stored into the rendered cache.
+    /// but directly stored into the rendered cache.  Host-related helpers
+    /// are bound to the config's [`hostname_override`], if set.
     ///
     /// [`renderable`]: crate::config::Group::renderable
+    /// [`hostname_override`]: crate::config::GlobalConfig::hostname_override
     fn load(self, config: &DTConfig) -> Result<Self> {
         unimplemented!()
     }
@@ -54,6 +64,14 @@ where
     fn get(&self, name: &str) -> Result<Vec<u8>> {
         unimplemented!()
     }
+    /// Renders the template addressed by `name` once more, with the context
+    /// it was loaded with overlaid by `extra`, without touching the cache.
+    /// Tables in `extra` are merged into the context key by key, other values
+    /// replace their counterparts.  Items that were not rendered when
+    /// loading (e.g. binary ones) are returned as they are.
+    fn render_with_overlay(&self, name: &str, extra: &JsonValue) -> Result<Vec<u8>> {
+        unimplemented!()
+    }
 }
 
 /// Registry with a cache for rendered item contents.
@@ -63,21 +81,440 @@ pub struct Registry<'reg> {
     pub env: Handlebars<'reg>,
     /// The rendered contents of items.
     pub content: HashMap<String, Vec<u8>>,
+    /// The templating context items are rendered with.
+    pub context: JsonValue,
+    /// Encoding of renderable sources, see [`source_encoding`].  Sources
+    /// are taken as UTF-8 when it is `None`.
+    ///
+    /// [`source_encoding`]: crate::config::GlobalConfig::source_encoding
+    pub encoding: Option<&'static Encoding>,
+    /// Limits on rendering a single template, see [`RenderLimits`].
+    pub limits: RenderLimits,
+}
+
+/// Limits on rendering a single template, so that a runaway template (e.g.
+/// a recursive partial, or a huge `repeat` count) fails with a
+/// [`RenderingError`] instead of exhausting memory.  Limits are checked
+/// whenever the template produces output.
+///
+/// [`RenderingError`]: AppError::RenderingError
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RenderLimits {
+    /// Maximum size of rendered contents in bytes, see
+    /// [`max_render_size`].
+    ///
+    /// [`max_render_size`]: crate::config::GlobalConfig::max_render_size
+    pub max_size: Option<usize>,
+    /// Maximum time spent on rendering, see [`max_render_time`].
+    ///
+    /// [`max_render_time`]: crate::config::GlobalConfig::max_render_time
+    pub max_time: Option<Duration>,
+}
+
+/// Collects rendered contents, fails once a [`RenderLimits`] is exceeded.
+struct LimitedWriter {
+    buf: Vec<u8>,
+    limits: RenderLimits,
+    started: Instant,
+    exceeded: Option<String>,
+}
+
+impl Write for LimitedWriter {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        match (self.limits.max_size, self.limits.max_time) {
+            (Some(max_size), _) if self.buf.len() + data.len() > max_size => {
+                self.exceeded = Some(format!("exceeds the maximum size of {} bytes", max_size));
+            }
+            (_, Some(max_time)) if self.started.elapsed() > max_time => {
+                self.exceeded = Some(format!("exceeds the maximum time of {:?}", max_time));
+            }
+            _ => {
+                self.buf.extend_from_slice(data);
+                return Ok(data.len());
+            }
+        }
+        Err(std::io::Error::other("rendering limit exceeded"))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Registry<'_> {
+    /// Renders the template addressed by `name` with given context, within
+    /// [`limits`].
+    ///
+    /// [`limits`]: Registry::limits
+    fn render_limited<S: Serialize>(&self, name: &str, ctx: &S) -> Result<String> {
+        let mut writer = LimitedWriter {
+            buf: Vec::new(),
+            limits: self.limits,
+            started: Instant::now(),
+            exceeded: None,
+        };
+        if let Err(e) = self.env.render_to_write(name, ctx, &mut writer) {
+            return Err(match writer.exceeded {
+                Some(reason) => {
+                    AppError::RenderingError(format!("rendering '{}' {}", name, reason))
+                }
+                None => e.into(),
+            });
+        }
+        String::from_utf8(writer.buf).map_err(|e| AppError::RenderingError(e.to_string()))
+    }
+}
+
+/// Names of helpers that come with handlebars itself.
+const HANDLEBARS_HELPERS: &[&str] = &[
+    "if", "unless", "each", "with", "lookup", "raw", "log", "eq", "ne", "gt", "gte", "lt", "lte",
+    "and", "or", "not", "len",
+];
+
+/// Signature of the [host-related helpers], which take the hostname DT
+/// considers current machine to have as their first argument.
+///
+/// [host-related helpers]: HOST_HELPERS
+type HostHelperFn = for<'reg, 'rc> fn(
+    &str,
+    &Helper<'reg, 'rc>,
+    &'reg Handlebars<'reg>,
+    &'rc Context,
+    &mut RenderContext<'reg, 'rc>,
+    &mut dyn Output,
+) -> HelperResult;
+
+/// A host-related helper bound to a hostname.
+struct HostHelper {
+    hostname: String,
+    helper: HostHelperFn,
+}
+
+impl HelperDef for HostHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        (self.helper)(&self.hostname, h, r, ctx, rc, out)
+    }
+}
+
+/// Names of the [built-in helpers] that depend on the hostname, they are
+/// bound to [`get_hostname`] of the config when [loading] it.
+///
+/// [built-in helpers]: helpers
+/// [`get_hostname`]: crate::config::GlobalConfig::get_hostname
+/// [loading]: Register::load
+const HOST_HELPERS: &[&str] = &["get_mine", "with_host_fallback", "if_host", "unless_host"];
+
+/// DT's [built-in helpers], with their names and one-line descriptions.
+/// Host-related helpers are bound to given `hostname`.
+///
+/// [built-in helpers]: helpers
+fn builtin_helpers(
+    hostname: &str,
+) -> Vec<(&'static str, &'static str, Box<dyn HelperDef + Send + Sync>)> {
+    let host_helper = |helper: HostHelperFn| -> Box<dyn HelperDef + Send + Sync> {
+        Box::new(HostHelper {
+            hostname: hostname.to_owned(),
+            helper,
+        })
+    };
+    vec![
+        (
+            "get_mine",
+            "Value for current host from a map, or current hostname",
+            host_helper(helpers::get_mine),
+        ),
+        (
+            "coalesce",
+            "First argument that is neither null nor empty",
+            Box::new(helpers::coalesce),
+        ),
+        (
+            "with_host_fallback",
+            "Renders block with the value for current host from a map",
+            host_helper(helpers::with_host_fallback),
+        ),
+        (
+            "if_user",
+            "Renders block if current user is any of the given users",
+            Box::new(helpers::if_user),
+        ),
+        (
+            "if_uid",
+            "Renders block if current uid is any of the given uids",
+            Box::new(helpers::if_uid),
+        ),
+        (
+            "if_host",
+            "Renders block if current host is any of the given hosts",
+            host_helper(helpers::if_host),
+        ),
+        (
+            "unless_user",
+            "Negated version of `if_user`",
+            Box::new(helpers::unless_user),
+        ),
+        (
+            "unless_uid",
+            "Negated version of `if_uid`",
+            Box::new(helpers::unless_uid),
+        ),
+        (
+            "unless_host",
+            "Negated version of `if_host`",
+            host_helper(helpers::unless_host),
+        ),
+        (
+            "if_os",
+            "Renders block if a field of /etc/os-release matches",
+            Box::new(helpers::if_os),
+        ),
+        (
+            "unless_os",
+            "Negated version of `if_os`",
+            Box::new(helpers::unless_os),
+        ),
+        (
+            "if_range",
+            "Renders block if a value is within the given range",
+            Box::new(helpers::if_range),
+        ),
+        (
+            "unless_range",
+            "Negated version of `if_range`",
+            Box::new(helpers::unless_range),
+        ),
+        (
+            "if_int_cmp",
+            "Renders block if a numeric comparison holds",
+            Box::new(helpers::if_int_cmp),
+        ),
+        (
+            "unless_int_cmp",
+            "Negated version of `if_int_cmp`",
+            Box::new(helpers::unless_int_cmp),
+        ),
+        (
+            "if_kernel",
+            "Renders block if the kernel version comparison holds",
+            Box::new(helpers::if_kernel),
+        ),
+        (
+            "unless_kernel",
+            "Negated version of `if_kernel`",
+            Box::new(helpers::unless_kernel),
+        ),
+        (
+            "if_passwd",
+            "Renders block if a field of the user's passwd entry matches",
+            Box::new(helpers::if_passwd),
+        ),
+        (
+            "unless_passwd",
+            "Negated version of `if_passwd`",
+            Box::new(helpers::unless_passwd),
+        ),
+        (
+            "if_os_family",
+            "Renders block if the OS is of any of the given families",
+            Box::new(helpers::if_os_family),
+        ),
+        (
+            "unless_os_family",
+            "Negated version of `if_os_family`",
+            Box::new(helpers::unless_os_family),
+        ),
+        (
+            "if_container_runtime",
+            "Renders block if running in any of the given containers",
+            Box::new(helpers::if_container_runtime),
+        ),
+        (
+            "unless_container_runtime",
+            "Negated version of `if_container_runtime`",
+            Box::new(helpers::unless_container_runtime),
+        ),
+        (
+            "if_network",
+            "Renders block if the given address is reachable",
+            Box::new(helpers::if_network),
+        ),
+        (
+            "unless_network",
+            "Negated version of `if_network`",
+            Box::new(helpers::unless_network),
+        ),
+        (
+            "if_on_battery",
+            "Renders block if the machine is on battery",
+            Box::new(helpers::if_on_battery),
+        ),
+        (
+            "unless_on_battery",
+            "Negated version of `if_on_battery`",
+            Box::new(helpers::unless_on_battery),
+        ),
+        (
+            "if_desktop",
+            "Renders block if the desktop is any of the given ones",
+            Box::new(helpers::if_desktop),
+        ),
+        (
+            "unless_desktop",
+            "Negated version of `if_desktop`",
+            Box::new(helpers::unless_desktop),
+        ),
+        (
+            "if_locale",
+            "Renders block if the locale is any of the given ones",
+            Box::new(helpers::if_locale),
+        ),
+        (
+            "unless_locale",
+            "Negated version of `if_locale`",
+            Box::new(helpers::unless_locale),
+        ),
+        (
+            "if_root",
+            "Renders block if running as root",
+            Box::new(helpers::if_root),
+        ),
+        (
+            "unless_root",
+            "Negated version of `if_root`",
+            Box::new(helpers::unless_root),
+        ),
+        (
+            "if_tty",
+            "Renders block if the standard output is a terminal",
+            Box::new(helpers::if_tty),
+        ),
+        (
+            "unless_tty",
+            "Negated version of `if_tty`",
+            Box::new(helpers::unless_tty),
+        ),
+        (
+            "env",
+            "Value of an environment variable, with an optional default",
+            Box::new(helpers::env),
+        ),
+        ("get_env", "Alias of `env`", Box::new(helpers::env)),
+        (
+            "if_env",
+            "Renders block if an environment variable is any of the given values",
+            Box::new(helpers::if_env),
+        ),
+        (
+            "unless_env",
+            "Negated version of `if_env`",
+            Box::new(helpers::unless_env),
+        ),
+        (
+            "repeat",
+            "Renders block a given number of times",
+            Box::new(helpers::repeat),
+        ),
+        (
+            "if_cmd_succeeds",
+            "Renders block if the given command exits successfully",
+            Box::new(helpers::if_cmd_succeeds),
+        ),
+        (
+            "unless_cmd_succeeds",
+            "Negated version of `if_cmd_succeeds`",
+            Box::new(helpers::unless_cmd_succeeds),
+        ),
+    ]
+}
+
+/// Lists names and one-line descriptions of all helpers registered for
+/// given config, i.e. DT's [built-in helpers] followed by the user-defined
+/// ones in [`DTConfig::helpers`] (sorted by their names).
+///
+/// [built-in helpers]: helpers
+pub fn list_helpers(config: &DTConfig) -> Vec<(String, String)> {
+    let mut user_defined: Vec<&String> = config.helpers.keys().collect();
+    user_defined.sort();
+    builtin_helpers(&config.global.get_hostname())
+        .into_iter()
+        .map(|(name, description, _)| (name.to_owned(), description.to_owned()))
+        .chain(user_defined.into_iter().map(|name| {
+            (
+                name.to_owned(),
+                format!("User-defined snippet, used as `{{{{> {}}}}}`", name),
+            )
+        }))
+        .collect()
+}
+
+/// Names of the [built-in helpers] available when rendering a config file
+/// with [`render_config`], i.e. the ones without side effects on the
+/// filesystem, network or other processes.
+///
+/// [built-in helpers]: helpers
+const CONFIG_HELPERS: &[&str] = &[
+    "get_mine",
+    "coalesce",
+    "with_host_fallback",
+    "if_user",
+    "unless_user",
+    "if_uid",
+    "unless_uid",
+    "if_host",
+    "unless_host",
+    "if_os",
+    "unless_os",
+    "if_os_family",
+    "unless_os_family",
+    "if_kernel",
+    "unless_kernel",
+    "if_passwd",
+    "unless_passwd",
+    "if_desktop",
+    "unless_desktop",
+    "if_locale",
+    "unless_locale",
+    "if_root",
+    "unless_root",
+    "env",
+    "get_env",
+    "if_env",
+    "unless_env",
+];
+
+/// Renders the contents of a config file before it is parsed, with a
+/// restricted set of [built-in helpers] (see [`CONFIG_HELPERS`]) and an
+/// empty context.  Contents are not escaped, and unresolved variables or
+/// helpers are errors.
+///
+/// [built-in helpers]: helpers
+pub fn render_config(s: &str) -> Result<String> {
+    let mut env = Handlebars::new();
+    env.set_strict_mode(true);
+    env.register_escape_fn(handlebars::no_escape);
+    for (name, _, helper) in builtin_helpers(&hostname()) {
+        if CONFIG_HELPERS.contains(&name) {
+            env.register_helper(name, helper);
+        }
+    }
+    env.render_template(s, &JsonValue::Object(Default::default()))
+        .map_err(|e| AppError::ConfigError(format!("could not render config: {}", e)))
 }
 
 impl Register for Registry<'_> {
     fn register_helpers(self) -> Result<Self> {
         let mut render_env = self.env;
 
-        render_env.register_helper("get_mine", Box::new(helpers::get_mine));
-        render_env.register_helper("if_user", Box::new(helpers::if_user));
-        render_env.register_helper("if_uid", Box::new(helpers::if_uid));
-        render_env.register_helper("if_host", Box::new(helpers::if_host));
-        render_env.register_helper("unless_user", Box::new(helpers::unless_user));
-        render_env.register_helper("unless_uid", Box::new(helpers::unless_uid));
-        render_env.register_helper("unless_host", Box::new(helpers::unless_host));
-        render_env.register_helper("if_os", Box::new(helpers::if_os));
-        render_env.register_helper("unless_os", Box::new(helpers::unless_os));
+        for (name, _, helper) in builtin_helpers(&hostname()) {
+            render_env.register_helper(name, helper);
+        }
 
         Ok(Self {
             env: render_env,
@@ -87,11 +524,39 @@ impl Register for Registry<'_> {
 
     fn load(self, config: &DTConfig) -> Result<Self> {
         let mut registry = self;
+        let builtins = builtin_helpers(&config.global.get_hostname());
+        for (name, template) in &config.helpers {
+            if HANDLEBARS_HELPERS.contains(&name.as_str())
+                || builtins.iter().any(|(builtin, _, _)| builtin == name)
+            {
+                return Err(AppError::TemplatingError(format!(
+                    "user-defined helper '{}' clashes with a built-in helper",
+                    name,
+                )));
+            }
+            registry.env.register_partial(name, template)?;
+        }
+        for (name, _, helper) in builtins {
+            if HOST_HELPERS.contains(&name) {
+                registry.env.register_helper(name, helper);
+            }
+        }
+        registry.context = to_json(&config.context);
+        registry.encoding = Some(config.global.get_source_encoding()?);
+        registry.limits = RenderLimits {
+            max_size: config.global.max_render_size,
+            max_time: config.global.max_render_time.map(Duration::from_millis),
+        };
         for group in &config.local {
             for s in &group.sources {
                 let name = s.to_string_lossy();
 
-                if group.is_renderable() {
+                if s.is_dir() {
+                    log::trace!(
+                        "'{}' is a symlinked directory, skipping rendering",
+                        s.display(),
+                    );
+                } else if group.is_renderable() {
                     registry.update(&name, &config.context)?;
                 } else {
                     log::trace!(
@@ -106,15 +571,32 @@ impl Register for Registry<'_> {
     }
 
     fn update<S: Serialize>(&mut self, name: &str, ctx: &S) -> Result<()> {
-        let mut f = std::fs::File::open(name)?;
-        f.seek(std::io::SeekFrom::Start(0))?;
-        let mut indicator = vec![0; std::cmp::min(1024, f.metadata()?.len() as usize)];
-        f.read_exact(&mut indicator)?;
-        if inspect(&indicator).is_text() {
-            self.env
-                .register_template_string(name, std::fs::read_to_string(name)?)?;
-            self.content
-                .insert(name.to_owned(), self.env.render(name, ctx)?.into());
+        if std::fs::metadata(name)?.len() == 0 {
+            log::trace!("'{}' is empty, rendering it to empty contents", name);
+            self.content.insert(name.to_owned(), Vec::new());
+        } else if !is_binary(name)? {
+            let encoding = self.encoding.unwrap_or(encoding_rs::UTF_8);
+            let template = encoding
+                .decode_without_bom_handling_and_without_replacement(&std::fs::read(name)?)
+                .ok_or_else(|| {
+                    AppError::RenderingError(format!(
+                        "'{}' could not be decoded as {}",
+                        name,
+                        encoding.name(),
+                    ))
+                })?
+                .into_owned();
+            self.env.register_template_string(name, template)?;
+            let rendered = self.render_limited(name, ctx)?;
+            let (content, _, unmappable) = encoding.encode(&rendered);
+            if unmappable {
+                return Err(AppError::RenderingError(format!(
+                    "rendered contents of '{}' could not be encoded as {}",
+                    name,
+                    encoding.name(),
+                )));
+            }
+            self.content.insert(name.to_owned(), content.into_owned());
         } else {
             log::trace!("'{}' has binary contents, skipping rendering", name);
             self.content.insert(name.to_owned(), std::fs::read(name)?);
@@ -131,6 +613,28 @@ impl Register for Registry<'_> {
             ))),
         }
     }
+
+    fn render_with_overlay(&self, name: &str, extra: &JsonValue) -> Result<Vec<u8>> {
+        if self.env.get_template(name).is_none() {
+            return self.get(name);
+        }
+        let mut ctx = self.context.to_owned();
+        overlay(&mut ctx, extra);
+        Ok(self.render_limited(name, &ctx)?.into())
+    }
+}
+
+/// Merges `extra` into `base` recursively: objects are merged key by key,
+/// other values in `extra` replace the ones in `base`.
+fn overlay(base: &mut JsonValue, extra: &JsonValue) {
+    match (base, extra) {
+        (JsonValue::Object(base), JsonValue::Object(extra)) => {
+            for (key, value) in extra {
+                overlay(base.entry(key).or_insert(JsonValue::Null), value);
+            }
+        }
+        (base, extra) => *base = extra.to_owned(),
+    }
 }
 
 // ===========================================================================
@@ -139,21 +643,27 @@ impl Register for Registry<'_> {
 pub mod helpers {
     #[cfg(not(test))]
     use {
-        gethostname::gethostname,
+        crate::utils::{
+            container_runtime, is_reachable, kernel_release, on_battery, passwd_field,
+            stdout_is_tty,
+        },
         sys_info::linux_os_release,
-        users::{get_current_uid, get_current_username},
+        users::{get_current_uid, get_current_username, get_effective_uid},
     };
 
     #[cfg(test)]
     use crate::utils::testing::{
-        get_current_uid, get_current_username, gethostname, linux_os_release,
+        container_runtime, get_current_uid, get_current_username, get_effective_uid, is_reachable,
+        kernel_release, linux_os_release, on_battery, passwd_field, stdout_is_tty,
     };
 
     use handlebars::{
-        Context, Handlebars, Helper, HelperResult, JsonRender, Output, RenderContext, RenderError,
-        Renderable,
+        to_json, BlockContext, Context, Handlebars, Helper, HelperResult, JsonRender, JsonValue,
+        Output, PathAndJson, RenderContext, RenderError, Renderable,
     };
 
+    use crate::utils::{current_desktops, current_locale};
+
     /// A templating helper that retrieves the value for current host from a
     /// map, returns a default value when current host is not recorded in the
     /// map.
@@ -166,10 +676,21 @@ pub mod helpers {
     /// 2. `{{ get_mine <map> <default-value> }}`
     ///
     ///     Renders `<map>.$CURRENT_HOSTNAME`, falls back to `<default-value>`.
+    /// 3. `{{ get_mine "<dotted.path.to.map>" <default-value> }}`
+    ///
+    ///    Same as above, but `<map>` is given as a string and resolved as a
+    ///    dotted path into the templating context, so that the map can be
+    ///    chosen dynamically.
+    ///
+    /// Current machine's hostname is given as `hostname`, which is
+    /// [`hostname_override`] when it is set.
+    ///
+    /// [`hostname_override`]: crate::config::GlobalConfig::hostname_override
     pub fn get_mine(
+        hostname: &str,
         h: &Helper,
         _: &Handlebars,
-        _: &Context,
+        ctx: &Context,
         _rc: &mut RenderContext,
         out: &mut dyn Output,
     ) -> HelperResult {
@@ -183,14 +704,18 @@ Inline helper `{0}`:
            Renders current machine's hostname
 
         2. {{{{ {0} <map> <default-value> }}}}
-           Gets value of <map>.$CURRENT_HOSTNAME, falls back to <default-value>"#,
+           Gets value of <map>.$CURRENT_HOSTNAME, falls back to <default-value>
+
+        3. {{{{ {0} "<dotted.path.to.map>" <default-value> }}}}
+           Same as above, but <map> is resolved from the given dotted path
+           into the templating context"#,
             h.name(),
         );
 
         let map = match h.param(0) {
             Some(map) => map.value(),
             None => {
-                out.write(&gethostname().to_string_lossy().to_string())?;
+                out.write(hostname)?;
                 return Ok(());
             }
         };
@@ -201,7 +726,7 @@ Inline helper `{0}`:
             }
         };
 
-        let content = match map.get(gethostname().to_string_lossy().to_string()) {
+        let content = match host_value(map, ctx, hostname) {
             Some(content) => content.render(),
             None => default_content.render(),
         };
@@ -211,6 +736,113 @@ Inline helper `{0}`:
         Ok(())
     }
 
+    /// Retrieves the value for given `hostname` from given map.  A string
+    /// `map` is resolved as a dotted path into the templating context.
+    fn host_value<'a>(
+        map: &'a JsonValue,
+        ctx: &'a Context,
+        hostname: &str,
+    ) -> Option<&'a JsonValue> {
+        let map = match map.as_str() {
+            Some(path) => ctx.data().pointer(&json_pointer(path)),
+            None => Some(map),
+        };
+        map.and_then(|map| map.get(hostname))
+    }
+
+    /// Converts a dotted path into a JSON pointer, escaping `~` and `/` in
+    /// each segment as specified by [RFC 6901].
+    ///
+    /// [RFC 6901]: https://www.rfc-editor.org/rfc/rfc6901
+    fn json_pointer(path: &str) -> String {
+        path.split('.')
+            .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+            .collect()
+    }
+
+    /// A templating helper that renders its inner block with `this` set to
+    /// the value for current host from a map, or to a default value when
+    /// current host is not recorded in the map.
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#with_host_fallback <map> <default-value>}}..{{this}}..{{/with_host_fallback}}`
+    ///
+    ///    Renders the inner block with `this` being
+    ///    `<map>.$CURRENT_HOSTNAME`, or `<default-value>` if it's not found.
+    ///    Like [`get_mine`], `<map>` can also be given as a dotted path
+    ///    string.
+    ///
+    /// Current machine's hostname is given as `hostname`, like [`get_mine`].
+    pub fn with_host_fallback<'reg, 'rc>(
+        hostname: &str,
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 2 arguments, {1} argument(s) found
+
+    Usage:
+        1. {{{{#{0} <map> <default-value>}}}}..{{{{this}}}}..{{{{/{0}}}}}
+           Renders the inner block with `this` being <map>.$CURRENT_HOSTNAME,
+           falls back to <default-value>"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if h.params().len() != 2 {
+            return Err(RenderError::new(docmsg));
+        }
+        let value = match host_value(h.param(0).unwrap().value(), ctx, hostname) {
+            Some(value) => value.to_owned(),
+            None => h.param(1).unwrap().value().to_owned(),
+        };
+
+        if let Some(t) = h.template() {
+            let mut block = BlockContext::new();
+            block.set_base_value(value);
+            rc.push_block(block);
+            t.render(r, ctx, rc, out)?;
+            rc.pop_block();
+        }
+        Ok(())
+    }
+
+    /// A templating helper that renders the first of its arguments that is
+    /// neither null nor empty, renders nothing if all of them are empty.
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{ coalesce <value1> <value2> ... }}`
+    ///
+    ///    Renders the first non-empty value among `<value1>`, `<value2>`,
+    ///    etc..  Values that are not defined in the templating context are
+    ///    treated as empty.
+    pub fn coalesce(
+        h: &Helper,
+        _: &Handlebars,
+        _: &Context,
+        _rc: &mut RenderContext,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        if let Some(content) = h
+            .params()
+            .iter()
+            .filter(|param| !param.value().is_null())
+            .map(|param| param.value().render())
+            .find(|content| !content.is_empty())
+        {
+            out.write(&content)?;
+        }
+
+        Ok(())
+    }
+
     /// A templating helper that tests if current user's username matches a
     /// set of given string(s).
     ///
@@ -298,14 +930,16 @@ Block helper `#{0}`:
                     current_username,
                     allowed_usernames,
                 );
-                h.template().map(|t| t.render(r, ctx, rc, out));
+                h.template()
+                    .map(|t| t.render(r, ctx, rc, out))
+                    .transpose()?;
             } else {
                 log::debug!(
                     "Current username '{}' does not match allowed usernames {:?}",
                     current_username,
                     allowed_usernames,
                 );
-                h.inverse().map(|t| t.render(r, ctx, rc, out));
+                h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
             }
         } else {
             return Err(RenderError::new(format!(
@@ -405,14 +1039,16 @@ Block helper `#{0}`:
                     current_username,
                     disallowed_usernames,
                 );
-                h.inverse().map(|t| t.render(r, ctx, rc, out));
+                h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
             } else {
                 log::debug!(
                     "Current username '{}' does not match disallowed usernames {:?}",
                     current_username,
                     disallowed_usernames,
                 );
-                h.template().map(|t| t.render(r, ctx, rc, out));
+                h.template()
+                    .map(|t| t.render(r, ctx, rc, out))
+                    .transpose()?;
             }
         } else {
             return Err(RenderError::new(format!(
@@ -506,14 +1142,16 @@ Block helper `#{0}`:
                     current_uid,
                     allowed_uids,
                 );
-                h.template().map(|t| t.render(r, ctx, rc, out));
+                h.template()
+                    .map(|t| t.render(r, ctx, rc, out))
+                    .transpose()?;
             } else {
                 log::debug!(
                     "Current uid '{}' does not match allowed uids {:?}",
                     current_uid,
                     allowed_uids,
                 );
-                h.inverse().map(|t| t.render(r, ctx, rc, out));
+                h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
             }
         } else {
             return Err(RenderError::new(format!(
@@ -608,14 +1246,16 @@ Block helper `#{0}`:
                     current_uid,
                     disallowed_uids,
                 );
-                h.inverse().map(|t| t.render(r, ctx, rc, out));
+                h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
             } else {
                 log::debug!(
                     "Current uid '{}' does not match disallowed uids '{:?}'",
                     current_uid,
                     disallowed_uids,
                 );
-                h.template().map(|t| t.render(r, ctx, rc, out));
+                h.template()
+                    .map(|t| t.render(r, ctx, rc, out))
+                    .transpose()?;
             }
         } else {
             return Err(RenderError::new(format!(
@@ -645,8 +1285,11 @@ Block helper `#{0}`:
     ///    of the values from the templating variable `some.array` (defined in
     ///    the config file's [`[context]`] section).
     ///
+    /// Current machine's hostname is given as `hostname`, like [`get_mine`].
+    ///
     /// [`[context]`]: dt_core::config::ContextConfig
     pub fn if_host<'reg, 'rc>(
+        hostname: &str,
         h: &Helper<'reg, 'rc>,
         r: &'reg Handlebars<'reg>,
         ctx: &'rc Context,
@@ -701,7 +1344,7 @@ Block helper `#{0}`:
             }
         };
 
-        let current_hostname = gethostname().to_string_lossy().to_string();
+        let current_hostname = hostname.to_owned();
         if !allowed_hostnames.is_empty() {
             if allowed_hostnames.contains(&current_hostname) {
                 log::debug!(
@@ -709,14 +1352,16 @@ Block helper `#{0}`:
                     current_hostname,
                     allowed_hostnames,
                 );
-                h.template().map(|t| t.render(r, ctx, rc, out));
+                h.template()
+                    .map(|t| t.render(r, ctx, rc, out))
+                    .transpose()?;
             } else {
                 log::debug!(
                     "Current hostname '{}' does not match allowed hostnames '{:?}'",
                     current_hostname,
                     allowed_hostnames,
                 );
-                h.inverse().map(|t| t.render(r, ctx, rc, out));
+                h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
             }
         } else {
             return Err(RenderError::new(format!(
@@ -746,9 +1391,12 @@ Block helper `#{0}`:
     ///    values from the templating variable `some.array` (defined in the
     ///    config file's [`[context]`] section).
     ///
+    /// Current machine's hostname is given as `hostname`, like [`get_mine`].
+    ///
     /// [`if_host`]: if_host
     /// [`[context]`]: dt_core::config::ContextConfig
     pub fn unless_host<'reg, 'rc>(
+        hostname: &str,
         h: &Helper<'reg, 'rc>,
         r: &'reg Handlebars<'reg>,
         ctx: &'rc Context,
@@ -803,7 +1451,7 @@ Block helper `#{0}`:
             }
         };
 
-        let current_hostname = gethostname().to_string_lossy().to_string();
+        let current_hostname = hostname.to_owned();
         if !disallowed_hostnames.is_empty() {
             if disallowed_hostnames.contains(&current_hostname) {
                 log::debug!(
@@ -811,14 +1459,16 @@ Block helper `#{0}`:
                     current_hostname,
                     disallowed_hostnames,
                 );
-                h.inverse().map(|t| t.render(r, ctx, rc, out));
+                h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
             } else {
                 log::debug!(
                     "Current hostname '{}' does not match disallowed hostnames '{:?}'",
                     current_hostname,
                     disallowed_hostnames,
                 );
-                h.template().map(|t| t.render(r, ctx, rc, out));
+                h.template()
+                    .map(|t| t.render(r, ctx, rc, out))
+                    .transpose()?;
             }
         } else {
             return Err(RenderError::new(format!(
@@ -910,12 +1560,12 @@ Block helper `#{0}`:
                 "PRIVACY_POLICY_URL" => os_rel_info.privacy_policy_url,
                 _ => None,
             };
-            if value == None {
-                log::warn!(
+            if value.is_none() {
+                crate::utils::warning!(
                     "/etc/os-release does not seem to provide '{}', see man:os-release(5) for more information",
                     query,
                 );
-                h.inverse().map(|t| t.render(r, ctx, rc, out));
+                h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
                 return Ok(());
             }
             let value = value.unwrap();
@@ -949,14 +1599,16 @@ Block helper `#{0}`:
                         value,
                         allowed_values,
                     );
-                    h.template().map(|t| t.render(r, ctx, rc, out));
+                    h.template()
+                        .map(|t| t.render(r, ctx, rc, out))
+                        .transpose()?;
                 } else {
                     log::debug!(
                         "Query result '{}' does not match allowed value '{:?}'",
                         value,
                         allowed_values,
                     );
-                    h.inverse().map(|t| t.render(r, ctx, rc, out));
+                    h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
                 }
             } else {
                 return Err(RenderError::new(format!(
@@ -1053,12 +1705,12 @@ Block helper `#{0}`:
                 "PRIVACY_POLICY_URL" => os_rel_info.privacy_policy_url,
                 _ => None,
             };
-            if value == None {
-                log::warn!(
+            if value.is_none() {
+                crate::utils::warning!(
                     "/etc/os-release does not seem to provide '{}', see man:os-release(5) for more information",
                     query,
                 );
-                h.inverse().map(|t| t.render(r, ctx, rc, out));
+                h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
                 return Ok(());
             }
             let value = value.unwrap();
@@ -1092,14 +1744,16 @@ Block helper `#{0}`:
                         value,
                         disallowed_values,
                     );
-                    h.inverse().map(|t| t.render(r, ctx, rc, out));
+                    h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
                 } else {
                     log::debug!(
                         "Query result '{}' does not match disallowed value '{:?}'",
                         value,
                         disallowed_values,
                     );
-                    h.template().map(|t| t.render(r, ctx, rc, out));
+                    h.template()
+                        .map(|t| t.render(r, ctx, rc, out))
+                        .transpose()?;
                 }
             } else {
                 return Err(RenderError::new(format!(
@@ -1113,6 +1767,2019 @@ Block helper `#{0}`:
 
         Ok(())
     }
+
+    /// Parses the given helper parameter as a number.  A parameter that is
+    /// literally `uid` (either quoted, or as a path that is not defined in
+    /// the templating context) is parsed as current user's effective uid.
+    fn param_as_number(param: &PathAndJson) -> Option<f64> {
+        let value = param.value();
+        if value.as_str() == Some("uid")
+            || (value.is_null() && param.relative_path().map(|p| p.as_str()) == Some("uid"))
+        {
+            Some(get_effective_uid() as f64)
+        } else if let Some(number) = value.as_f64() {
+            Some(number)
+        } else {
+            value.render().trim().parse().ok()
+        }
+    }
+
+    /// Checks the parameters given to the range helpers, returns whether
+    /// the value falls in the inclusive range.
+    fn in_range(h: &Helper, docmsg: &str) -> Result<bool, RenderError> {
+        if h.params().len() != 3 {
+            return Err(RenderError::new(docmsg));
+        }
+        let numbers: Vec<f64> = h
+            .params()
+            .iter()
+            .map(|param| {
+                param_as_number(param).ok_or_else(|| {
+                    RenderError::new(format!(
+                        "'{}' is not a number in helper `#{}`",
+                        param.value().render(),
+                        h.name(),
+                    ))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        let (value, low, high) = (numbers[0], numbers[1], numbers[2]);
+        log::debug!("Testing if {} is in range [{}, {}]", value, low, high);
+        Ok(low <= value && value <= high)
+    }
+
+    /// A templating helper that tests if a numeric value falls in a given
+    /// inclusive range.  The value can be a number, a templating variable,
+    /// or `uid` for current user's effective uid.
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#if_range uid 1000 2000}}..foo..{{/if_range}}`
+    ///
+    ///    Renders `..foo..` only if current user's effective uid is in range
+    ///    `[1000, 2000]`.
+    /// 2. `{{#if_range some.number 1 10}}..foo..{{else}}..bar..{{/if_range}}`
+    ///
+    ///    Renders `..foo..` only if the templating variable `some.number`
+    ///    (defined in the config file's [`[context]`] section) is in range
+    ///    `[1, 10]`, renders `..bar..` otherwise.
+    ///
+    /// [`[context]`]: dt_core::config::ContextConfig
+    pub fn if_range<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 3 arguments, {1} found
+
+    Usage:
+        1. {{{{#{0} uid 1000 2000}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if current user's effective uid is in range
+           [1000, 2000]
+
+        2. {{{{#{0} some.number 1 10}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if the templating variable `some.number`
+           (defined in the config file's `[context]` section) is in range
+           [1, 10], renders `..bar..` otherwise"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if in_range(h, &docmsg)? {
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        } else {
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        }
+        Ok(())
+    }
+
+    /// A templating helper that tests if a numeric value falls out of a
+    /// given inclusive range.  It is the negated version of [`if_range`].
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#unless_range uid 1000 2000}}..foo..{{/unless_range}}`
+    ///
+    ///    Renders `..foo..` only if current user's effective uid is NOT in
+    ///    range `[1000, 2000]`.
+    /// 2. `{{#unless_range some.number 1 10}}..foo..{{else}}..bar..{{/unless_range}}`
+    ///
+    ///    Renders `..foo..` only if the templating variable `some.number`
+    ///    (defined in the config file's [`[context]`] section) is NOT in
+    ///    range `[1, 10]`, renders `..bar..` otherwise.
+    ///
+    /// [`if_range`]: if_range
+    /// [`[context]`]: dt_core::config::ContextConfig
+    pub fn unless_range<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 3 arguments, {1} found
+
+    Usage:
+        1. {{{{#{0} uid 1000 2000}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if current user's effective uid is NOT in
+           range [1000, 2000]
+
+        2. {{{{#{0} some.number 1 10}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if the templating variable `some.number`
+           (defined in the config file's `[context]` section) is NOT in range
+           [1, 10], renders `..bar..` otherwise"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if in_range(h, &docmsg)? {
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        } else {
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        }
+        Ok(())
+    }
+
+    /// Checks the parameters given to the numeric comparison helpers,
+    /// returns whether the comparison holds.
+    fn int_cmp(h: &Helper, docmsg: &str) -> Result<bool, RenderError> {
+        if h.params().len() != 3 {
+            return Err(RenderError::new(docmsg));
+        }
+        let as_number = |param: &PathAndJson| {
+            param_as_number(param).ok_or_else(|| {
+                RenderError::new(format!(
+                    "'{}' is not a number in helper `#{}`",
+                    param.value().render(),
+                    h.name(),
+                ))
+            })
+        };
+        let lhs = as_number(&h.params()[0])?;
+        let op = h.params()[1].value().render();
+        let rhs = as_number(&h.params()[2])?;
+        log::debug!("Testing if {} {} {}", lhs, op, rhs);
+        match op.as_str() {
+            ">" => Ok(lhs > rhs),
+            ">=" => Ok(lhs >= rhs),
+            "<" => Ok(lhs < rhs),
+            "<=" => Ok(lhs <= rhs),
+            "==" => Ok(lhs == rhs),
+            "!=" => Ok(lhs != rhs),
+            _ => Err(RenderError::new(format!(
+                "'{}' is not a valid operator in helper `#{}`, expected one of `>`, `>=`, `<`, `<=`, `==`, `!=`",
+                op,
+                h.name(),
+            ))),
+        }
+    }
+
+    /// A templating helper that compares two numeric values with given
+    /// operator, one of `>`, `>=`, `<`, `<=`, `==` and `!=`.  The values can
+    /// be numbers, templating variables, or `uid` for current user's
+    /// effective uid.
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#if_int_cmp version.major ">=" 3}}..foo..{{/if_int_cmp}}`
+    ///
+    ///    Renders `..foo..` only if the templating variable `version.major`
+    ///    (defined in the config file's [`[context]`] section) is greater
+    ///    than or equal to `3`.
+    /// 2. `{{#if_int_cmp uid "==" 0}}..foo..{{else}}..bar..{{/if_int_cmp}}`
+    ///
+    ///    Renders `..foo..` only if current user is root, renders `..bar..`
+    ///    otherwise.
+    ///
+    /// [`[context]`]: dt_core::config::ContextConfig
+    pub fn if_int_cmp<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 3 arguments, {1} found
+
+    Usage:
+        1. {{{{#{0} version.major ">=" 3}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if the templating variable `version.major`
+           (defined in the config file's `[context]` section) is greater than
+           or equal to 3
+
+        2. {{{{#{0} uid "==" 0}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if current user is root, renders `..bar..`
+           otherwise
+
+    Supported operators: `>`, `>=`, `<`, `<=`, `==`, `!=`"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if int_cmp(h, &docmsg)? {
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        } else {
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        }
+        Ok(())
+    }
+
+    /// A templating helper that compares two numeric values with given
+    /// operator.  It is the negated version of [`if_int_cmp`].
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#unless_int_cmp version.major ">=" 3}}..foo..{{/unless_int_cmp}}`
+    ///
+    ///    Renders `..foo..` only if the templating variable `version.major`
+    ///    (defined in the config file's [`[context]`] section) is NOT
+    ///    greater than or equal to `3`.
+    /// 2. `{{#unless_int_cmp uid "==" 0}}..foo..{{else}}..bar..{{/unless_int_cmp}}`
+    ///
+    ///    Renders `..foo..` only if current user is NOT root, renders
+    ///    `..bar..` otherwise.
+    ///
+    /// [`if_int_cmp`]: if_int_cmp
+    /// [`[context]`]: dt_core::config::ContextConfig
+    pub fn unless_int_cmp<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 3 arguments, {1} found
+
+    Usage:
+        1. {{{{#{0} version.major ">=" 3}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if the templating variable `version.major`
+           (defined in the config file's `[context]` section) is NOT greater
+           than or equal to 3
+
+        2. {{{{#{0} uid "==" 0}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if current user is NOT root, renders
+           `..bar..` otherwise
+
+    Supported operators: `>`, `>=`, `<`, `<=`, `==`, `!=`"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if int_cmp(h, &docmsg)? {
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        } else {
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        }
+        Ok(())
+    }
+
+    /// Parses the leading `x.y.z` of a version string into its numeric
+    /// components, missing components are taken as `0`, e.g. `6.1-arch1`
+    /// gives `[6, 1, 0]`.  Returns `None` if it does not start with a
+    /// number.
+    fn parse_version(version: &str) -> Option<[u64; 3]> {
+        let mut ret = [0; 3];
+        for (i, component) in version.split('.').take(3).enumerate() {
+            let digits: String = component.chars().take_while(char::is_ascii_digit).collect();
+            if digits.is_empty() {
+                return if i == 0 { None } else { Some(ret) };
+            }
+            ret[i] = digits.parse().ok()?;
+            if digits.len() != component.len() {
+                break;
+            }
+        }
+        Some(ret)
+    }
+
+    /// Checks the parameter given to the kernel version helpers, returns
+    /// whether the running kernel's version satisfies the comparison.
+    fn kernel_cmp(h: &Helper, docmsg: &str) -> Result<bool, RenderError> {
+        let requirement = match h.param(0) {
+            Some(v) if h.params().len() == 1 => v.value().render(),
+            _ => return Err(RenderError::new(docmsg)),
+        };
+        let requirement = requirement.trim();
+        let version_start = requirement
+            .find(|c: char| !"<>=!".contains(c))
+            .unwrap_or(requirement.len());
+        let (op, version) = requirement.split_at(version_start);
+        let wanted = parse_version(version.trim()).ok_or_else(|| {
+            RenderError::new(format!(
+                "'{}' is not a valid version in helper `#{}`",
+                version.trim(),
+                h.name(),
+            ))
+        })?;
+        let release = match kernel_release() {
+            Some(release) => release,
+            None => {
+                crate::utils::warning!("Could not determine the kernel release");
+                return Ok(false);
+            }
+        };
+        let current = match parse_version(&release) {
+            Some(current) => current,
+            None => {
+                crate::utils::warning!("Could not parse kernel release '{}'", release);
+                return Ok(false);
+            }
+        };
+        log::debug!("Testing if kernel {:?} {} {:?}", current, op, wanted);
+        match op {
+            ">" => Ok(current > wanted),
+            ">=" => Ok(current >= wanted),
+            "<" => Ok(current < wanted),
+            "<=" => Ok(current <= wanted),
+            "==" | "" => Ok(current == wanted),
+            "!=" => Ok(current != wanted),
+            _ => Err(RenderError::new(format!(
+                "'{}' is not a valid operator in helper `#{}`, expected one of `>`, `>=`, `<`, `<=`, `==`, `!=`",
+                op,
+                h.name(),
+            ))),
+        }
+    }
+
+    /// A templating helper that compares the running kernel's version
+    /// (detected with [`kernel_release`]) with given version.  The argument
+    /// is an operator (one of `>`, `>=`, `<`, `<=`, `==` and `!=`, default
+    /// to `==`) followed by an `x.y.z` version, where omitted components are
+    /// taken as `0`.
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#if_kernel ">=6.1"}}..foo..{{/if_kernel}}`
+    ///
+    ///    Renders `..foo..` only if the kernel's version is at least 6.1.0.
+    /// 2. `{{#if_kernel "<5"}}..foo..{{else}}..bar..{{/if_kernel}}`
+    ///
+    ///    Renders `..foo..` only if the kernel's version is lower than
+    ///    5.0.0, renders `..bar..` otherwise.
+    ///
+    /// [`kernel_release`]: crate::utils::kernel_release
+    pub fn if_kernel<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 1 argument, {1} found
+
+    Usage:
+        1. {{{{#{0} ">=6.1"}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if the kernel's version is at least 6.1.0
+
+        2. {{{{#{0} "<5"}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if the kernel's version is lower than
+           5.0.0, renders `..bar..` otherwise
+
+    Supported operators: `>`, `>=`, `<`, `<=`, `==` (default), `!=`"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if kernel_cmp(h, &docmsg)? {
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        } else {
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        }
+        Ok(())
+    }
+
+    /// A templating helper that compares the running kernel's version with
+    /// given version.  It is the negated version of [`if_kernel`].
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#unless_kernel ">=6.1"}}..foo..{{/unless_kernel}}`
+    ///
+    ///    Renders `..foo..` only if the kernel's version is lower than
+    ///    6.1.0.
+    /// 2. `{{#unless_kernel "<5"}}..foo..{{else}}..bar..{{/unless_kernel}}`
+    ///
+    ///    Renders `..foo..` only if the kernel's version is NOT lower than
+    ///    5.0.0, renders `..bar..` otherwise.
+    ///
+    /// [`if_kernel`]: if_kernel
+    pub fn unless_kernel<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 1 argument, {1} found
+
+    Usage:
+        1. {{{{#{0} ">=6.1"}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if the kernel's version is lower than 6.1.0
+
+        2. {{{{#{0} "<5"}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if the kernel's version is NOT lower than
+           5.0.0, renders `..bar..` otherwise
+
+    Supported operators: `>`, `>=`, `<`, `<=`, `==` (default), `!=`"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if kernel_cmp(h, &docmsg)? {
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        } else {
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        }
+        Ok(())
+    }
+
+    /// Checks the parameters given to the passwd helpers, returns whether
+    /// the given field of the current user's passwd entry has the given
+    /// value.
+    fn passwd_matches(h: &Helper, docmsg: &str) -> Result<bool, RenderError> {
+        let (field, expected) = match (h.param(0), h.param(1)) {
+            (Some(field), Some(expected)) if h.params().len() == 2 => {
+                (field.value().render(), expected.value().render())
+            }
+            _ => return Err(RenderError::new(docmsg)),
+        };
+        if !["shell", "home", "gecos"].contains(&field.as_str()) {
+            return Err(RenderError::new(format!(
+                "'{}' is not a supported passwd field in helper `#{}`, expected one of `shell`, `home`, `gecos`",
+                field,
+                h.name(),
+            )));
+        }
+        match passwd_field(&field) {
+            Some(value) => {
+                log::debug!("Testing if passwd {} '{}' is '{}'", field, value, expected);
+                Ok(value == expected)
+            }
+            None => {
+                crate::utils::warning!("Could not find the passwd entry of current user");
+                Ok(false)
+            }
+        }
+    }
+
+    /// A templating helper that tests a field of the current user's passwd
+    /// entry.  The arguments are a field name (one of `shell`, `home` and
+    /// `gecos`) and the expected value of the field.
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#if_passwd "shell" "/bin/zsh"}}..foo..{{/if_passwd}}`
+    ///
+    ///    Renders `..foo..` only if the current user's login shell is
+    ///    `/bin/zsh`.
+    /// 2. `{{#if_passwd "home" "/home/luke"}}..foo..{{else}}..bar..{{/if_passwd}}`
+    ///
+    ///    Renders `..foo..` only if the current user's home directory is
+    ///    `/home/luke`, renders `..bar..` otherwise.
+    pub fn if_passwd<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 2 arguments, {1} found
+
+    Usage:
+        1. {{{{#{0} "shell" "/bin/zsh"}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if the current user's login shell is
+           `/bin/zsh`
+
+        2. {{{{#{0} "home" "/home/luke"}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if the current user's home directory is
+           `/home/luke`, renders `..bar..` otherwise
+
+    Supported fields: `shell`, `home`, `gecos`"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if passwd_matches(h, &docmsg)? {
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        } else {
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        }
+        Ok(())
+    }
+
+    /// A templating helper that tests a field of the current user's passwd
+    /// entry.  It is the negated version of [`if_passwd`].
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#unless_passwd "shell" "/bin/zsh"}}..foo..{{/unless_passwd}}`
+    ///
+    ///    Renders `..foo..` only if the current user's login shell is NOT
+    ///    `/bin/zsh`.
+    /// 2. `{{#unless_passwd "home" "/home/luke"}}..foo..{{else}}..bar..{{/unless_passwd}}`
+    ///
+    ///    Renders `..foo..` only if the current user's home directory is
+    ///    NOT `/home/luke`, renders `..bar..` otherwise.
+    ///
+    /// [`if_passwd`]: if_passwd
+    pub fn unless_passwd<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 2 arguments, {1} found
+
+    Usage:
+        1. {{{{#{0} "shell" "/bin/zsh"}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if the current user's login shell is NOT
+           `/bin/zsh`
+
+        2. {{{{#{0} "home" "/home/luke"}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if the current user's home directory is
+           NOT `/home/luke`, renders `..bar..` otherwise
+
+    Supported fields: `shell`, `home`, `gecos`"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if passwd_matches(h, &docmsg)? {
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        } else {
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        }
+        Ok(())
+    }
+
+    /// Gets the OS families of current machine, i.e. the `ID` and the
+    /// space-separated `ID_LIKE` list from its /etc/os-release file.
+    fn os_families() -> Result<Vec<String>, RenderError> {
+        let os_rel_info = match linux_os_release() {
+            Ok(info) => info,
+            Err(msg) => return Err(RenderError::new(msg.to_string())),
+        };
+        let mut families: Vec<String> = os_rel_info.id.into_iter().collect();
+        if let Some(id_like) = os_rel_info.id_like {
+            families.extend(id_like.split_whitespace().map(|f| f.to_owned()));
+        }
+        Ok(families)
+    }
+
+    /// Gets the OS family names given to the os family helpers, either as a
+    /// comma-separated string or as an array.
+    fn wanted_os_families(h: &Helper) -> Vec<String> {
+        match h.param(0) {
+            Some(v) if v.value().is_array() => v
+                .value()
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|elem| elem.render())
+                .collect(),
+            Some(v) => v
+                .value()
+                .render()
+                .split(',')
+                .map(|f| f.trim().to_owned())
+                .filter(|f| !f.is_empty())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// A templating helper that tests if current machine belongs to any of
+    /// the given OS families.  A family matches if it is either the `ID` or
+    /// one of the space-separated `ID_LIKE` values from current machine's
+    /// /etc/os-release file.
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#if_os_family "debian,arch"}}..foo..{{/if_os_family}}`
+    ///
+    ///    Renders `..foo..` only if current machine is a derivative of
+    ///    either Debian or Arch Linux (or is one of them).
+    /// 2. `{{#if_os_family "debian"}}..foo..{{else}}..bar..{{/if_os_family}}`
+    ///
+    ///    Renders `..foo..` only if current machine is a derivative of Debian,
+    ///    renders `..bar..` otherwise.
+    /// 3. `{{#if_os_family some.array}}..foo..{{/if_os_family}}`
+    ///
+    ///    Renders `..foo..` only if current machine belongs to any of the
+    ///    families from the templating variable `some.array` (defined in the
+    ///    config file's [`[context]`] section).
+    ///
+    /// [`[context]`]: dt_core::config::ContextConfig
+    pub fn if_os_family<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 1 argument, {1} found
+
+    Usage:
+        1. {{{{#{0} "debian,arch"}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if current machine is a derivative of either
+           Debian or Arch Linux (or is one of them)
+
+        2. {{{{#{0} "debian"}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if current machine is a derivative of
+           Debian, renders `..bar..` otherwise
+
+        3. {{{{#{0} some.array}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if current machine belongs to any of the
+           families from the templating variable `some.array` (defined in the
+           config file's `[context]` section)"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if h.params().len() != 1 {
+            return Err(RenderError::new(docmsg));
+        }
+
+        let families = os_families()?;
+        let wanted = wanted_os_families(h);
+        if wanted.iter().any(|f| families.contains(f)) {
+            log::debug!(
+                "OS families '{:?}' match wanted families '{:?}'",
+                families,
+                wanted,
+            );
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        } else {
+            log::debug!(
+                "OS families '{:?}' do not match wanted families '{:?}'",
+                families,
+                wanted,
+            );
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        }
+        Ok(())
+    }
+
+    /// A templating helper that tests if current machine belongs to none of
+    /// the given OS families.  It is the negated version of
+    /// [`if_os_family`].
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#unless_os_family "debian,arch"}}..foo..{{/unless_os_family}}`
+    ///
+    ///    Renders `..foo..` only if current machine is neither a derivative
+    ///    of Debian nor of Arch Linux.
+    /// 2. `{{#unless_os_family "debian"}}..foo..{{else}}..bar..{{/unless_os_family}}`
+    ///
+    ///    Renders `..foo..` only if current machine is NOT a derivative of
+    ///    Debian, renders `..bar..` otherwise.
+    /// 3. `{{#unless_os_family some.array}}..foo..{{/unless_os_family}}`
+    ///
+    ///    Renders `..foo..` only if current machine belongs to none of the
+    ///    families from the templating variable `some.array` (defined in the
+    ///    config file's [`[context]`] section).
+    ///
+    /// [`if_os_family`]: if_os_family
+    /// [`[context]`]: dt_core::config::ContextConfig
+    pub fn unless_os_family<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 1 argument, {1} found
+
+    Usage:
+        1. {{{{#{0} "debian,arch"}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if current machine is neither a derivative
+           of Debian nor of Arch Linux
+
+        2. {{{{#{0} "debian"}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if current machine is NOT a derivative of
+           Debian, renders `..bar..` otherwise
+
+        3. {{{{#{0} some.array}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if current machine belongs to none of the
+           families from the templating variable `some.array` (defined in the
+           config file's `[context]` section)"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if h.params().len() != 1 {
+            return Err(RenderError::new(docmsg));
+        }
+
+        let families = os_families()?;
+        let wanted = wanted_os_families(h);
+        if wanted.iter().any(|f| families.contains(f)) {
+            log::debug!(
+                "OS families '{:?}' match wanted families '{:?}'",
+                families,
+                wanted,
+            );
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        } else {
+            log::debug!(
+                "OS families '{:?}' do not match wanted families '{:?}'",
+                families,
+                wanted,
+            );
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        }
+        Ok(())
+    }
+
+    /// Checks the parameter given to the container runtime helpers, returns
+    /// whether current container runtime is one of the given runtimes.
+    fn container_runtime_matches(h: &Helper, docmsg: &str) -> Result<bool, RenderError> {
+        let allowed_runtimes: Vec<String> = match h.param(0) {
+            Some(v) if h.params().len() == 1 => {
+                if v.value().is_array() {
+                    v.value()
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|elem| elem.render())
+                        .collect()
+                } else {
+                    v.value()
+                        .render()
+                        .split(',')
+                        .map(|r| r.trim().to_owned())
+                        .collect()
+                }
+            }
+            _ => return Err(RenderError::new(docmsg)),
+        };
+        match container_runtime() {
+            Some(runtime) => {
+                log::debug!(
+                    "Testing if container runtime '{}' is one of {:?}",
+                    runtime,
+                    allowed_runtimes,
+                );
+                Ok(allowed_runtimes.contains(&runtime))
+            }
+            None => {
+                log::debug!("Not running in a container");
+                Ok(false)
+            }
+        }
+    }
+
+    /// A templating helper that tests if DT runs in a container of any of
+    /// the given runtimes, detected with [`container_runtime`].
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#if_container_runtime "podman,docker"}}..foo..{{/if_container_runtime}}`
+    ///
+    ///    Renders `..foo..` only if DT runs in a podman or docker container.
+    /// 2. `{{#if_container_runtime "lxc"}}..foo..{{else}}..bar..{{/if_container_runtime}}`
+    ///
+    ///    Renders `..foo..` only if DT runs in an LXC container, renders
+    ///    `..bar..` otherwise (including on bare metal).
+    ///
+    /// [`container_runtime`]: crate::utils::container_runtime
+    pub fn if_container_runtime<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 1 argument, {1} found
+
+    Usage:
+        1. {{{{#{0} "podman,docker"}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if DT runs in a podman or docker container
+
+        2. {{{{#{0} "lxc"}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if DT runs in an LXC container, renders
+           `..bar..` otherwise
+
+    Recognized runtimes: `podman`, `docker`, `lxc`"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if container_runtime_matches(h, &docmsg)? {
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        } else {
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        }
+        Ok(())
+    }
+
+    /// A templating helper that tests if DT does NOT run in a container of
+    /// any of the given runtimes.  It is the negated version of
+    /// [`if_container_runtime`].
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#unless_container_runtime "podman,docker"}}..foo..{{/unless_container_runtime}}`
+    ///
+    ///    Renders `..foo..` only if DT does NOT run in a podman or docker
+    ///    container.
+    /// 2. `{{#unless_container_runtime "lxc"}}..foo..{{else}}..bar..{{/unless_container_runtime}}`
+    ///
+    ///    Renders `..foo..` only if DT does NOT run in an LXC container,
+    ///    renders `..bar..` otherwise.
+    ///
+    /// [`if_container_runtime`]: if_container_runtime
+    pub fn unless_container_runtime<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 1 argument, {1} found
+
+    Usage:
+        1. {{{{#{0} "podman,docker"}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if DT does NOT run in a podman or docker
+           container
+
+        2. {{{{#{0} "lxc"}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if DT does NOT run in an LXC container,
+           renders `..bar..` otherwise
+
+    Recognized runtimes: `podman`, `docker`, `lxc`"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if container_runtime_matches(h, &docmsg)? {
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        } else {
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        }
+        Ok(())
+    }
+
+    /// Address probed by the network helpers when none is given.
+    const DEFAULT_PROBE_ADDR: &str = "1.1.1.1:53";
+    /// Timeout in milliseconds of the network helpers' probe when none is
+    /// given.
+    const DEFAULT_PROBE_TIMEOUT_MS: u64 = 1000;
+
+    /// Checks the parameters given to the network helpers, returns whether
+    /// the probed address is reachable.
+    fn network_reachable(h: &Helper, docmsg: &str) -> Result<bool, RenderError> {
+        if h.params().len() > 2 {
+            return Err(RenderError::new(docmsg));
+        }
+        let addr = match h.param(0) {
+            Some(addr) => addr.value().render(),
+            None => DEFAULT_PROBE_ADDR.to_owned(),
+        };
+        let timeout_ms = match h.param(1) {
+            Some(timeout) => match timeout.value().as_u64() {
+                Some(timeout) => timeout,
+                None => return Err(RenderError::new(docmsg)),
+            },
+            None => DEFAULT_PROBE_TIMEOUT_MS,
+        };
+        let reachable = is_reachable(&addr, std::time::Duration::from_millis(timeout_ms));
+        log::debug!(
+            "'{}' is {}reachable (timeout: {}ms)",
+            addr,
+            if reachable { "" } else { "NOT " },
+            timeout_ms,
+        );
+        Ok(reachable)
+    }
+
+    /// A templating helper that tests if the machine has network
+    /// connectivity, by connecting to an address over TCP with a timeout.
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#if_network}}..foo..{{/if_network}}`
+    ///
+    ///    Renders `..foo..` only if `1.1.1.1:53` is reachable within 1000
+    ///    milliseconds.
+    /// 2. `{{#if_network "example.com:443" 300}}..foo..{{else}}..bar..{{/if_network}}`
+    ///
+    ///    Renders `..foo..` only if `example.com:443` is reachable within 300
+    ///    milliseconds, renders `..bar..` otherwise.
+    pub fn if_network<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected up to 2 arguments (an address and a timeout in milliseconds), {1} found
+
+    Usage:
+        1. {{{{#{0}}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if {2} is reachable within {3}ms
+
+        2. {{{{#{0} "example.com:443" 300}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if example.com:443 is reachable within
+           300ms, renders `..bar..` otherwise"#,
+            h.name(),
+            h.params().len(),
+            DEFAULT_PROBE_ADDR,
+            DEFAULT_PROBE_TIMEOUT_MS,
+        );
+
+        if network_reachable(h, &docmsg)? {
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        } else {
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        }
+        Ok(())
+    }
+
+    /// A templating helper that tests if the machine has NO network
+    /// connectivity.  It is the negated version of [`if_network`].
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#unless_network}}..foo..{{/unless_network}}`
+    ///
+    ///    Renders `..foo..` only if `1.1.1.1:53` is NOT reachable within
+    ///    1000 milliseconds.
+    /// 2. `{{#unless_network "example.com:443" 300}}..foo..{{else}}..bar..{{/unless_network}}`
+    ///
+    ///    Renders `..foo..` only if `example.com:443` is NOT reachable within
+    ///    300 milliseconds, renders `..bar..` otherwise.
+    ///
+    /// [`if_network`]: if_network
+    pub fn unless_network<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected up to 2 arguments (an address and a timeout in milliseconds), {1} found
+
+    Usage:
+        1. {{{{#{0}}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if {2} is NOT reachable within {3}ms
+
+        2. {{{{#{0} "example.com:443" 300}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if example.com:443 is NOT reachable within
+           300ms, renders `..bar..` otherwise"#,
+            h.name(),
+            h.params().len(),
+            DEFAULT_PROBE_ADDR,
+            DEFAULT_PROBE_TIMEOUT_MS,
+        );
+
+        if network_reachable(h, &docmsg)? {
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        } else {
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        }
+        Ok(())
+    }
+
+    /// A templating helper that tests if the machine is running on battery
+    /// power, see [`on_battery`].  Machines without a battery are considered
+    /// on AC.
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#if_on_battery}}..foo..{{/if_on_battery}}`
+    ///
+    ///    Renders `..foo..` only if the machine is on battery.
+    /// 2. `{{#if_on_battery}}..foo..{{else}}..bar..{{/if_on_battery}}`
+    ///
+    ///    Renders `..foo..` only if the machine is on battery, renders
+    ///    `..bar..` only if it is on AC.
+    ///
+    /// [`on_battery`]: crate::utils::on_battery
+    pub fn if_on_battery<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected no argument, {1} found
+
+    Usage:
+        1. {{{{#{0}}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if the machine is on battery
+
+        2. {{{{#{0}}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if the machine is on battery, renders
+           `..bar..` only if it is on AC"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if !h.params().is_empty() {
+            return Err(RenderError::new(docmsg));
+        }
+
+        if on_battery() {
+            log::debug!("Machine is on battery");
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        } else {
+            log::debug!("Machine is on AC");
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        }
+        Ok(())
+    }
+
+    /// A templating helper that tests if the machine is NOT running on
+    /// battery power.  It is the negated version of [`if_on_battery`].
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#unless_on_battery}}..foo..{{/unless_on_battery}}`
+    ///
+    ///    Renders `..foo..` only if the machine is on AC.
+    /// 2. `{{#unless_on_battery}}..foo..{{else}}..bar..{{/unless_on_battery}}`
+    ///
+    ///    Renders `..foo..` only if the machine is on AC, renders `..bar..`
+    ///    only if it is on battery.
+    ///
+    /// [`if_on_battery`]: if_on_battery
+    pub fn unless_on_battery<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected no argument, {1} found
+
+    Usage:
+        1. {{{{#{0}}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if the machine is on AC
+
+        2. {{{{#{0}}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if the machine is on AC, renders `..bar..`
+           only if it is on battery"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if !h.params().is_empty() {
+            return Err(RenderError::new(docmsg));
+        }
+
+        if on_battery() {
+            log::debug!("Machine is on battery");
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        } else {
+            log::debug!("Machine is on AC");
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        }
+        Ok(())
+    }
+
+    /// Checks the parameter given to the desktop helpers, returns whether
+    /// any of current desktop environments is one of the given desktops,
+    /// case-insensitively.
+    fn desktop_matches(h: &Helper, docmsg: &str) -> Result<bool, RenderError> {
+        let allowed_desktops: Vec<String> = match h.param(0) {
+            Some(v) if h.params().len() == 1 => {
+                if v.value().is_array() {
+                    v.value()
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|elem| elem.render().to_lowercase())
+                        .collect()
+                } else {
+                    v.value()
+                        .render()
+                        .split(',')
+                        .map(|d| d.trim().to_lowercase())
+                        .collect()
+                }
+            }
+            _ => return Err(RenderError::new(docmsg)),
+        };
+        let desktops = current_desktops();
+        log::debug!(
+            "Testing if any of current desktops {:?} is one of {:?}",
+            desktops,
+            allowed_desktops,
+        );
+        Ok(desktops
+            .iter()
+            .any(|desktop| allowed_desktops.contains(desktop)))
+    }
+
+    /// A templating helper that tests if current desktop environment is any
+    /// of the given desktops (case-insensitively), detected with
+    /// [`current_desktops`].
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#if_desktop "gnome,kde"}}..foo..{{/if_desktop}}`
+    ///
+    ///    Renders `..foo..` only if current desktop is GNOME or KDE.
+    /// 2. `{{#if_desktop "sway"}}..foo..{{else}}..bar..{{/if_desktop}}`
+    ///
+    ///    Renders `..foo..` only if current desktop is Sway, renders
+    ///    `..bar..` otherwise (including when no desktop is detected).
+    ///
+    /// [`current_desktops`]: crate::utils::current_desktops
+    pub fn if_desktop<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 1 argument, {1} found
+
+    Usage:
+        1. {{{{#{0} "gnome,kde"}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if current desktop is GNOME or KDE
+
+        2. {{{{#{0} "sway"}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if current desktop is Sway, renders
+           `..bar..` otherwise
+
+    Desktops are read from `$XDG_CURRENT_DESKTOP`, or `$DESKTOP_SESSION`
+    when the former is unset, and are matched case-insensitively"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if desktop_matches(h, &docmsg)? {
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        } else {
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        }
+        Ok(())
+    }
+
+    /// A templating helper that tests if current desktop environment is NOT
+    /// any of the given desktops.  It is the negated version of
+    /// [`if_desktop`].
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#unless_desktop "gnome,kde"}}..foo..{{/unless_desktop}}`
+    ///
+    ///    Renders `..foo..` only if current desktop is neither GNOME nor
+    ///    KDE.
+    /// 2. `{{#unless_desktop "sway"}}..foo..{{else}}..bar..{{/unless_desktop}}`
+    ///
+    ///    Renders `..foo..` only if current desktop is NOT Sway, renders
+    ///    `..bar..` otherwise.
+    ///
+    /// [`if_desktop`]: if_desktop
+    pub fn unless_desktop<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 1 argument, {1} found
+
+    Usage:
+        1. {{{{#{0} "gnome,kde"}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if current desktop is neither GNOME nor
+           KDE
+
+        2. {{{{#{0} "sway"}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if current desktop is NOT Sway, renders
+           `..bar..` otherwise
+
+    Desktops are read from `$XDG_CURRENT_DESKTOP`, or `$DESKTOP_SESSION`
+    when the former is unset, and are matched case-insensitively"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if desktop_matches(h, &docmsg)? {
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        } else {
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        }
+        Ok(())
+    }
+
+    /// Checks the parameter given to the locale helpers, returns whether
+    /// current locale is one of the given locales, or starts with one of
+    /// them followed by `_`, `.` or `@`, e.g. `en` matches `en_US.UTF-8`.
+    fn locale_matches(h: &Helper, docmsg: &str) -> Result<bool, RenderError> {
+        let allowed_locales: Vec<String> = match h.param(0) {
+            Some(v) if h.params().len() == 1 => {
+                if v.value().is_array() {
+                    v.value()
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|elem| elem.render())
+                        .collect()
+                } else {
+                    v.value()
+                        .render()
+                        .split(',')
+                        .map(|l| l.trim().to_owned())
+                        .collect()
+                }
+            }
+            _ => return Err(RenderError::new(docmsg)),
+        };
+        let locale = match current_locale() {
+            Some(locale) => locale,
+            None => {
+                log::debug!("No locale is set");
+                return Ok(false);
+            }
+        };
+        log::debug!(
+            "Testing if current locale '{}' is one of {:?}",
+            locale,
+            allowed_locales,
+        );
+        Ok(allowed_locales
+            .iter()
+            .any(|allowed| match locale.strip_prefix(allowed.as_str()) {
+                Some(rest) => rest.is_empty() || rest.starts_with(['_', '.', '@']),
+                None => false,
+            }))
+    }
+
+    /// A templating helper that tests if current locale is any of the given
+    /// locales, detected with [`current_locale`].  A given locale also
+    /// matches its more specific variants, e.g. `en` matches `en_US.UTF-8`.
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#if_locale "en_US,en_GB"}}..foo..{{/if_locale}}`
+    ///
+    ///    Renders `..foo..` only if current locale is `en_US` or `en_GB`
+    ///    (with any encoding).
+    /// 2. `{{#if_locale "zh"}}..foo..{{else}}..bar..{{/if_locale}}`
+    ///
+    ///    Renders `..foo..` only if current locale is any Chinese locale,
+    ///    renders `..bar..` otherwise (including when no locale is set).
+    ///
+    /// [`current_locale`]: crate::utils::current_locale
+    pub fn if_locale<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 1 argument, {1} found
+
+    Usage:
+        1. {{{{#{0} "en_US,en_GB"}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if current locale is `en_US` or `en_GB`
+
+        2. {{{{#{0} "zh"}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if current locale is any Chinese locale,
+           renders `..bar..` otherwise
+
+    The locale is read from `$LC_ALL`, or `$LANG` when the former is unset,
+    and `en` matches `en`, `en_US`, `en_US.UTF-8`, etc."#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if locale_matches(h, &docmsg)? {
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        } else {
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        }
+        Ok(())
+    }
+
+    /// A templating helper that tests if current locale is NOT any of the
+    /// given locales.  It is the negated version of [`if_locale`].
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#unless_locale "en_US,en_GB"}}..foo..{{/unless_locale}}`
+    ///
+    ///    Renders `..foo..` only if current locale is neither `en_US` nor
+    ///    `en_GB`.
+    /// 2. `{{#unless_locale "zh"}}..foo..{{else}}..bar..{{/unless_locale}}`
+    ///
+    ///    Renders `..foo..` only if current locale is NOT a Chinese locale,
+    ///    renders `..bar..` otherwise.
+    ///
+    /// [`if_locale`]: if_locale
+    pub fn unless_locale<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 1 argument, {1} found
+
+    Usage:
+        1. {{{{#{0} "en_US,en_GB"}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if current locale is neither `en_US` nor
+           `en_GB`
+
+        2. {{{{#{0} "zh"}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if current locale is NOT a Chinese locale,
+           renders `..bar..` otherwise
+
+    The locale is read from `$LC_ALL`, or `$LANG` when the former is unset,
+    and `en` matches `en`, `en_US`, `en_US.UTF-8`, etc."#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if locale_matches(h, &docmsg)? {
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        } else {
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        }
+        Ok(())
+    }
+
+    /// A templating helper that tests if DT runs as root, i.e. with an
+    /// effective uid of `0`.  It is a shorthand for `{{#if_uid 0}}`, but
+    /// checks the effective uid instead of the real one.
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#if_root}}..foo..{{/if_root}}`
+    ///
+    ///    Renders `..foo..` only if DT runs as root.
+    /// 2. `{{#if_root}}..foo..{{else}}..bar..{{/if_root}}`
+    ///
+    ///    Renders `..foo..` only if DT runs as root, renders `..bar..` only
+    ///    if DT runs as a normal user.
+    pub fn if_root<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected no argument, {1} found
+
+    Usage:
+        1. {{{{#{0}}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if DT runs as root
+
+        2. {{{{#{0}}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if DT runs as root, renders `..bar..`
+           only if DT runs as a normal user"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if !h.params().is_empty() {
+            return Err(RenderError::new(docmsg));
+        }
+
+        if get_effective_uid() == 0 {
+            log::debug!("Running as root");
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        } else {
+            log::debug!("Running as a normal user");
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        }
+        Ok(())
+    }
+
+    /// A templating helper that tests if DT does NOT run as root.  It is the
+    /// negated version of [`if_root`].
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#unless_root}}..foo..{{/unless_root}}`
+    ///
+    ///    Renders `..foo..` only if DT runs as a normal user.
+    /// 2. `{{#unless_root}}..foo..{{else}}..bar..{{/unless_root}}`
+    ///
+    ///    Renders `..foo..` only if DT runs as a normal user, renders
+    ///    `..bar..` only if DT runs as root.
+    ///
+    /// [`if_root`]: if_root
+    pub fn unless_root<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected no argument, {1} found
+
+    Usage:
+        1. {{{{#{0}}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if DT runs as a normal user
+
+        2. {{{{#{0}}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if DT runs as a normal user, renders
+           `..bar..` only if DT runs as root"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if !h.params().is_empty() {
+            return Err(RenderError::new(docmsg));
+        }
+
+        if get_effective_uid() == 0 {
+            log::debug!("Running as root");
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        } else {
+            log::debug!("Running as a normal user");
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        }
+        Ok(())
+    }
+
+    /// A templating helper that tests if the standard output of DT is
+    /// attached to a terminal.
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#if_tty}}..foo..{{/if_tty}}`
+    ///
+    ///    Renders `..foo..` only if the standard output is a terminal.
+    /// 2. `{{#if_tty}}..foo..{{else}}..bar..{{/if_tty}}`
+    ///
+    ///    Renders `..foo..` only if the standard output is a terminal,
+    ///    renders `..bar..` only if the standard output is NOT a terminal
+    ///    (e.g. when it is piped).
+    pub fn if_tty<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected no argument, {1} found
+
+    Usage:
+        1. {{{{#{0}}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if the standard output is a terminal
+
+        2. {{{{#{0}}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if the standard output is a terminal,
+           renders `..bar..` only if the standard output is NOT a terminal"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if !h.params().is_empty() {
+            return Err(RenderError::new(docmsg));
+        }
+
+        if stdout_is_tty() {
+            log::debug!("Standard output is attached to a terminal");
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        } else {
+            log::debug!("Standard output is not attached to a terminal");
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        }
+        Ok(())
+    }
+
+    /// A templating helper that tests if the standard output of DT is NOT
+    /// attached to a terminal.  It is the negated version of [`if_tty`].
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#unless_tty}}..foo..{{/unless_tty}}`
+    ///
+    ///    Renders `..foo..` only if the standard output is NOT a terminal.
+    /// 2. `{{#unless_tty}}..foo..{{else}}..bar..{{/unless_tty}}`
+    ///
+    ///    Renders `..foo..` only if the standard output is NOT a terminal,
+    ///    renders `..bar..` only if the standard output is a terminal.
+    ///
+    /// [`if_tty`]: if_tty
+    pub fn unless_tty<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected no argument, {1} found
+
+    Usage:
+        1. {{{{#{0}}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if the standard output is NOT a terminal
+
+        2. {{{{#{0}}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if the standard output is NOT a terminal,
+           renders `..bar..` only if the standard output is a terminal"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if !h.params().is_empty() {
+            return Err(RenderError::new(docmsg));
+        }
+
+        if stdout_is_tty() {
+            log::debug!("Standard output is attached to a terminal");
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        } else {
+            log::debug!("Standard output is not attached to a terminal");
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        }
+        Ok(())
+    }
+
+    /// A templating helper that retrieves the value of an environment
+    /// variable, returns a default value when the variable is not set.  It
+    /// is also registered as `get_env`.
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{ env "<NAME>" }}`
+    ///
+    ///     Renders the value of `$NAME`, or nothing if it is not set.
+    /// 2. `{{ env "<NAME>" <default-value> }}`
+    ///
+    ///     Renders the value of `$NAME`, falls back to `<default-value>`.
+    pub fn env(
+        h: &Helper,
+        _: &Handlebars,
+        _: &Context,
+        _rc: &mut RenderContext,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Inline helper `{0}`:
+    expected 1 or 2 arguments, {1} found
+
+    Usage:
+        1. {{{{ {0} "<NAME>" }}}}
+           Renders the value of $NAME, or nothing if it is not set
+
+        2. {{{{ {0} "<NAME>" <default-value> }}}}
+           Renders the value of $NAME, falls back to <default-value>"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        let name = match h.param(0) {
+            Some(name) if h.params().len() <= 2 => name.value().render(),
+            _ => return Err(RenderError::new(docmsg)),
+        };
+        let content = match std::env::var(&name) {
+            Ok(value) => value,
+            Err(_) => {
+                log::debug!("Environment variable '{}' is not set", name);
+                h.param(1).map(|v| v.value().render()).unwrap_or_default()
+            }
+        };
+
+        out.write(&content)?;
+
+        Ok(())
+    }
+
+    /// Checks the parameters given to the env helpers, returns whether the
+    /// given environment variable is set to any of the given values.
+    fn env_matches(h: &Helper, docmsg: &str) -> Result<bool, RenderError> {
+        let (name, allowed_values): (String, Vec<String>) = match (h.param(0), h.param(1)) {
+            (Some(name), Some(v)) if h.params().len() == 2 => (
+                name.value().render(),
+                if v.value().is_array() {
+                    v.value()
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|elem| elem.render())
+                        .collect()
+                } else {
+                    v.value()
+                        .render()
+                        .split(',')
+                        .map(|value| value.trim().to_owned())
+                        .collect()
+                },
+            ),
+            _ => return Err(RenderError::new(docmsg)),
+        };
+        match std::env::var(&name) {
+            Ok(value) => {
+                log::debug!(
+                    "Testing if environment variable '{}' ('{}') is one of {:?}",
+                    name,
+                    value,
+                    allowed_values,
+                );
+                Ok(allowed_values.contains(&value))
+            }
+            Err(_) => {
+                log::debug!("Environment variable '{}' is not set", name);
+                Ok(false)
+            }
+        }
+    }
+
+    /// A templating helper that tests if an environment variable is set to
+    /// any of the given values.
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#if_env "TERM" "xterm-kitty,foot"}}..foo..{{/if_env}}`
+    ///
+    ///    Renders `..foo..` only if `$TERM` is `xterm-kitty` or `foot`.
+    /// 2. `{{#if_env "XDG_SESSION_TYPE" "wayland"}}..foo..{{else}}..bar..{{/if_env}}`
+    ///
+    ///    Renders `..foo..` only if `$XDG_SESSION_TYPE` is `wayland`,
+    ///    renders `..bar..` otherwise (including when it is not set).
+    pub fn if_env<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 2 arguments, {1} found
+
+    Usage:
+        1. {{{{#{0} "TERM" "xterm-kitty,foot"}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if $TERM is `xterm-kitty` or `foot`
+
+        2. {{{{#{0} "XDG_SESSION_TYPE" "wayland"}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if $XDG_SESSION_TYPE is `wayland`, renders
+           `..bar..` otherwise"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if env_matches(h, &docmsg)? {
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        } else {
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        }
+        Ok(())
+    }
+
+    /// A templating helper that tests if an environment variable is NOT set
+    /// to any of the given values.  It is the negated version of
+    /// [`if_env`].
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#unless_env "TERM" "xterm-kitty,foot"}}..foo..{{/unless_env}}`
+    ///
+    ///    Renders `..foo..` only if `$TERM` is neither `xterm-kitty` nor
+    ///    `foot` (including when it is not set).
+    /// 2. `{{#unless_env "XDG_SESSION_TYPE" "wayland"}}..foo..{{else}}..bar..{{/unless_env}}`
+    ///
+    ///    Renders `..foo..` only if `$XDG_SESSION_TYPE` is NOT `wayland`,
+    ///    renders `..bar..` otherwise.
+    ///
+    /// [`if_env`]: if_env
+    pub fn unless_env<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 2 arguments, {1} found
+
+    Usage:
+        1. {{{{#{0} "TERM" "xterm-kitty,foot"}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if $TERM is neither `xterm-kitty` nor
+           `foot`
+
+        2. {{{{#{0} "XDG_SESSION_TYPE" "wayland"}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if $XDG_SESSION_TYPE is NOT `wayland`,
+           renders `..bar..` otherwise"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if env_matches(h, &docmsg)? {
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        } else {
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        }
+        Ok(())
+    }
+
+    /// A templating helper that renders its inner block a given number of
+    /// times.  The zero-based index of current repetition is available as
+    /// `@index` in the inner block.
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#repeat 3}}workspace-{{@index}} {{/repeat}}`
+    ///
+    ///    Renders `workspace-0 workspace-1 workspace-2 `.  Nothing is
+    ///    rendered when the count is zero or negative.
+    pub fn repeat<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 1 integer argument, {1} argument(s) found
+
+    Usage:
+        1. {{{{#{0} 3}}}}workspace-{{{{@index}}}} {{{{/{0}}}}}
+           Renders `workspace-0 workspace-1 workspace-2 `, nothing is rendered
+           when the count is zero or negative"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if h.params().len() != 1 {
+            return Err(RenderError::new(docmsg));
+        }
+        let count = match h.param(0).unwrap().value().as_i64() {
+            Some(count) => count,
+            None => return Err(RenderError::new(docmsg)),
+        };
+
+        if let Some(t) = h.template() {
+            let block = rc.block().cloned().unwrap_or_default();
+            rc.push_block(block);
+            for i in 0..count.max(0) {
+                if let Some(block) = rc.block_mut() {
+                    block.set_local_var("index", to_json(i));
+                }
+                t.render(r, ctx, rc, out)?;
+            }
+            rc.pop_block();
+        }
+        Ok(())
+    }
+
+    /// Runs given command with `sh -c`, returns whether it exits with status
+    /// 0.  Standard output and standard error of the command are discarded.
+    fn cmd_succeeds(cmd: &str) -> bool {
+        match std::process::Command::new("sh")
+            .arg("-c")
+            .arg(cmd)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+        {
+            Ok(status) => {
+                log::debug!("Command '{}' exited with {}", cmd, status);
+                status.success()
+            }
+            Err(e) => {
+                crate::utils::warning!("Failed to run command '{}': {}", cmd, e);
+                false
+            }
+        }
+    }
+
+    /// A templating helper that tests if a given command succeeds, i.e.
+    /// exits with status 0.  The command is run with `sh -c`, its output is
+    /// ignored.
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#if_cmd_succeeds "pgrep -x pipewire"}}..foo..{{/if_cmd_succeeds}}`
+    ///
+    ///    Renders `..foo..` only if command `pgrep -x pipewire` succeeds.
+    /// 2. `{{#if_cmd_succeeds "pgrep -x pipewire"}}..foo..{{else}}..bar..{{/if_cmd_succeeds}}`
+    ///
+    ///    Renders `..foo..` only if command `pgrep -x pipewire` succeeds,
+    ///    renders `..bar..` only if it fails.
+    pub fn if_cmd_succeeds<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 1 argument, {1} found
+
+    Usage:
+        1. {{{{#{0} "pgrep -x pipewire"}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if command `pgrep -x pipewire` succeeds
+
+        2. {{{{#{0} "pgrep -x pipewire"}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if command `pgrep -x pipewire` succeeds,
+           renders `..bar..` only if it fails"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if h.params().len() != 1 {
+            return Err(RenderError::new(docmsg));
+        }
+
+        let cmd = h.param(0).unwrap().value().render();
+        if cmd_succeeds(&cmd) {
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        } else {
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        }
+        Ok(())
+    }
+
+    /// A templating helper that tests if a given command fails, i.e. exits
+    /// with a non-zero status.  It is the negated version of
+    /// [`if_cmd_succeeds`].
+    ///
+    /// Usage:
+    ///
+    /// 1. `{{#unless_cmd_succeeds "pgrep -x pipewire"}}..foo..{{/unless_cmd_succeeds}}`
+    ///
+    ///    Renders `..foo..` only if command `pgrep -x pipewire` fails.
+    /// 2. `{{#unless_cmd_succeeds "pgrep -x pipewire"}}..foo..{{else}}..bar..{{/unless_cmd_succeeds}}`
+    ///
+    ///    Renders `..foo..` only if command `pgrep -x pipewire` fails,
+    ///    renders `..bar..` only if it succeeds.
+    ///
+    /// [`if_cmd_succeeds`]: if_cmd_succeeds
+    pub fn unless_cmd_succeeds<'reg, 'rc>(
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let docmsg = format!(
+            r#"
+Block helper `#{0}`:
+    expected exactly 1 argument, {1} found
+
+    Usage:
+        1. {{{{#{0} "pgrep -x pipewire"}}}}..foo..{{{{/{0}}}}}
+           Renders `..foo..` only if command `pgrep -x pipewire` fails
+
+        2. {{{{#{0} "pgrep -x pipewire"}}}}..foo..{{{{else}}}}..bar..{{{{/{0}}}}}
+           Renders `..foo..` only if command `pgrep -x pipewire` fails,
+           renders `..bar..` only if it succeeds"#,
+            h.name(),
+            h.params().len(),
+        );
+
+        if h.params().len() != 1 {
+            return Err(RenderError::new(docmsg));
+        }
+
+        let cmd = h.param(0).unwrap().value().render();
+        if cmd_succeeds(&cmd) {
+            h.inverse().map(|t| t.render(r, ctx, rc, out)).transpose()?;
+        } else {
+            h.template()
+                .map(|t| t.render(r, ctx, rc, out))
+                .transpose()?;
+        }
+        Ok(())
+    }
 }
 
 // Author: Blurgy <gy@blurgy.xyz>
@@ -1,15 +1,24 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
+    fmt,
+    io::Write,
+    os::unix::{
+        ffi::OsStrExt,
+        fs::{DirBuilderExt, OpenOptionsExt},
+    },
     path::{Path, PathBuf},
     rc::Rc,
+    time::{Duration, Instant},
 };
 
 use crate::{
     config::*,
     error::{Error as AppError, Result},
-    item::Operate,
+    item::{download_path, file_path, Operate},
     registry::{Register, Registry},
+    utils,
 };
+use url::Url;
 
 /// Expands tildes and globs in [`sources`], returns the updated config
 /// object.
@@ -26,17 +35,446 @@ use crate::{
 /// [`base`]: crate::config::Group::base
 /// [`target`]: crate::config::Group::target
 pub(crate) fn expand(config: DTConfig) -> Result<DTConfig> {
+    expand_explained(config, &mut Vec::new(), &mut Vec::new())
+}
+
+/// Why a source path is included in or excluded from a group.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Decision {
+    /// Included since it matched (or is a descendant of a directory that
+    /// matched) the contained glob in the group's [`sources`].
+    ///
+    /// [`sources`]: crate::config::Group::sources
+    Matched(PathBuf),
+    /// Excluded since it is host-specific for another host.
+    OtherHost,
+    /// Excluded since its name matched the contained pattern in the group's
+    /// [ignored names].
+    ///
+    /// [ignored names]: crate::config::Group::get_ignore_patterns
+    Ignored(String),
+    /// Excluded since it is the [`Sidecar`] of another item.
+    ///
+    /// [`Sidecar`]: crate::config::Sidecar
+    Sidecar,
+    /// Excluded since it is deeper than the group's [`max_depth`].
+    ///
+    /// [`max_depth`]: crate::config::Group::max_depth
+    ExceedsMaxDepth,
+    /// Excluded since the contained group has a higher priority on the same
+    /// target path.
+    LostPriority(GroupName),
+}
+
+/// A recorded [`Decision`] on a source path, see [`explain`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Explanation {
+    /// Name of the group where the decision was made.
+    pub group: GroupName,
+    /// Path to the source the decision was made on.
+    pub source: PathBuf,
+    /// The decision itself.
+    pub decision: Decision,
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] '{}': ", self.group, self.source.display())?;
+        match &self.decision {
+            Decision::Matched(pattern) => {
+                write!(f, "included, matched '{}'", pattern.display())
+            }
+            Decision::OtherHost => write!(f, "excluded, meant for another host"),
+            Decision::Ignored(pattern) => write!(f, "excluded, ignored by '{}'", pattern),
+            Decision::Sidecar => write!(f, "excluded, is a sidecar"),
+            Decision::ExceedsMaxDepth => write!(f, "excluded, exceeds max_depth"),
+            Decision::LostPriority(winner) => {
+                write!(f, "excluded, lost priority to group [{}]", winner)
+            }
+        }
+    }
+}
+
+/// Expands given [`DTConfig`] like [`sync`] would do, and returns the
+/// reasons why each source path is included or excluded, in the order they
+/// are decided.
+pub fn explain(config: DTConfig) -> Result<Vec<Explanation>> {
+    let mut explanations = Vec::new();
+    expand_explained(config, &mut explanations, &mut Vec::new())?;
+    Ok(explanations)
+}
+
+/// The group and source that syncs to a target path, after resolving
+/// priorities among groups, see [`resolved`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Resolution {
+    /// Path to the target.
+    pub target: PathBuf,
+    /// Name of the group that won the target.
+    pub group: GroupName,
+    /// Path to the source that is synced to the target.
+    pub source: PathBuf,
+}
+
+impl fmt::Display for Resolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "'{}' <= [{}] '{}'",
+            self.target.display(),
+            self.group,
+            self.source.display(),
+        )
+    }
+}
+
+/// Expands given [`DTConfig`] like [`sync`] would do, and returns which
+/// group and source each target path is synced from after resolving
+/// priorities, sorted by target paths.
+pub fn resolved(config: DTConfig) -> Result<Vec<Resolution>> {
+    let config = expand(config)?;
+    let mut ret = Vec::new();
+    for group in &config.local {
+        for spath in &group.sources {
+            ret.push(Resolution {
+                target: spath.to_owned().make_target(
+                    &group.get_hostname_sep(),
+                    &group.base,
+                    &group.target,
+                    group.strip_components,
+                    group.get_item_renaming_rules(spath)?,
+                )?,
+                group: group.name.to_owned(),
+                source: spath.to_owned(),
+            });
+        }
+    }
+    ret.sort_by(|a, b| a.target.cmp(&b.target));
+    Ok(ret)
+}
+
+/// How a target differs from what syncing would produce, see [`verify`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum DriftKind {
+    /// The target does not exist.
+    Missing,
+    /// The target does not have the expected contents, or is not the
+    /// expected symlink, see [`Operate::target_matches`].
+    Mismatched,
+    /// The target (or its staging file) has unexpected permission bits.
+    Mode {
+        /// The permission bits syncing would set.
+        expected: u32,
+        /// The current permission bits.
+        actual: u32,
+    },
+}
+
+/// A target that drifted from what syncing would produce, see [`verify`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Drift {
+    /// Name of the group the source belongs to.
+    pub group: GroupName,
+    /// Path to the source.
+    pub source: PathBuf,
+    /// Path to the target.
+    pub target: PathBuf,
+    /// How the target drifted.
+    pub kind: DriftKind,
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] '{}': ", self.group, self.target.display())?;
+        match &self.kind {
+            DriftKind::Missing => write!(f, "missing"),
+            DriftKind::Mismatched => {
+                write!(f, "not up to date with '{}'", self.source.display())
+            }
+            DriftKind::Mode { expected, actual } => {
+                write!(f, "has mode {:04o}, expected {:04o}", actual, expected)
+            }
+        }
+    }
+}
+
+/// Expands given [`DTConfig`] like [`sync`] would do, and checks every
+/// target against what syncing would produce without changing anything.
+/// Items that syncing would skip (see [`skip_empty_renders`]) are not
+/// checked.  Nothing is fetched for remote groups, their targets are
+/// checked against the copies downloaded by the last sync, and items that
+/// were never downloaded are only reported when their targets are missing.
+/// Returns the drifted targets, in the order they are checked.
+///
+/// [`skip_empty_renders`]: crate::config::GlobalConfig::skip_empty_renders
+pub fn verify(config: DTConfig) -> Result<Vec<Drift>> {
+    Ok(drifts(config)?
+        .into_iter()
+        .map(|(drift, _)| drift)
+        .collect())
+}
+
+/// Works like [`verify`], and returns the contents syncing would write to
+/// each drifted target along with it.
+fn drifts(config: DTConfig) -> Result<Vec<(Drift, Vec<u8>)>> {
+    let config = expand(config)?;
+    let mut registry = Registry::default().register_helpers()?.load(&config)?;
+    for group in config.remote.iter().filter(|g| g.is_renderable()) {
+        for url in &group.sources {
+            let dpath = download_path(url, &group.global.staging.0);
+            if dpath.exists() {
+                registry.update(&dpath.to_string_lossy(), &config.context)?;
+            }
+        }
+    }
+    let registry = Rc::new(registry);
+    let mut ret = Vec::new();
+    for group in &config.local {
+        let group_ref = Rc::new(group.to_owned());
+        for spath in group.sources.iter().filter(|s| !s.is_dir()) {
+            let target = spath.to_owned().make_target(
+                &group.get_hostname_sep(),
+                &group.base,
+                &group.target,
+                group.strip_components,
+                group.get_item_renaming_rules(spath)?,
+            )?;
+            let content = spath.get_content(&registry, &group_ref)?;
+            // Syncing leaves the target alone
+            if group.global.skip_empty_renders && group.is_renderable() && content.is_empty() {
+                continue;
+            }
+            let kind = if !target.exists() && !target.is_symlink() {
+                DriftKind::Missing
+            } else if !spath.target_matches(group, &content)? {
+                DriftKind::Mismatched
+            } else if let Some((expected, actual)) = spath.target_mode_drift(group)? {
+                DriftKind::Mode { expected, actual }
+            } else {
+                continue;
+            };
+            ret.push((
+                Drift {
+                    group: group.name.to_owned(),
+                    source: spath.to_owned(),
+                    target,
+                    kind,
+                },
+                content,
+            ));
+        }
+    }
+    for group in &config.remote {
+        let group_ref = Rc::new(group.to_owned());
+        for url in &group.sources {
+            let source = download_path(url, &group.global.staging.0);
+            let target = remote_target(url, group)?;
+            let missing = !target.exists() && !target.is_symlink();
+            let (kind, content) = if source.exists() {
+                let content = url.get_content(&registry, &group_ref)?;
+                if missing {
+                    (DriftKind::Missing, content)
+                } else if !url.target_matches(group, &content)? {
+                    (DriftKind::Mismatched, content)
+                } else {
+                    continue;
+                }
+            } else if missing {
+                (DriftKind::Missing, Vec::new())
+            } else {
+                log::debug!(
+                    target: &group.name.log_target(),
+                    "VERIFY [{}]> '{}' is not downloaded yet, not checking '{}'",
+                    group.name,
+                    url,
+                    target.display(),
+                );
+                continue;
+            };
+            ret.push((
+                Drift {
+                    group: group.name.to_owned(),
+                    source,
+                    target,
+                    kind,
+                },
+                content,
+            ));
+        }
+    }
+    Ok(ret)
+}
+
+/// Difference between the current contents of a target and the contents
+/// syncing would write to it, see [`diff`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ItemDiff {
+    /// Name of the group the source belongs to.
+    pub group: GroupName,
+    /// Path to the source.
+    pub source: PathBuf,
+    /// Path to the target.
+    pub target: PathBuf,
+    /// Current contents of the target, empty if it does not exist.
+    pub current: Vec<u8>,
+    /// Contents syncing would write to the target.
+    pub proposed: Vec<u8>,
+}
+
+impl ItemDiff {
+    /// Formats this difference as a unified diff.  Contents that are not
+    /// valid UTF-8 are only reported to differ.
+    pub fn unified(&self) -> String {
+        match (
+            std::str::from_utf8(&self.current),
+            std::str::from_utf8(&self.proposed),
+        ) {
+            (Ok(current), Ok(proposed)) => similar::TextDiff::from_lines(current, proposed)
+                .unified_diff()
+                .header(
+                    &self.target.display().to_string(),
+                    &self.source.display().to_string(),
+                )
+                .to_string(),
+            _ => format!(
+                "Binary contents of '{}' and '{}' differ\n",
+                self.target.display(),
+                self.source.display(),
+            ),
+        }
+    }
+
+    /// Writes the current and proposed contents to temporary files, and runs
+    /// `tool` (e.g. `nvim -d`) with `sh -c`, with the paths to both files
+    /// appended as arguments.  Returns the exit status of `tool`, which is
+    /// not treated as an error when non-zero, since diff tools commonly
+    /// exit with a non-zero status when contents differ.
+    ///
+    /// The temporary files are only accessible to the current user, and
+    /// their directory must not exist beforehand.
+    pub fn open_with(&self, tool: &str) -> Result<std::process::ExitStatus> {
+        static COUNT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "dt-diff-{}-{}",
+            std::process::id(),
+            COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+        ));
+        let name = self
+            .target
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let (current, proposed) = (
+            dir.join("current").join(&name),
+            dir.join("proposed").join(&name),
+        );
+        let mut builder = std::fs::DirBuilder::new();
+        builder.mode(0o700);
+        builder.create(&dir)?;
+        let status = (|| -> Result<std::process::ExitStatus> {
+            for (path, content) in [(&current, &self.current), (&proposed, &self.proposed)] {
+                builder.create(path.parent().unwrap())?;
+                std::fs::OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .mode(0o600)
+                    .open(path)?
+                    .write_all(content)?;
+            }
+            log::debug!(
+                "DIFF [{}]> Running '{}' on '{}'",
+                self.group,
+                tool,
+                self.target.display(),
+            );
+            Ok(std::process::Command::new("sh")
+                .arg("-c")
+                .arg(format!("{} \"$1\" \"$2\"", tool))
+                .arg("dt-diff")
+                .arg(&current)
+                .arg(&proposed)
+                .status()?)
+        })();
+        if let Err(e) = std::fs::remove_dir_all(&dir) {
+            log::warn!(
+                "DIFF [{}]> Could not remove temporary directory '{}': {}",
+                self.group,
+                dir.display(),
+                e,
+            );
+        }
+        let status = status?;
+        if !status.success() {
+            log::debug!("DIFF [{}]> '{}' exited with {}", self.group, tool, status,);
+        }
+        Ok(status)
+    }
+}
+
+/// Works like [`verify`], and returns the differences between the contents
+/// of every target that is missing or not up to date and the contents
+/// syncing would write to it, in the order they are checked.  With the
+/// [`Symlink`] method, contents of the target are read through the link.
+///
+/// [`Symlink`]: crate::config::SyncMethod::Symlink
+pub fn diff(config: DTConfig) -> Result<Vec<ItemDiff>> {
+    Ok(drifts(config)?
+        .into_iter()
+        .filter(|(drift, _)| matches!(drift.kind, DriftKind::Missing | DriftKind::Mismatched))
+        .map(|(drift, proposed)| ItemDiff {
+            current: std::fs::read(&drift.target).unwrap_or_default(),
+            group: drift.group,
+            source: drift.source,
+            target: drift.target,
+            proposed,
+        })
+        .collect())
+}
+
+/// Works like [`expand`], and records reasons of decisions made on each
+/// source path into `explanations`, and time spent expanding each group into
+/// `durations`.
+fn expand_explained(
+    config: DTConfig,
+    explanations: &mut Vec<Explanation>,
+    durations: &mut Vec<(GroupName, Duration)>,
+) -> Result<DTConfig> {
+    // Remote sources are not expanded, only those for other hosts are
+    // dropped
+    let remote = config
+        .remote
+        .into_iter()
+        .map(|group| {
+            let hostname_sep = group.get_hostname_sep();
+            let hostname = group.global.get_hostname();
+            Ok(RemoteGroup {
+                sources: group
+                    .sources
+                    .iter()
+                    .filter(|s| !s.is_for_other_host(&hostname_sep, &hostname))
+                    .cloned()
+                    .collect(),
+                target: group.target.to_owned().absolute()?,
+                ..group
+            })
+        })
+        .collect::<Result<_>>()?;
+
     let mut ret = DTConfig {
         // Remove `global` and `context` in expanded configuration object.
         // Further references of these two values are referenced via Rc from
         // within groups.
         global: config.global,
         context: config.context,
+        profiles: config.profiles,
+        helpers: config.helpers,
+        filtered: config.filtered,
         local: Vec::new(),
-        remote: Vec::new(),
+        remote,
     };
 
     for original in config.local {
+        let started = Instant::now();
         let mut next = LocalGroup {
             global: Rc::clone(&original.global),
             base: original.base.to_owned().absolute()?,
@@ -46,11 +484,21 @@ pub(crate) fn expand(config: DTConfig) -> Result<DTConfig> {
         };
 
         let group_hostname_sep = original.get_hostname_sep();
+        let hostname = original.global.get_hostname();
+        let precedence = original.global.host_specific_precedence;
 
         // Check for host-specific `base`
-        let host_specific_base = next.base.to_owned().host_specific(&group_hostname_sep);
-        if host_specific_base.exists() {
-            next.base = host_specific_base;
+        next.base = pick_host_specific(next.base, &group_hostname_sep, &hostname, precedence);
+
+        // Skip optional groups whose `base` does not exist
+        if next.optional && !next.base.exists() {
+            utils::warning!(
+                target: &next.name.log_target(),
+                "Group [{}]: skipping optional group since its base '{}' does not exist",
+                next.name,
+                next.base.display(),
+            );
+            continue;
         }
 
         // Check for host-specific `sources`
@@ -63,9 +511,14 @@ pub(crate) fn expand(config: DTConfig) -> Result<DTConfig> {
                     .join(s)
                     .absolute()
                     .unwrap_or_else(|e| panic!("{}", e));
-                let try_s = try_s.host_specific(&group_hostname_sep);
-                if try_s.exists() {
-                    try_s
+                let picked = pick_host_specific(
+                    try_s.to_owned(),
+                    &group_hostname_sep,
+                    &hostname,
+                    precedence,
+                );
+                if picked != try_s {
+                    picked
                 } else {
                     s.to_owned()
                 }
@@ -75,19 +528,71 @@ pub(crate) fn expand(config: DTConfig) -> Result<DTConfig> {
         // Recursively expand source paths
         for s in &sources {
             let s = next.base.join(s);
-            let mut s = expand_recursive(&s, &next.get_hostname_sep(), true)?;
-            next.sources.append(&mut s);
+            let mut expanded = expand_recursive(&s, &next, true, 0, explanations)?;
+            explanations.extend(expanded.iter().map(|p| Explanation {
+                group: next.name.to_owned(),
+                source: p.to_owned(),
+                decision: Decision::Matched(s.to_owned()),
+            }));
+            next.sources.append(&mut expanded);
         }
         next.sources.sort();
+        if next.global.warn_on_duplicate_sources {
+            for pair in next.sources.windows(2).filter(|pair| pair[0] == pair[1]) {
+                utils::warning!(
+                    target: &next.name.log_target(),
+                    "Group [{}]: duplicated source '{}' is collapsed",
+                    next.name,
+                    pair[0].display(),
+                );
+            }
+        }
         next.sources.dedup();
+        durations.push((next.name.to_owned(), started.elapsed()));
         ret.local.push(next);
     }
 
-    let ret = resolve(ret)?;
+    let ret = resolve(ret, explanations)?;
 
-    check_readable(&ret)?;
+    check_readable(ret)
+}
 
-    Ok(ret)
+/// Picks either `path` or its host-specific counterpart for the machine with
+/// given `hostname`, according to the given [`HostSpecificPrecedence`].  `path` itself can be either
+/// host-specific or not.
+///
+/// [`HostSpecificPrecedence`]: crate::config::HostSpecificPrecedence
+fn pick_host_specific(
+    path: PathBuf,
+    hostname_sep: &str,
+    hostname: &str,
+    precedence: HostSpecificPrecedence,
+) -> PathBuf {
+    let host_specific = path.to_owned().host_specific(hostname_sep, hostname);
+    match precedence {
+        HostSpecificPrecedence::Override => {
+            if host_specific.exists() {
+                host_specific
+            } else {
+                path
+            }
+        }
+        HostSpecificPrecedence::Fallback => {
+            let generic = match path.file_name() {
+                Some(filename) => {
+                    path.with_file_name(PathBuf::from(filename).non_host_specific(hostname_sep))
+                }
+                None => path.to_owned(),
+            };
+            if generic.exists() {
+                generic
+            } else if host_specific.exists() {
+                host_specific
+            } else {
+                path
+            }
+        }
+    }
 }
 
 /// Recursively expands glob from a given path.
@@ -96,10 +601,51 @@ pub(crate) fn expand(config: DTConfig) -> Result<DTConfig> {
 /// - If `do_glob` is `false`, `path` must be a directory, then children of `path` are recursively
 ///   expanded.
 ///
+/// `depth` is the depth of `path` relative to the source it is matched from,
+/// directories are not descended into beyond the group's [`max_depth`].
+///
+/// Paths that are excluded are recorded into `explanations`.
+///
 /// Returns a [`Vec`] of the expanded paths.
 ///
+/// [`max_depth`]: crate::config::Group::max_depth
 /// [`Vec`]: Vec
-fn expand_recursive(path: &Path, hostname_sep: &str, do_glob: bool) -> Result<Vec<PathBuf>> {
+fn expand_recursive(
+    path: &Path,
+    group: &LocalGroup,
+    do_glob: bool,
+    depth: usize,
+    explanations: &mut Vec<Explanation>,
+) -> Result<Vec<PathBuf>> {
+    let log_target = group.name.log_target();
+    let hostname_sep = &group.get_hostname_sep();
+    let hostname = &group.global.get_hostname();
+    let precedence = group.global.host_specific_precedence;
+    let explain = |explanations: &mut Vec<Explanation>, source: &Path, decision: Decision| {
+        explanations.push(Explanation {
+            group: group.name.to_owned(),
+            source: source.to_owned(),
+            decision,
+        })
+    };
+    let ignore_patterns = group
+        .get_ignore_patterns()
+        .iter()
+        .map(|p| glob::Pattern::new(p))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    // The ignore pattern that matches the basename of given path, if any
+    let ignored_by = |path: &Path| {
+        let name = path.file_name()?.to_string_lossy();
+        ignore_patterns
+            .iter()
+            .find(|p| p.matches(&name))
+            .map(|p| p.as_str().to_owned())
+    };
+    // Whether a directory at the given depth should be descended into
+    let descendable = |depth: usize| match group.max_depth {
+        Some(max_depth) => depth < max_depth,
+        None => true,
+    };
     if do_glob {
         let globbing_options = glob::MatchOptions {
             case_sensitive: true,
@@ -112,19 +658,26 @@ fn expand_recursive(path: &Path, hostname_sep: &str, do_glob: bool) -> Result<Ve
             .map(|x| {
                 x.unwrap_or_else(|_| panic!("Failed globbing source path '{}'", path.display(),))
             })
-            // Filter out paths that are meant for other hosts
-            .filter(|x| !x.is_for_other_host(hostname_sep))
-            // **After** filtering out paths that are meant for other
-            // hosts, replace current path to its host-specific
-            // counterpart if it exists.
-            .map(|x| {
-                let host_specific_x = x.to_owned().host_specific(hostname_sep);
-                if host_specific_x.exists() {
-                    host_specific_x
+            // Filter out paths that are meant for other hosts, ignored
+            // paths, and sidecars, which only configure other items
+            .filter(|x| {
+                let decision = if x.is_for_other_host(hostname_sep, hostname) {
+                    Decision::OtherHost
+                } else if let Some(pattern) = ignored_by(x) {
+                    Decision::Ignored(pattern)
+                } else if Sidecar::is_sidecar(x) {
+                    Decision::Sidecar
                 } else {
-                    x
-                }
+                    return true;
+                };
+                explain(explanations, x, decision);
+                false
             })
+            // **After** filtering out paths that are meant for other
+            // hosts, replace current path to its host-specific
+            // counterpart (or vice versa) according to the configured
+            // precedence.
+            .map(|x| pick_host_specific(x, hostname_sep, hostname, precedence))
             // Convert to absolute paths
             .map(|x| {
                 x.to_owned().absolute().unwrap_or_else(|_| {
@@ -133,18 +686,53 @@ fn expand_recursive(path: &Path, hostname_sep: &str, do_glob: bool) -> Result<Ve
             })
             .collect();
         if initial.is_empty() {
-            log::warn!("'{}' did not match anything", path.display());
+            let literal = path.to_string_lossy();
+            if group.global.on_missing_source.is_some()
+                && glob::Pattern::escape(&literal) == literal
+                && !path.exists()
+                && !path.is_symlink()
+            {
+                handle_missing_source(group, path, "does not exist")?;
+            } else {
+                utils::warning!(
+                    target: &log_target,
+                    "'{}' did not match anything",
+                    path.display(),
+                );
+            }
         }
 
         let mut ret: Vec<PathBuf> = Vec::new();
         for p in initial {
             if p.is_file() {
                 ret.push(p);
+            } else if p.is_dir() && p.is_symlink() && !group.is_symlink_followed() {
+                log::debug!(
+                    target: &log_target,
+                    "Not descending into symlinked directory '{}' in group '{}'",
+                    p.display(),
+                    group.name,
+                );
+                ret.push(p);
             } else if p.is_dir() {
-                ret.append(&mut expand_recursive(&p, hostname_sep, false)?);
+                if descendable(0) {
+                    ret.append(&mut expand_recursive(&p, group, false, 0, explanations)?);
+                } else {
+                    explain(explanations, &p, Decision::ExceedsMaxDepth);
+                    log::debug!(
+                        target: &log_target,
+                        "Skipping '{}' since it exceeds max_depth of group '{}'",
+                        p.display(),
+                        group.name,
+                    );
+                }
             } else {
-                log::warn!("Skipping unimplemented file type at '{}'", p.display(),);
-                log::trace!("{:#?}", p.symlink_metadata()?);
+                utils::warning!(
+                    target: &log_target,
+                    "Skipping unimplemented file type at '{}'",
+                    p.display(),
+                );
+                log::trace!(target: &log_target, "{:#?}", p.symlink_metadata()?);
             }
         }
 
@@ -155,30 +743,65 @@ fn expand_recursive(path: &Path, hostname_sep: &str, do_glob: bool) -> Result<Ve
                 x.unwrap_or_else(|_| panic!("Cannot read dir '{}' properly", path.display()))
                     .path()
             })
-            // Filter out paths that are meant for other hosts
-            .filter(|x| !x.is_for_other_host(hostname_sep))
-            // **After** filtering out paths that are meant for other
-            // hosts, replace current path to its host-specific
-            // counterpart if it exists.
-            .map(|x| {
-                let host_specific_x = x.to_owned().host_specific(hostname_sep);
-                if host_specific_x.exists() {
-                    host_specific_x
+            // Filter out paths that are meant for other hosts, ignored
+            // paths, and sidecars, which only configure other items
+            .filter(|x| {
+                let decision = if x.is_for_other_host(hostname_sep, hostname) {
+                    Decision::OtherHost
+                } else if let Some(pattern) = ignored_by(x) {
+                    Decision::Ignored(pattern)
+                } else if Sidecar::is_sidecar(x) {
+                    Decision::Sidecar
                 } else {
-                    x
-                }
+                    return true;
+                };
+                explain(explanations, x, decision);
+                false
             })
+            // **After** filtering out paths that are meant for other
+            // hosts, replace current path to its host-specific
+            // counterpart (or vice versa) according to the configured
+            // precedence.
+            .map(|x| pick_host_specific(x, hostname_sep, hostname, precedence))
             .collect();
 
         let mut ret: Vec<PathBuf> = Vec::new();
         for p in initial {
             if p.is_file() {
                 ret.push(p);
+            } else if p.is_dir() && p.is_symlink() && !group.is_symlink_followed() {
+                log::debug!(
+                    target: &log_target,
+                    "Not descending into symlinked directory '{}' in group '{}'",
+                    p.display(),
+                    group.name,
+                );
+                ret.push(p);
             } else if p.is_dir() {
-                ret.append(&mut expand_recursive(&p, hostname_sep, false)?);
+                if descendable(depth + 1) {
+                    ret.append(&mut expand_recursive(
+                        &p,
+                        group,
+                        false,
+                        depth + 1,
+                        explanations,
+                    )?);
+                } else {
+                    explain(explanations, &p, Decision::ExceedsMaxDepth);
+                    log::debug!(
+                        target: &log_target,
+                        "Skipping '{}' since it exceeds max_depth of group '{}'",
+                        p.display(),
+                        group.name,
+                    );
+                }
             } else {
-                log::warn!("Skipping unimplemented file type at '{}'", p.display(),);
-                log::trace!("{:#?}", p.symlink_metadata()?);
+                utils::warning!(
+                    target: &log_target,
+                    "Skipping unimplemented file type at '{}'",
+                    p.display(),
+                );
+                log::trace!(target: &log_target, "{:#?}", p.symlink_metadata()?);
             }
         }
 
@@ -189,22 +812,28 @@ fn expand_recursive(path: &Path, hostname_sep: &str, do_glob: bool) -> Result<Ve
 /// Resolve priorities within expanded [`DTConfig`], this function is called
 /// after [`expand`] so that it can correctly resolve priorities of all
 /// expanded sources, and before [`check_readable`], since it does not have to
-/// query the filesystem.
-fn resolve(config: DTConfig) -> Result<DTConfig> {
+/// query the filesystem.  Sources that are dropped are recorded into
+/// `explanations`.
+fn resolve(config: DTConfig, explanations: &mut Vec<Explanation>) -> Result<DTConfig> {
     // Maps an item to the index of the group which holds the highest priority
     // of it.
     let mut mapping: HashMap<PathBuf, usize> = HashMap::new();
+    // Target paths of each group's sources, in the same order.
+    let mut targets: Vec<Vec<PathBuf>> = Vec::with_capacity(config.local.len());
 
     // Get each item's highest priority group.
     for i in 0..config.local.len() {
         let current_priority = &config.local[i].scope;
+        let mut group_targets = Vec::with_capacity(config.local[i].sources.len());
         for s in &config.local[i].sources {
             let t = s.to_owned().make_target(
                 &config.local[i].get_hostname_sep(),
                 &config.local[i].base,
                 &config.local[i].target,
-                config.local[i].get_renaming_rules(),
+                config.local[i].strip_components,
+                config.local[i].get_item_renaming_rules(s)?,
             )?;
+            group_targets.push(t.to_owned());
             match mapping.get(&t) {
                 Some(prev_group_idx) => {
                     let prev_priority = &config.local[*prev_group_idx].scope;
@@ -221,495 +850,5581 @@ fn resolve(config: DTConfig) -> Result<DTConfig> {
                 }
             }
         }
+        targets.push(group_targets);
     }
 
     // Remove redundant groups.
-    Ok(DTConfig {
-        local: config
-            .local
-            .iter()
-            .enumerate()
-            .map(|(cur_id, group)| LocalGroup {
-                sources: group
-                    .sources
-                    .iter()
-                    .filter(|&s| {
-                        let t = s
-                            .to_owned()
-                            .make_target(
-                                &group.get_hostname_sep(),
-                                &group.base,
-                                &group.target,
-                                group.get_renaming_rules(),
-                            )
-                            .unwrap();
-                        let best_id = *mapping.get(&t).unwrap();
-                        best_id == cur_id
-                    })
-                    .map(|s| s.to_owned())
-                    .collect(),
-                ..group.to_owned()
-            })
-            .collect(),
-        ..config
-    })
-}
+    let mut conflicts: Vec<String> = Vec::new();
+    let local = config
+        .local
+        .iter()
+        .enumerate()
+        .map(|(cur_id, group)| LocalGroup {
+            sources: group
+                .sources
+                .iter()
+                .zip(&targets[cur_id])
+                .filter(|&(s, t)| {
+                    let best_id = mapping[t];
+                    if best_id != cur_id {
+                        conflicts.push(format!(
+                            "'{}' => '{}': dropped from [{}], synced by [{}]",
+                            s.display(),
+                            t.display(),
+                            group.name,
+                            config.local[best_id].name,
+                        ));
+                        explanations.push(Explanation {
+                            group: group.name.to_owned(),
+                            source: s.to_owned(),
+                            decision: Decision::LostPriority(config.local[best_id].name.to_owned()),
+                        });
+                    }
+                    best_id == cur_id
+                })
+                .map(|(s, _)| s.to_owned())
+                .collect(),
+            ..group.to_owned()
+        })
+        .collect();
 
-/// Checks validity of the given [DTConfig].
-fn check_readable(config: &DTConfig) -> Result<()> {
-    for group in &config.local {
-        for s in &group.sources {
-            if std::fs::File::open(s).is_err() {
-                return Err(AppError::IoError(format!(
-                    "'{}' is not readable in group '{}'",
-                    s.display(),
-                    group.name,
-                )));
-            }
-            if !s.is_file() {
-                unreachable!();
+    // Record dropped sources for later review.
+    if let Some(conflict_log) = &config.global.conflict_log {
+        if !conflicts.is_empty() {
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(conflict_log)?;
+            for conflict in &conflicts {
+                writeln!(file, "{}", conflict)?;
             }
         }
     }
 
-    Ok(())
+    Ok(DTConfig { local, ..config })
 }
 
-/// Syncs items specified with given [DTConfig].
-pub fn sync(config: DTConfig, dry_run: bool) -> Result<()> {
-    if config.local.is_empty() {
-        log::warn!("Nothing to be synced");
-        return Ok(());
+/// Checks validity of the given [DTConfig].  Unreadable sources are removed
+/// or reported according to their group's [`on_missing_source`] policy.
+///
+/// [`on_missing_source`]: crate::config::GlobalConfig::on_missing_source
+fn check_readable(mut config: DTConfig) -> Result<DTConfig> {
+    for group in &mut config.local {
+        let mut readable = Vec::with_capacity(group.sources.len());
+        for s in std::mem::take(&mut group.sources) {
+            if std::fs::File::open(&s).is_err() {
+                handle_missing_source(group, &s, "is not readable")?;
+                continue;
+            }
+            // Symlinked directories are only kept as leaf items when the
+            // group does not follow symlinks
+            if !s.is_file() && !(s.is_dir() && s.is_symlink()) {
+                unreachable!();
+            }
+            readable.push(s);
+        }
+        group.sources = readable;
     }
-    log::trace!("Local groups to process: {:#?}", config.local);
 
-    let config = expand(config)?;
-    let registry = Rc::new(Registry::default().register_helpers()?.load(&config)?);
+    Ok(config)
+}
 
-    for group in &config.local {
-        log::info!("Local group: [{}]", group.name);
-        if group.sources.is_empty() {
-            log::debug!("Group [{}]: skipping due to empty group", group.name,);
-            continue;
-        } else {
-            log::debug!(
-                "Group [{}]: {} {} detected",
+/// Reports a listed source that is missing or unreadable according to the
+/// group's [`on_missing_source`] policy.  Returns an error only under the
+/// [`Error`] policy, the caller should skip the source otherwise.
+///
+/// [`on_missing_source`]: crate::config::GlobalConfig::on_missing_source
+/// [`Error`]: OnMissingSource::Error
+fn handle_missing_source(group: &LocalGroup, path: &Path, reason: &str) -> Result<()> {
+    match group.global.on_missing_source.unwrap_or_default() {
+        OnMissingSource::Error => Err(AppError::IoError(format!(
+            "'{}' {} in group '{}'",
+            path.display(),
+            reason,
+            group.name,
+        ))),
+        OnMissingSource::Warn => {
+            utils::warning!(
+                target: &group.name.log_target(),
+                "Group [{}]: skipping '{}' since it {}",
                 group.name,
-                group.sources.len(),
-                if group.sources.len() <= 1 {
-                    "item"
-                } else {
-                    "items"
-                },
+                path.display(),
+                reason,
             );
+            Ok(())
         }
-
-        let group_ref = Rc::new(group.to_owned());
-        for spath in &group.sources {
-            if dry_run {
-                if let Err(e) = spath.populate_dry(Rc::clone(&group_ref)) {
-                    if group.is_failure_ignored() {
-                        log::warn!("Error ignored: {}", e);
-                    } else {
-                        return Err(e);
-                    }
-                }
-            } else {
-                #[allow(clippy::collapsible_else_if)]
-                if let Err(e) = spath.populate(Rc::clone(&group_ref), Rc::clone(&registry)) {
-                    if group.is_failure_ignored() {
-                        log::warn!("Error ignored: {}", e);
-                    } else {
-                        return Err(e);
-                    }
-                }
-            }
+        OnMissingSource::Skip => {
+            log::debug!(
+                target: &group.name.log_target(),
+                "Skipping '{}' in group '{}' since it {}",
+                path.display(),
+                group.name,
+                reason,
+            );
+            Ok(())
         }
     }
-    Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    mod validation {
-        use std::str::FromStr;
+/// Options that affect a single run of [`sync_with_options`].
+#[derive(Clone, Debug, Default)]
+pub struct SyncOptions {
+    /// Shows changes to be made without actually syncing files.
+    pub dry_run: bool,
+    /// When set, overrides every group's [`ignore_failure`] setting for
+    /// this run.
+    ///
+    /// [`ignore_failure`]: crate::config::Group::ignore_failure
+    pub ignore_failure: Option<bool>,
+    /// Continues past errors that are not ignored, and returns all of them
+    /// as an [`Error::MultipleErrors`] after every item has been attempted.
+    ///
+    /// [`Error::MultipleErrors`]: crate::error::Error::MultipleErrors
+    pub keep_going: bool,
+    /// Removes targets that were synced by the previous run with this option
+    /// set, but are no longer produced by the given config.  Previously
+    /// synced targets are recorded in an inventory file under the staging
+    /// root directory, see [`INVENTORY_FILENAME`].  Under [`dry_run`],
+    /// targets to be removed are only reported.
+    ///
+    /// Only targets that are unchanged since they were synced are removed:
+    /// a symlink that still points to where it pointed, or a file whose
+    /// contents still match.  Other orphaned targets are left in place with
+    /// a warning.
+    ///
+    /// The given config must be complete, syncing fails if groups have been
+    /// filtered out of it (see [`DTConfig::filtered`]).
+    ///
+    /// [`dry_run`]: SyncOptions::dry_run
+    pub delete_orphans: bool,
+    /// With [`delete_orphans`], also removes directories that became empty
+    /// after removing orphaned targets, as long as they were created by a
+    /// previous run with [`delete_orphans`] set.  Such directories are
+    /// recorded in the inventory file as well, other directories are never
+    /// removed.
+    ///
+    /// [`delete_orphans`]: SyncOptions::delete_orphans
+    pub prune: bool,
+    /// Does not take the exclusive lock on [`LOCK_FILENAME`] under the
+    /// staging root directory.  By default the lock is held while syncing,
+    /// so that concurrent runs do not race on staging and target files, and
+    /// syncing fails if the lock is held by another run.  No lock is taken
+    /// under [`dry_run`], or when nothing is to be written under the staging
+    /// root directory (no selected group stages its items and
+    /// [`delete_orphans`] is not set), so that the directory is not created
+    /// only for the lock.
+    ///
+    /// [`dry_run`]: SyncOptions::dry_run
+    /// [`delete_orphans`]: SyncOptions::delete_orphans
+    pub no_lock: bool,
+    /// Only processes items whose target would change, i.e. items whose
+    /// target does not already match their (rendered) contents, see
+    /// [`Operate::target_matches`].  Unchanged items are skipped before
+    /// anything is written (including staging files), and are left out of
+    /// the returned [`SyncSummary`].
+    pub only_changed: bool,
+    /// When set, only processes items that changed since this git revision,
+    /// as reported by `git diff --name-only` in the git work tree of each
+    /// group's [`base`], or that are untracked and not ignored.  Groups whose [`base`] is not in a git work tree
+    /// are skipped with a warning.  Other items are left out of the
+    /// returned [`SyncSummary`].
+    ///
+    /// [`base`]: crate::config::Group::base
+    pub since_commit: Option<String>,
+}
 
-        use color_eyre::{eyre::eyre, Report};
-        use pretty_assertions::assert_eq;
+/// Name of the lock file under the staging root directory, see
+/// [`SyncOptions::no_lock`].
+pub const LOCK_FILENAME: &str = ".dt.lock";
 
-        use crate::config::DTConfig;
-        use crate::error::Error as AppError;
+/// Name of the inventory file under the staging root directory, which lists
+/// targets synced by the last run with [`SyncOptions::delete_orphans`] set,
+/// one per line.  Each line holds the kind of the target (`link` or
+/// `file`), a fingerprint of where it points to or of its contents, and its
+/// absolute path, separated by tabs.  Directories created while syncing
+/// these targets are recorded with the kind `dir` and a fingerprint of `-`,
+/// see [`SyncOptions::prune`].
+pub const INVENTORY_FILENAME: &str = ".inventory";
 
-        use super::super::expand;
-        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+/// Kind of a target recorded in the inventory file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RecordKind {
+    /// A symlink, fingerprinted by the path it points to.
+    Link,
+    /// A regular file, fingerprinted by its contents.
+    File,
+    /// A directory created while syncing, not fingerprinted.
+    Dir,
+}
 
-        #[test]
-        fn unreadable_source() -> Result<(), Report> {
-            // setup
-            let source_basename = "src-file-but-unreadable";
-            let base = prepare_directory(
-                get_testroot("syncing")
-                    .join("unreadable_source")
-                    .join("base"),
-                0o755,
-            )?;
-            let _source_path = prepare_file(base.join(source_basename), 0o200)?;
-            let target_path = prepare_directory(
-                get_testroot("syncing")
-                    .join("unreadable_source")
-                    .join("target"),
-                0o755,
-            )?;
+/// A target recorded in the inventory file, see [`INVENTORY_FILENAME`].
+#[derive(Clone, Debug, PartialEq)]
+struct Record {
+    kind: RecordKind,
+    fingerprint: String,
+    path: PathBuf,
+}
 
-            if let Err(err) = expand(
-                DTConfig::from_str(&format!(
-                    r#"
-[[local]]
-name = "source is unreadable"
-base = "{}"
-sources = ["{}"]
-target = "{}""#,
-                    base.display(),
-                    source_basename,
-                    target_path.display(),
-                ))
-                .unwrap(),
-            ) {
-                assert_eq!(
-                err,
-                AppError::IoError(
-                    "'/tmp/dt-testing/syncing/unreadable_source/base/src-file-but-unreadable' is not readable in group 'source is unreadable'"
-                        .to_owned(),
-                ),
-                "{}",
-                err,
-            );
-                Ok(())
-            } else {
-                Err(eyre!(
-                    "This config should not be loaded because source item is not readable"
-                ))
-            }
+impl Record {
+    /// Records `path` as a directory created while syncing.
+    fn dir(path: &Path) -> Self {
+        Self {
+            kind: RecordKind::Dir,
+            fingerprint: "-".to_owned(),
+            path: path.to_owned(),
         }
     }
 
-    mod expansion {
-        use std::{path::PathBuf, str::FromStr};
-
-        use color_eyre::Report;
+    /// Records the current state of `path`, if it is a symlink or a regular
+    /// file.
+    fn of(path: &Path) -> Option<Self> {
+        let (kind, fingerprint) = if path.is_symlink() {
+            let dest = std::fs::read_link(path).ok()?;
+            (
+                RecordKind::Link,
+                utils::short_hash(dest.as_os_str().as_bytes()),
+            )
+        } else if path.is_file() {
+            (
+                RecordKind::File,
+                utils::short_hash(&std::fs::read(path).ok()?),
+            )
+        } else {
+            return None;
+        };
+        Some(Self {
+            kind,
+            fingerprint,
+            path: path.to_owned(),
+        })
+    }
+
+    /// Parses a line of the inventory file.
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(3, '\t');
+        let kind = match fields.next()? {
+            "link" => RecordKind::Link,
+            "file" => RecordKind::File,
+            "dir" => RecordKind::Dir,
+            _ => return None,
+        };
+        Some(Self {
+            kind,
+            fingerprint: fields.next()?.to_owned(),
+            path: PathBuf::from(fields.next()?),
+        })
+    }
+
+    /// Checks whether the recorded path is still in the recorded state.
+    fn is_intact(&self) -> bool {
+        Self::of(&self.path).as_ref() == Some(self)
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}",
+            match self.kind {
+                RecordKind::Link => "link",
+                RecordKind::File => "file",
+                RecordKind::Dir => "dir",
+            },
+            self.fingerprint,
+            self.path.display(),
+        )
+    }
+}
+
+/// Outcome of syncing a single item.
+#[derive(Debug, PartialEq)]
+pub struct ItemSummary {
+    /// Name of the group this item belongs to.
+    pub group: GroupName,
+    /// Path to the (expanded) source item.  For an item of a remote group,
+    /// this is the path it is downloaded to, see [`download_path`].
+    pub source: PathBuf,
+    /// URL of the source item, if it belongs to a remote group.
+    pub url: Option<Url>,
+    /// Result of populating this item.  Only errors that are ignored
+    /// according to the group's [`ignore_failure`] setting are recorded
+    /// here, other errors abort the whole syncing process (or are collected
+    /// and returned at the end, with [`SyncOptions::keep_going`]).
+    ///
+    /// [`ignore_failure`]: crate::config::Group::ignore_failure
+    pub result: Result<()>,
+}
+
+/// Outcome of a run of [`sync`] or [`sync_with_options`].
+#[derive(Debug, Default, PartialEq)]
+pub struct SyncSummary {
+    /// Whether this run was a dry run.
+    pub dry_run: bool,
+    /// Number of processed groups.
+    pub groups: usize,
+    /// Outcomes of all processed items, in the order they are processed.
+    pub items: Vec<ItemSummary>,
+    /// Statistics of all processed groups, in the order they are processed.
+    pub stats: Vec<GroupStats>,
+}
+
+/// Statistics of syncing a single group.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GroupStats {
+    /// Name of the group.
+    pub group: GroupName,
+    /// Number of (expanded) items in this group.
+    pub items: usize,
+    /// Wall-clock time spent expanding this group's sources.
+    pub expanding: Duration,
+    /// Wall-clock time spent populating this group's items.
+    pub populating: Duration,
+}
+
+impl GroupStats {
+    /// Total wall-clock time spent on this group.
+    pub fn total(&self) -> Duration {
+        self.expanding + self.populating
+    }
+}
+
+impl fmt::Display for GroupStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[{}]: {} {} in {:?} (expanding: {:?}, populating: {:?})",
+            self.group,
+            self.items,
+            if self.items <= 1 { "item" } else { "items" },
+            self.total(),
+            self.expanding,
+            self.populating,
+        )
+    }
+}
+
+impl SyncSummary {
+    /// Number of items that are successfully synced.
+    pub fn synced(&self) -> usize {
+        self.items.iter().filter(|item| item.result.is_ok()).count()
+    }
+
+    /// Number of items that failed to sync, with their errors ignored.
+    pub fn failed(&self) -> usize {
+        self.items
+            .iter()
+            .filter(|item| item.result.is_err())
+            .count()
+    }
+}
+
+/// Syncs items specified with given [DTConfig].
+pub fn sync(config: DTConfig, dry_run: bool) -> Result<SyncSummary> {
+    sync_with_options(
+        config,
+        SyncOptions {
+            dry_run,
+            ..Default::default()
+        },
+    )
+}
+
+/// Syncs items specified with given [DTConfig], with given [`SyncOptions`].
+///
+/// When [`fail_on_warning`] is set, an error is returned after syncing if
+/// any warning is logged during this run.  Unless [`SyncOptions::no_lock`]
+/// is set, an error is returned before syncing if another run holds the
+/// lock.
+///
+/// [`fail_on_warning`]: crate::config::GlobalConfig::fail_on_warning
+pub fn sync_with_options(config: DTConfig, options: SyncOptions) -> Result<SyncSummary> {
+    if options.delete_orphans && config.filtered {
+        return Err(AppError::ConfigError(
+            "orphaned targets can only be deleted with a complete config, but groups have been filtered out of it"
+                .to_owned(),
+        ));
+    }
+    let fail_on_warning = config.global.fail_on_warning;
+    // Staging is deferred as a whole when its directory does not exist and
+    // dangling links are allowed, so it is not created for the lock either
+    let staging_deferred = config.global.symlink_broken_ok && !config.global.staging.0.exists();
+    // The inventory of `delete_orphans` is kept under the staging root too
+    let writes_staging = options.delete_orphans || stages(&config);
+    // Released when dropped at the end of this function
+    let _lock = if !options.no_lock && !options.dry_run && !staging_deferred && writes_staging {
+        std::fs::create_dir_all(&config.global.staging.0)?;
+        Some(utils::try_lock(
+            config.global.staging.0.join(LOCK_FILENAME),
+        )?)
+    } else {
+        None
+    };
+    let warnings_before = utils::warning_count();
+    let (dry_run, ignore_hook_failure) = (options.dry_run, config.global.ignore_hook_failure);
+    if let Some(cmd) = &config.global.pre_sync {
+        run_hook("pre_sync", cmd, dry_run, ignore_hook_failure)?;
+    }
+    let post_sync = config.global.post_sync.to_owned();
+    let summary = sync_all(config, options)?;
+    if let Some(cmd) = &post_sync {
+        run_hook("post_sync", cmd, dry_run, ignore_hook_failure)?;
+    }
+    let warnings = utils::warning_count() - warnings_before;
+    if fail_on_warning && warnings > 0 {
+        return Err(AppError::SyncingError(format!(
+            "{} {} logged while `fail_on_warning` is set",
+            warnings,
+            if warnings <= 1 { "warning" } else { "warnings" },
+        )));
+    }
+    Ok(summary)
+}
+
+/// Checks whether syncing given config may write under the staging root
+/// directory, i.e. whether it has any remote group (whose sources are
+/// downloaded there), or any local group that uses [`Symlink`] for its
+/// items or its binary items.
+///
+/// [`Symlink`]: SyncMethod::Symlink
+fn stages(config: &DTConfig) -> bool {
+    !config.remote.is_empty()
+        || config.local.iter().any(|group| {
+            group.get_method() == SyncMethod::Symlink
+                || group.global.default_method_for_binary == Some(SyncMethod::Symlink)
+        })
+}
+
+/// An event that a long-running [`watch`] reacts to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WatchEvent {
+    /// Reloads the config and syncs everything again, e.g. on `SIGHUP`.
+    Reload,
+    /// Stops watching, e.g. on `SIGTERM` or `SIGINT`.
+    Exit,
+}
+
+/// Syncs given config with given [`SyncOptions`], then keeps handling
+/// `events` until a [`WatchEvent::Exit`] arrives or `events` ends.  On every
+/// [`WatchEvent::Reload`], the config is loaded again with `load` (e.g. from
+/// disk) and fully synced.  Failing to load or sync the config on a reload
+/// is logged without stopping, so that a broken config can be fixed and
+/// reloaded, while failing on the first sync is returned as an error.
+/// Returns the number of syncs that succeeded.
+pub fn watch<L, I>(config: DTConfig, mut load: L, options: SyncOptions, events: I) -> Result<usize>
+where
+    L: FnMut() -> Result<DTConfig>,
+    I: IntoIterator<Item = WatchEvent>,
+{
+    sync_with_options(config, options.to_owned())?;
+    let mut synced = 1;
+    for event in events {
+        match event {
+            WatchEvent::Reload => {
+                log::info!("WATCH> Reloading config and syncing again");
+                match load().and_then(|config| sync_with_options(config, options.to_owned())) {
+                    Ok(_) => synced += 1,
+                    Err(e) => log::error!("WATCH> Could not sync after reloading: {}", e),
+                }
+            }
+            WatchEvent::Exit => {
+                log::info!("WATCH> Exiting");
+                break;
+            }
+        }
+    }
+    Ok(synced)
+}
+
+/// Runs the command of given global hook (`pre_sync` or `post_sync`) with
+/// `sh -c`, or only prints it under dry run.  A failing command is an error
+/// unless `ignore_failure` is set, see [`ignore_hook_failure`].
+///
+/// [`ignore_hook_failure`]: crate::config::GlobalConfig::ignore_hook_failure
+fn run_hook(hook: &str, cmd: &str, dry_run: bool, ignore_failure: bool) -> Result<()> {
+    if dry_run {
+        log::info!("HOOK::DRY [{}]> Would run '{}'", hook, cmd);
+        return Ok(());
+    }
+    log::debug!("HOOK [{}]> Running '{}'", hook, cmd);
+    let failure = match std::process::Command::new("sh").arg("-c").arg(cmd).status() {
+        Ok(status) if status.success() => return Ok(()),
+        Ok(status) => format!("`{}` command '{}' exited with {}", hook, cmd, status),
+        Err(e) => format!("failed to run `{}` command '{}': {}", hook, cmd, e),
+    };
+    if ignore_failure {
+        utils::warning!("{}", failure);
+        Ok(())
+    } else {
+        Err(AppError::SyncingError(failure))
+    }
+}
+
+/// Syncs items specified with given [DTConfig], does the actual work of
+/// [`sync_with_options`].
+fn sync_all(config: DTConfig, options: SyncOptions) -> Result<SyncSummary> {
+    let dry_run = options.dry_run;
+    let mut summary = SyncSummary {
+        dry_run,
+        ..Default::default()
+    };
+    if config.local.is_empty() && config.remote.is_empty() {
+        utils::warning!("Nothing to be synced");
+        return Ok(summary);
+    }
+    log::trace!(
+        "Local groups to process: {:?}",
+        config
+            .local
+            .iter()
+            .map(|group| group.name.to_string())
+            .collect::<Vec<_>>(),
+    );
+
+    let mut expanding = Vec::new();
+    let config = expand_explained(config, &mut Vec::new(), &mut expanding)?;
+    let mut registry = Registry::default().register_helpers()?.load(&config)?;
+    let mut fetch_failures = fetch_remote(&config, &mut registry, dry_run);
+    let registry = Rc::new(registry);
+    // Directories along target paths that do not exist yet, those that
+    // exist after syncing are created by this run
+    let missing_dirs = if options.delete_orphans && !dry_run {
+        missing_target_dirs(&config)?
+    } else {
+        Vec::new()
+    };
+
+    // Errors collected when `keep_going` is set
+    let mut errors: Vec<AppError> = Vec::new();
+
+    for group in &config.local {
+        let started = Instant::now();
+        let log_target = group.name.log_target();
+        log::info!(target: &log_target, "Local group: [{}]", group.name);
+        summary.groups += 1;
+        summary.stats.push(GroupStats {
+            group: group.name.to_owned(),
+            items: group.sources.len(),
+            expanding: expanding
+                .iter()
+                .find(|(name, _)| *name == group.name)
+                .map(|(_, duration)| *duration)
+                .unwrap_or_default(),
+            populating: Duration::default(),
+        });
+        if group.dry_run && !dry_run {
+            log::info!(
+                target: &log_target,
+                "Group [{}]: only showing changes to be made (dry run)",
+                group.name,
+            );
+        }
+        // Never log the group as a whole, which holds the context
+        log::trace!(
+            target: &log_target,
+            "Group [{}]: base: '{}', target: '{}', method: {:?}, scope: {:?}, renderable: {}",
+            group.name,
+            group.base.display(),
+            group.target.display(),
+            group.get_method(),
+            group.scope,
+            group.is_renderable(),
+        );
+        if group.sources.is_empty() {
+            log::debug!(
+                target: &log_target,
+                "Group [{}]: skipping due to empty group",
+                group.name,
+            );
+            continue;
+        } else {
+            log::debug!(
+                target: &log_target,
+                "Group [{}]: {} {} detected",
+                group.name,
+                group.sources.len(),
+                if group.sources.len() <= 1 {
+                    "item"
+                } else {
+                    "items"
+                },
+            );
+        }
+
+        let changed = match &options.since_commit {
+            Some(rev) => match utils::git_changed_files(&group.base, rev)? {
+                Some(changed) => Some(
+                    changed
+                        .into_iter()
+                        .map(|p| p.canonicalize().unwrap_or(p))
+                        .collect::<HashSet<_>>(),
+                ),
+                None => {
+                    utils::warning!(
+                        target: &log_target,
+                        "Group [{}]: skipping since base '{}' is not in a git repository",
+                        group.name,
+                        group.base.display(),
+                    );
+                    continue;
+                }
+            },
+            None => None,
+        };
+
+        let group_ref = Rc::new(group.to_owned());
+        let failure_ignored = options
+            .ignore_failure
+            .unwrap_or_else(|| group.is_failure_ignored());
+        for spath in &group.sources {
+            let with_context = |e: AppError| {
+                e.context(format!(
+                    "Group [{}], source '{}'",
+                    group.name,
+                    spath.display()
+                ))
+            };
+            if let (Some(rev), Some(changed)) = (&options.since_commit, &changed) {
+                let canonical = spath.canonicalize().unwrap_or_else(|_| spath.to_owned());
+                if !changed.contains(&canonical) {
+                    log::debug!(
+                        target: &log_target,
+                        "SYNC::UNCHANGED [{}]> '{}' did not change since '{}', skipping",
+                        group.name,
+                        spath.display(),
+                        rev,
+                    );
+                    continue;
+                }
+            }
+            if options.only_changed
+                && !spath.is_dir()
+                && matches!(
+                    spath
+                        .get_content(&registry, &group_ref)
+                        .and_then(|content| spath.target_matches(group, &content)),
+                    Ok(true)
+                )
+            {
+                log::debug!(
+                    target: &log_target,
+                    "SYNC::UNCHANGED [{}]> '{}' is up to date, skipping",
+                    group.name,
+                    spath.display(),
+                );
+                continue;
+            }
+            let result = if dry_run || group.dry_run {
+                spath.populate_dry(Rc::clone(&group_ref))
+            } else {
+                spath.populate(Rc::clone(&group_ref), Rc::clone(&registry))
+            };
+            let result = match result.map_err(with_context) {
+                Ok(()) => Ok(()),
+                Err(e) if failure_ignored => {
+                    utils::warning!(target: &log_target, "Error ignored: {}", e);
+                    Err(e)
+                }
+                Err(e) if options.keep_going => {
+                    log::error!(target: &log_target, "{}", e);
+                    errors.push(e);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            summary.items.push(ItemSummary {
+                group: group.name.to_owned(),
+                source: spath.to_owned(),
+                url: None,
+                result,
+            });
+        }
+        if let Some(stats) = summary.stats.last_mut() {
+            stats.populating = started.elapsed();
+        }
+    }
+    for group in &config.remote {
+        let started = Instant::now();
+        let log_target = group.name.log_target();
+        log::info!(target: &log_target, "Remote group: [{}]", group.name);
+        summary.groups += 1;
+        summary.stats.push(GroupStats {
+            group: group.name.to_owned(),
+            items: group.sources.len(),
+            expanding: Duration::default(),
+            populating: Duration::default(),
+        });
+
+        let group_ref = Rc::new(group.to_owned());
+        let failure_ignored = options
+            .ignore_failure
+            .unwrap_or_else(|| group.is_failure_ignored());
+        for url in &group.sources {
+            let result = if dry_run || group.dry_run {
+                log::info!(
+                    target: &log_target,
+                    "DRYRUN [{}]> '{}' would be fetched and synced to '{}'",
+                    group.name,
+                    url,
+                    group.target.display(),
+                );
+                Ok(())
+            } else if let Some(e) = fetch_failures.remove(&(group.name.to_string(), url.to_owned()))
+            {
+                Err(e)
+            } else {
+                url.populate(Rc::clone(&group_ref), Rc::clone(&registry))
+            };
+            let result = match result
+                .map_err(|e| e.context(format!("Group [{}], source '{}'", group.name, url)))
+            {
+                Ok(()) => Ok(()),
+                Err(e) if failure_ignored => {
+                    utils::warning!(target: &log_target, "Error ignored: {}", e);
+                    Err(e)
+                }
+                Err(e) if options.keep_going => {
+                    log::error!(target: &log_target, "{}", e);
+                    errors.push(e);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            summary.items.push(ItemSummary {
+                group: group.name.to_owned(),
+                source: download_path(url, &group.global.staging.0),
+                url: Some(url.to_owned()),
+                result,
+            });
+        }
+        if let Some(stats) = summary.stats.last_mut() {
+            stats.populating = started.elapsed();
+        }
+    }
+    if !errors.is_empty() {
+        return Err(AppError::multiple(errors));
+    }
+    if options.delete_orphans {
+        delete_orphans(&config, &registry, &missing_dirs, &options)?;
+    }
+    Ok(summary)
+}
+
+/// Downloads every source of the remote groups of given [DTConfig] to its
+/// [`download_path`] under the staging directory, and renders it into
+/// `registry` if its group is renderable.  Nothing is downloaded for groups
+/// that are dry-run.  Failures are keyed by group name and source URL, so
+/// that they are handled along with other errors of their groups when
+/// populating.
+fn fetch_remote<R: Register>(
+    config: &DTConfig,
+    registry: &mut R,
+    dry_run: bool,
+) -> HashMap<(String, Url), AppError> {
+    let mut failures = HashMap::new();
+    for group in config.remote.iter().filter(|g| !dry_run && !g.dry_run) {
+        for url in &group.sources {
+            let dpath = download_path(url, &group.global.staging.0);
+            log::debug!(
+                target: &group.name.log_target(),
+                "FETCH [{}]> '{}' => '{}'",
+                group.name,
+                url,
+                dpath.display(),
+            );
+            let result = utils::fetch(url).and_then(|content| {
+                std::fs::create_dir_all(dpath.parent().unwrap())?;
+                utils::write_atomically(&dpath, &content)?;
+                if group.is_renderable() {
+                    registry.update(&dpath.to_string_lossy(), &config.context)?;
+                }
+                Ok(())
+            });
+            if let Err(e) = result {
+                failures.insert((group.name.to_string(), url.to_owned()), e);
+            }
+        }
+    }
+    failures
+}
+
+/// Renders every source of given [DTConfig] into `output_dir`, regardless
+/// of groups' [`method`]s.  Each item is written to its path relative to
+/// its group's [`target`] under `output_dir`, nothing is linked or staged.
+/// Symlinked directories that are not followed (see [`follow_symlinks`])
+/// have nothing to render, they are reproduced as symlinks pointing to
+/// where they resolve to, like syncing does.  Returns paths to the written
+/// files and links, in the order they are written.
+///
+/// [`follow_symlinks`]: crate::config::Group::follow_symlinks
+/// [`method`]: crate::config::Group::method
+/// [`target`]: crate::config::Group::target
+pub fn render<P: AsRef<Path>>(config: DTConfig, output_dir: P) -> Result<Vec<PathBuf>> {
+    let output_dir = output_dir.as_ref();
+    let config = expand(config)?;
+    let registry = Rc::new(Registry::default().register_helpers()?.load(&config)?);
+    let mut written = Vec::new();
+    for group in &config.local {
+        let group_ref = Rc::new(group.to_owned());
+        for spath in &group.sources {
+            let opath = spath.to_owned().make_target(
+                &group.get_hostname_sep(),
+                &group.base,
+                output_dir,
+                group.strip_components,
+                group.get_item_renaming_rules(spath)?,
+            )?;
+            log::debug!(
+                target: &group.name.log_target(),
+                "RENDER [{}]> '{}' => '{}'",
+                group.name,
+                spath.display(),
+                opath.display(),
+            );
+            std::fs::create_dir_all(opath.parent().unwrap())?;
+            if opath.is_symlink() {
+                std::fs::remove_file(&opath)?;
+            }
+            if spath.is_dir() {
+                std::os::unix::fs::symlink(spath.canonicalize()?, &opath)?;
+                written.push(opath);
+                continue;
+            }
+            let content = spath.get_content(&registry, &group_ref).map_err(|e| {
+                e.context(format!(
+                    "Group [{}], source '{}'",
+                    group.name,
+                    spath.display()
+                ))
+            })?;
+            std::fs::write(&opath, content)?;
+            written.push(opath);
+        }
+    }
+    Ok(written)
+}
+
+/// Renders a single `source` of given [DTConfig] the way syncing would,
+/// without writing anything.  Errors if `source` is not a source of any
+/// group after expanding.
+pub fn render_source<P: AsRef<Path>>(config: DTConfig, source: P) -> Result<Vec<u8>> {
+    let source = source.as_ref().to_owned().absolute()?;
+    let config = expand(config)?;
+    let group = match config.local.iter().find(|g| g.sources.contains(&source)) {
+        Some(group) => Rc::new(group.to_owned()),
+        None => {
+            return Err(AppError::PathError(format!(
+                "'{}' is not a source of any group",
+                source.display(),
+            )))
+        }
+    };
+    let registry = Rc::new(Registry::default().register_helpers()?.load(&config)?);
+    source.get_content(&registry, &group)
+}
+
+/// Gets the path where a remote item `url` of `group` is synced to.
+fn remote_target(url: &Url, group: &RemoteGroup) -> Result<PathBuf> {
+    file_path(&url.to_owned().make_target(
+        &group.get_hostname_sep(),
+        &group.base,
+        &group.target,
+        group.strip_components,
+        group.get_renaming_rules(),
+    )?)
+}
+
+/// Gets the directories along target paths of given (expanded) config that
+/// do not exist yet.
+fn missing_target_dirs(config: &DTConfig) -> Result<Vec<PathBuf>> {
+    let mut tpaths = Vec::new();
+    for group in &config.local {
+        for spath in &group.sources {
+            tpaths.push(spath.to_owned().make_target(
+                &group.get_hostname_sep(),
+                &group.base,
+                &group.target,
+                group.strip_components,
+                group.get_item_renaming_rules(spath)?,
+            )?);
+        }
+    }
+    for group in &config.remote {
+        for url in &group.sources {
+            tpaths.push(remote_target(url, group)?);
+        }
+    }
+    let mut missing: Vec<PathBuf> = tpaths
+        .iter()
+        .flat_map(|tpath| {
+            tpath
+                .ancestors()
+                .skip(1)
+                .take_while(|dir| !dir.exists())
+                .map(Path::to_path_buf)
+        })
+        .collect();
+    missing.sort();
+    missing.dedup();
+    Ok(missing)
+}
+
+/// Removes targets listed in the inventory file but no longer produced by
+/// the given (expanded) config, then records targets of the given config as
+/// the new inventory.  Orphaned targets that changed since they were
+/// recorded are kept.  Targets that are not up to date (e.g. skipped or
+/// failed) keep their previous records.  Directories in `missing_dirs`
+/// that exist now are recorded as created while syncing, and are removed
+/// once emptied by removing orphaned targets when [`SyncOptions::prune`]
+/// is set.  Nothing is removed or recorded under dry run.
+fn delete_orphans<R: Register>(
+    config: &DTConfig,
+    registry: &Rc<R>,
+    missing_dirs: &[PathBuf],
+    options: &SyncOptions,
+) -> Result<()> {
+    let dry_run = options.dry_run;
+    let inventory = config.global.staging.0.join(INVENTORY_FILENAME);
+    // Targets produced by the config, and whether each is up to date
+    let mut targets: Vec<(PathBuf, bool)> = Vec::new();
+    for group in &config.local {
+        let group_ref = Rc::new(group.to_owned());
+        for spath in &group.sources {
+            let tpath = spath.to_owned().make_target(
+                &group.get_hostname_sep(),
+                &group.base,
+                &group.target,
+                group.strip_components,
+                group.get_item_renaming_rules(spath)?,
+            )?;
+            // Never record a source rendered in place as a target
+            if tpath == *spath {
+                continue;
+            }
+            let synced = if spath.is_dir() {
+                std::fs::read_link(&tpath).ok() == spath.canonicalize().ok()
+            } else {
+                spath
+                    .get_content(registry, &group_ref)
+                    .and_then(|content| spath.target_matches(group, &content))
+                    .unwrap_or(false)
+            };
+            targets.push((tpath, synced));
+        }
+    }
+    for group in &config.remote {
+        let group_ref = Rc::new(group.to_owned());
+        for url in &group.sources {
+            // Remote items that were not fetched have no contents to check
+            let synced = url
+                .get_content(registry, &group_ref)
+                .and_then(|content| url.target_matches(group, &content))
+                .unwrap_or(false);
+            targets.push((remote_target(url, group)?, synced));
+        }
+    }
+    let previous: Vec<Record> = match std::fs::read_to_string(&inventory) {
+        Ok(content) => content.lines().filter_map(Record::parse).collect(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+    // Paths that are removed (or would be removed under dry run)
+    let mut removed: HashSet<PathBuf> = HashSet::new();
+    for orphan in previous.iter().filter(|record| {
+        record.kind != RecordKind::Dir && !targets.iter().any(|(tpath, _)| *tpath == record.path)
+    }) {
+        let path = &orphan.path;
+        if !path.exists() && !path.is_symlink() {
+            continue;
+        }
+        if path.is_dir() && !path.is_symlink() {
+            utils::warning!(
+                "Orphaned target '{}' is now a directory, skipping",
+                path.display(),
+            );
+            continue;
+        } else if !orphan.is_intact() {
+            utils::warning!(
+                "Orphaned target '{}' has changed since it was synced, skipping",
+                path.display(),
+            );
+            continue;
+        } else if dry_run {
+            log::info!(
+                "DRYRUN> Orphaned target '{}' would be removed",
+                path.display()
+            );
+        } else {
+            log::info!(
+                "SYNC::DELETE> Removing orphaned target '{}'",
+                path.display()
+            );
+            std::fs::remove_file(path)?;
+        }
+        removed.insert(path.to_owned());
+    }
+    // Directories created while syncing, by this or previous runs
+    let created: HashSet<PathBuf> = previous
+        .iter()
+        .filter(|record| record.kind == RecordKind::Dir)
+        .map(|record| record.path.to_owned())
+        .chain(missing_dirs.iter().cloned())
+        .filter(|dir| dir.is_dir() && !dir.is_symlink())
+        .collect();
+    if options.prune {
+        // Walk up from removed targets, through created directories that
+        // hold nothing but removed paths
+        let mut parents: Vec<PathBuf> = removed
+            .iter()
+            .filter_map(|path| path.parent().map(Path::to_path_buf))
+            .collect();
+        while let Some(dir) = parents.pop() {
+            if removed.contains(&dir)
+                || !created.contains(&dir)
+                || !std::fs::read_dir(&dir)?
+                    .all(|entry| entry.is_ok_and(|entry| removed.contains(&entry.path())))
+            {
+                continue;
+            }
+            if dry_run {
+                log::info!(
+                    "DRYRUN> Emptied directory '{}' would be pruned",
+                    dir.display()
+                );
+            } else {
+                log::info!(
+                    "SYNC::PRUNE> Removing emptied directory '{}'",
+                    dir.display()
+                );
+                std::fs::remove_dir(&dir)?;
+            }
+            parents.extend(dir.parent().map(Path::to_path_buf));
+            removed.insert(dir);
+        }
+    }
+    if !dry_run {
+        std::fs::create_dir_all(&config.global.staging.0)?;
+        let mut created: Vec<&PathBuf> = created
+            .iter()
+            .filter(|dir| !removed.contains(*dir))
+            .collect();
+        created.sort();
+        let content: String = targets
+            .iter()
+            .filter_map(|(tpath, synced)| {
+                if *synced {
+                    Record::of(tpath)
+                } else {
+                    previous
+                        .iter()
+                        .find(|record| record.path == *tpath)
+                        .cloned()
+                }
+            })
+            .chain(created.into_iter().map(|dir| Record::dir(dir)))
+            .map(|record| format!("{}\n", record))
+            .collect();
+        std::fs::write(&inventory, content)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    mod validation {
+        use std::str::FromStr;
+
+        use color_eyre::{eyre::eyre, Report};
+        use pretty_assertions::assert_eq;
+
+        use crate::config::DTConfig;
+        use crate::error::Error as AppError;
+
+        use super::super::expand;
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn unreadable_source() -> Result<(), Report> {
+            // setup
+            let source_basename = "src-file-but-unreadable";
+            let base = prepare_directory(
+                get_testroot("syncing")
+                    .join("unreadable_source")
+                    .join("base"),
+                0o755,
+            )?;
+            let _source_path = prepare_file(base.join(source_basename), 0o200)?;
+            let target_path = prepare_directory(
+                get_testroot("syncing")
+                    .join("unreadable_source")
+                    .join("target"),
+                0o755,
+            )?;
+
+            if let Err(err) = expand(
+                DTConfig::from_str(&format!(
+                    r#"
+[[local]]
+name = "source is unreadable"
+base = "{}"
+sources = ["{}"]
+target = "{}""#,
+                    base.display(),
+                    source_basename,
+                    target_path.display(),
+                ))
+                .unwrap(),
+            ) {
+                assert_eq!(
+                err,
+                AppError::IoError(
+                    "'/tmp/dt-testing/syncing/unreadable_source/base/src-file-but-unreadable' is not readable in group 'source is unreadable'"
+                        .to_owned(),
+                ),
+                "{}",
+                err,
+            );
+                Ok(())
+            } else {
+                Err(eyre!(
+                    "This config should not be loaded because source item is not readable"
+                ))
+            }
+        }
+    }
+
+    mod expansion {
+        use std::{path::PathBuf, str::FromStr};
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::*, item::Operate};
+
+        use super::super::expand;
+        use crate::utils::testing::{
+            capture_logs, captured_logs, get_testroot, prepare_directory, prepare_file,
+        };
+
+        #[test]
+        fn glob() -> Result<(), Report> {
+            let target_path =
+                prepare_directory(get_testroot("syncing").join("glob").join("target"), 0o755)?;
+
+            let config = expand(
+                DTConfig::from_str(&format!(
+                    r#"
+[[local]]
+name = "globbing test"
+base = ".."
+sources = ["dt-c*"]
+target = "{}""#,
+                    target_path.display(),
+                ))
+                .unwrap(),
+            )?;
+            for group in &config.local {
+                assert_eq!(
+                    group.sources,
+                    vec![
+                        PathBuf::from_str("../dt-cli/Cargo.toml")
+                            .unwrap()
+                            .absolute()?,
+                        PathBuf::from_str("../dt-cli/README.md")
+                            .unwrap()
+                            .absolute()?,
+                        PathBuf::from_str("../dt-cli/src/main.rs")
+                            .unwrap()
+                            .absolute()?,
+                        PathBuf::from_str("../dt-core/Cargo.toml")
+                            .unwrap()
+                            .absolute()?,
+                        PathBuf::from_str("../dt-core/README.md")
+                            .unwrap()
+                            .absolute()?,
+                        PathBuf::from_str("../dt-core/src/config.rs")
+                            .unwrap()
+                            .absolute()?,
+                        PathBuf::from_str("../dt-core/src/error.rs")
+                            .unwrap()
+                            .absolute()?,
+                        PathBuf::from_str("../dt-core/src/item.rs")
+                            .unwrap()
+                            .absolute()?,
+                        PathBuf::from_str("../dt-core/src/lib.rs")
+                            .unwrap()
+                            .absolute()?,
+                        PathBuf::from_str("../dt-core/src/registry.rs")
+                            .unwrap()
+                            .absolute()?,
+                        PathBuf::from_str("../dt-core/src/syncing.rs")
+                            .unwrap()
+                            .absolute()?,
+                        PathBuf::from_str("../dt-core/src/utils.rs")
+                            .unwrap()
+                            .absolute()?,
+                    ],
+                );
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn sorting_and_deduping() -> Result<(), Report> {
+            println!("Creating base ..");
+            let base_path = prepare_directory(
+                get_testroot("syncing")
+                    .join("sorting_and_deduping")
+                    .join("base"),
+                0o755,
+            )?;
+            println!("Creating target ..");
+            let target_path = prepare_directory(
+                get_testroot("syncing")
+                    .join("sorting_and_deduping")
+                    .join("target"),
+                0o755,
+            )?;
+            for f in ["A-a", "A-b", "A-c", "B-a", "B-b", "B-c"] {
+                println!("Creating source {} ..", f);
+                prepare_file(base_path.join(f), 0o644)?;
+            }
+
+            let config = expand(
+                DTConfig::from_str(&format!(
+                    r#"
+[[local]]
+name = "sorting and deduping"
+base = "{}"
+sources = ["B-*", "*-c", "A-b", "A-a"]
+target = "{}""#,
+                    base_path.display(),
+                    target_path.display(),
+                ))
+                .unwrap(),
+            )?;
+            for group in config.local {
+                assert_eq!(
+                    group.sources,
+                    vec![
+                        base_path.join("A-a"),
+                        base_path.join("A-b"),
+                        base_path.join("A-c"),
+                        base_path.join("B-a"),
+                        base_path.join("B-b"),
+                        base_path.join("B-c"),
+                    ],
+                );
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn duplicate_warning() -> Result<(), Report> {
+            let base_path = prepare_directory(
+                get_testroot("syncing")
+                    .join("duplicate_warning")
+                    .join("base"),
+                0o755,
+            )?;
+            prepare_file(base_path.join("item"), 0o644)?;
+            prepare_file(base_path.join("other"), 0o644)?;
+            let config = |warn: bool| {
+                DTConfig::from_str(&format!(
+                    r#"
+[global]
+warn_on_duplicate_sources = {}
+
+[[local]]
+name = "duplicates"
+base = "{}"
+sources = ["item", "other", "item"]
+target = "/tmp""#,
+                    warn,
+                    base_path.display(),
+                ))
+            };
+            let duplicate_warnings = || {
+                captured_logs()
+                    .into_iter()
+                    .filter(|(_, msg)| msg.contains("duplicated source"))
+                    .collect::<Vec<_>>()
+            };
+
+            capture_logs();
+            expand(config(false)?)?;
+            assert!(duplicate_warnings().is_empty());
+
+            capture_logs();
+            let expanded = expand(config(true)?)?;
+            assert_eq!(expanded.local[0].sources.len(), 2);
+            let warnings = duplicate_warnings();
+            assert_eq!(warnings.len(), 1);
+            assert!(warnings[0]
+                .1
+                .contains(&base_path.join("item").display().to_string()));
+            Ok(())
+        }
+    }
+
+    mod max_depth {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::config::DTConfig;
+
+        use super::super::expand;
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn deeper_items_are_excluded() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing")
+                    .join("deeper_items_are_excluded")
+                    .join("base"),
+                0o755,
+            )?;
+            let target = prepare_directory(
+                get_testroot("syncing")
+                    .join("deeper_items_are_excluded")
+                    .join("target"),
+                0o755,
+            )?;
+            prepare_file(base.join("src").join("a"), 0o644)?;
+            prepare_file(base.join("src").join("sub").join("b"), 0o644)?;
+            prepare_file(base.join("src").join("sub").join("deeper").join("c"), 0o644)?;
+
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "max depth"
+base = "{}"
+sources = ["src"]
+target = "{}"
+max_depth = 1"#,
+                base.display(),
+                target.display(),
+            ))?)?;
+            assert_eq!(config.local[0].sources, vec![base.join("src").join("a")]);
+
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "unlimited depth"
+base = "{}"
+sources = ["src"]
+target = "{}""#,
+                base.display(),
+                target.display(),
+            ))?)?;
+            assert_eq!(
+                config.local[0].sources,
+                vec![
+                    base.join("src").join("a"),
+                    base.join("src").join("sub").join("b"),
+                    base.join("src").join("sub").join("deeper").join("c"),
+                ],
+            );
+            Ok(())
+        }
+    }
+
+    mod follow_symlinks {
+        use std::{path::PathBuf, str::FromStr};
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{
+            config::DTConfig,
+            syncing::{expand, sync},
+        };
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        /// Prepares a base directory with a regular file and a symlinked
+        /// directory, returns paths to the base and the target.
+        fn prepare(name: &str) -> Result<(PathBuf, PathBuf), Report> {
+            let base = prepare_directory(get_testroot("syncing").join(name).join("base"), 0o755)?;
+            let target = get_testroot("syncing").join(name).join("target");
+            if target.exists() {
+                std::fs::remove_dir_all(&target)?;
+            }
+            let external =
+                prepare_directory(get_testroot("syncing").join(name).join("external"), 0o755)?;
+            prepare_file(external.join("inner"), 0o644)?;
+            prepare_file(base.join("dir").join("file"), 0o644)?;
+            let link = base.join("dir").join("linked");
+            if link.symlink_metadata().is_ok() {
+                std::fs::remove_file(&link)?;
+            }
+            std::os::unix::fs::symlink(&external, &link)?;
+            Ok((base, target))
+        }
+
+        #[test]
+        fn followed_by_default() -> Result<(), Report> {
+            let (base, target) = prepare("followed_by_default")?;
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "follow"
+base = "{}"
+sources = ["dir"]
+target = "{}""#,
+                base.display(),
+                target.display(),
+            ))?)?;
+            assert_eq!(
+                config.local[0].sources,
+                vec![
+                    base.join("dir").join("file"),
+                    base.join("dir").join("linked").join("inner"),
+                ],
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn not_followed() -> Result<(), Report> {
+            let (base, target) = prepare("not_followed")?;
+            let config = format!(
+                r#"
+[[local]]
+name = "no follow"
+base = "{}"
+sources = ["dir"]
+target = "{}"
+method = "Copy"
+follow_symlinks = false"#,
+                base.display(),
+                target.display(),
+            );
+            let expanded = expand(DTConfig::from_str(&config)?)?;
+            assert_eq!(
+                expanded.local[0].sources,
+                vec![
+                    base.join("dir").join("file"),
+                    base.join("dir").join("linked")
+                ],
+            );
+
+            sync(DTConfig::from_str(&config)?, false)?;
+            assert!(target.join("dir").join("file").is_file());
+            assert_eq!(
+                std::fs::read_link(target.join("dir").join("linked"))?,
+                base.join("dir").join("linked").canonicalize()?,
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn switched_to_not_followed() -> Result<(), Report> {
+            let (base, target) = prepare("switched_to_not_followed")?;
+            let config = |follow_symlinks: bool| {
+                DTConfig::from_str(&format!(
+                    r#"
+[[local]]
+name = "switch"
+base = "{}"
+sources = ["dir"]
+target = "{}"
+method = "Copy"
+allow_overwrite = true
+follow_symlinks = {}"#,
+                    base.display(),
+                    target.display(),
+                    follow_symlinks,
+                ))
+            };
+
+            sync(config(true)?, false)?;
+            let linked = target.join("dir").join("linked");
+            assert!(linked.join("inner").is_file());
+            assert!(!linked.is_symlink());
+
+            // Items synced one by one are replaced by the link
+            sync(config(false)?, false)?;
+            assert_eq!(
+                std::fs::read_link(&linked)?,
+                base.join("dir").join("linked").canonicalize()?,
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn foreign_directory_kept() -> Result<(), Report> {
+            let (base, target) = prepare("foreign_directory_kept")?;
+            let foreign = prepare_file(target.join("dir").join("linked").join("foreign"), 0o644)?;
+            let config = DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "foreign"
+base = "{}"
+sources = ["dir"]
+target = "{}"
+method = "Copy"
+allow_overwrite = true
+follow_symlinks = false"#,
+                base.display(),
+                target.display(),
+            ))?;
+
+            assert!(sync(config, false).is_err());
+            assert!(foreign.is_file());
+            Ok(())
+        }
+
+        #[test]
+        fn edited_copy_kept() -> Result<(), Report> {
+            let (base, target) = prepare("edited_copy_kept")?;
+            let config = |follow_symlinks: bool| {
+                DTConfig::from_str(&format!(
+                    r#"
+[[local]]
+name = "edited"
+base = "{}"
+sources = ["dir"]
+target = "{}"
+method = "Copy"
+allow_overwrite = true
+follow_symlinks = {}"#,
+                    base.display(),
+                    target.display(),
+                    follow_symlinks,
+                ))
+            };
+
+            sync(config(true)?, false)?;
+            let inner = target.join("dir").join("linked").join("inner");
+            std::fs::write(&inner, "edited")?;
+
+            assert!(sync(config(false)?, false).is_err());
+            assert_eq!(std::fs::read_to_string(&inner)?, "edited");
+            Ok(())
+        }
+
+        #[test]
+        fn staged_symlinks_replaced() -> Result<(), Report> {
+            let (base, target) = prepare("staged_symlinks_replaced")?;
+            let staging = get_testroot("syncing")
+                .join("staged_symlinks_replaced")
+                .join("staging");
+            let config = |follow_symlinks: bool| {
+                DTConfig::from_str(&format!(
+                    r#"
+[global]
+staging = "{}"
+
+[[local]]
+name = "staged"
+base = "{}"
+sources = ["dir"]
+target = "{}"
+method = "Symlink"
+allow_overwrite = true
+follow_symlinks = {}"#,
+                    staging.display(),
+                    base.display(),
+                    target.display(),
+                    follow_symlinks,
+                ))
+            };
+
+            sync(config(true)?, false)?;
+            let linked = target.join("dir").join("linked");
+            assert!(linked.join("inner").is_symlink());
+
+            sync(config(false)?, false)?;
+            assert_eq!(
+                std::fs::read_link(&linked)?,
+                base.join("dir").join("linked").canonicalize()?,
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn kept_without_overwrite() -> Result<(), Report> {
+            let (base, target) = prepare("kept_without_overwrite")?;
+            let config = |follow_symlinks: bool| {
+                DTConfig::from_str(&format!(
+                    r#"
+[[local]]
+name = "no overwrite"
+base = "{}"
+sources = ["dir"]
+target = "{}"
+method = "Copy"
+allow_overwrite = false
+follow_symlinks = {}"#,
+                    base.display(),
+                    target.display(),
+                    follow_symlinks,
+                ))
+            };
+
+            sync(config(true)?, false)?;
+            let linked = target.join("dir").join("linked");
+            sync(config(false)?, false)?;
+            assert!(!linked.is_symlink());
+            assert!(linked.join("inner").is_file());
+            Ok(())
+        }
+    }
+
+    mod optional {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{
+            config::DTConfig,
+            syncing::{expand, sync},
+        };
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn missing_base_skipped() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing")
+                    .join("missing_base_skipped")
+                    .join("base"),
+                0o755,
+            )?;
+            let missing = get_testroot("syncing")
+                .join("missing_base_skipped")
+                .join("missing");
+            let target = prepare_directory(
+                get_testroot("syncing")
+                    .join("missing_base_skipped")
+                    .join("target"),
+                0o755,
+            )?;
+            prepare_file(base.join("present"), 0o644)?;
+            let config = format!(
+                r#"
+[[local]]
+name = "optional"
+base = "{}"
+sources = ["*"]
+target = "{}"
+method = "Copy"
+optional = true
+
+[[local]]
+name = "normal"
+base = "{}"
+sources = ["*"]
+target = "{}"
+method = "Copy""#,
+                missing.display(),
+                target.display(),
+                base.display(),
+                target.display(),
+            );
+
+            let expanded = expand(DTConfig::from_str(&config)?)?;
+            assert_eq!(expanded.local.len(), 1);
+            assert_eq!(expanded.local[0].name.to_string(), "normal");
+
+            sync(DTConfig::from_str(&config)?, false)?;
+            assert!(target.join("present").is_file());
+            Ok(())
+        }
+    }
+
+    mod binary_method {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn binary_linked_directly() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing")
+                    .join("binary_linked_directly")
+                    .join("base"),
+                0o755,
+            )?;
+            let target = prepare_directory(
+                get_testroot("syncing")
+                    .join("binary_linked_directly")
+                    .join("target"),
+                0o755,
+            )?;
+            let staging = get_testroot("syncing")
+                .join("binary_linked_directly")
+                .join("staging");
+            let bin = prepare_file(base.join("bin"), 0o644)?;
+            std::fs::write(
+                &bin,
+                (0..64 * 1024).map(|i| (i % 256) as u8).collect::<Vec<_>>(),
+            )?;
+            let text = prepare_file(base.join("text"), 0o644)?;
+            std::fs::write(&text, "plain text")?;
+            let config = format!(
+                r#"
+[global]
+staging = "{}"
+method = "Symlink"
+default_method_for_binary = "SymlinkDirect"
+
+[[local]]
+name = "mixed"
+base = "{}"
+sources = ["*"]
+target = "{}""#,
+                staging.display(),
+                base.display(),
+                target.display(),
+            );
+
+            sync(DTConfig::from_str(&config)?, false)?;
+            assert_eq!(std::fs::read_link(target.join("bin"))?, bin);
+            let staged = std::fs::read_link(target.join("text"))?;
+            assert!(staged.starts_with(&staging));
+            assert_eq!(std::fs::read_to_string(staged)?, "plain text");
+            Ok(())
+        }
+    }
+
+    mod host_specific_precedence {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, utils::host_specific_suffix};
+
+        use super::super::expand;
+        use crate::utils::testing::{get_testroot, hostname, prepare_directory, prepare_file};
+
+        #[test]
+        fn host_specific_overrides() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing")
+                    .join("host_specific_overrides")
+                    .join("base"),
+                0o755,
+            )?;
+            let target = prepare_directory(
+                get_testroot("syncing")
+                    .join("host_specific_overrides")
+                    .join("target"),
+                0o755,
+            )?;
+            prepare_file(base.join("generic"), 0o644)?;
+            prepare_file(
+                base.join("generic".to_owned() + &host_specific_suffix("@@", &hostname())),
+                0o644,
+            )?;
+            prepare_file(
+                base.join("only-host".to_owned() + &host_specific_suffix("@@", &hostname())),
+                0o644,
+            )?;
+
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[global]
+host_specific_precedence = "Override"
+
+[[local]]
+name = "host-specific overrides"
+base = "{}"
+sources = ["*"]
+target = "{}""#,
+                base.display(),
+                target.display(),
+            ))?)?;
+            assert_eq!(
+                config.local[0].sources,
+                vec![
+                    base.join("generic".to_owned() + &host_specific_suffix("@@", &hostname())),
+                    base.join("only-host".to_owned() + &host_specific_suffix("@@", &hostname())),
+                ],
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn host_specific_as_fallback() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing")
+                    .join("host_specific_as_fallback")
+                    .join("base"),
+                0o755,
+            )?;
+            let target = prepare_directory(
+                get_testroot("syncing")
+                    .join("host_specific_as_fallback")
+                    .join("target"),
+                0o755,
+            )?;
+            prepare_file(base.join("generic"), 0o644)?;
+            prepare_file(
+                base.join("generic".to_owned() + &host_specific_suffix("@@", &hostname())),
+                0o644,
+            )?;
+            prepare_file(
+                base.join("only-host".to_owned() + &host_specific_suffix("@@", &hostname())),
+                0o644,
+            )?;
+
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[global]
+host_specific_precedence = "Fallback"
+
+[[local]]
+name = "host-specific as fallback"
+base = "{}"
+sources = ["*"]
+target = "{}""#,
+                base.display(),
+                target.display(),
+            ))?)?;
+            assert_eq!(
+                config.local[0].sources,
+                vec![
+                    base.join("generic"),
+                    base.join("only-host".to_owned() + &host_specific_suffix("@@", &hostname())),
+                ],
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn hostname_override() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing")
+                    .join("hostname_override")
+                    .join("base"),
+                0o755,
+            )?;
+            let target = prepare_directory(
+                get_testroot("syncing")
+                    .join("hostname_override")
+                    .join("target"),
+                0o755,
+            )?;
+            prepare_file(base.join("generic"), 0o644)?;
+            prepare_file(base.join("generic@@tatooine"), 0o644)?;
+            prepare_file(base.join("only-naboo@@naboo"), 0o644)?;
+
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[global]
+hostname_override = "tatooine"
+
+[[local]]
+name = "hostname override"
+base = "{}"
+sources = ["*"]
+target = "{}""#,
+                base.display(),
+                target.display(),
+            ))?)?;
+            assert_eq!(
+                config.local[0].sources,
+                vec![base.join("generic@@tatooine")]
+            );
+            Ok(())
+        }
+    }
+
+    mod logging {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::sync};
+
+        use crate::utils::testing::{
+            capture_logs, captured_logs, get_testroot, prepare_directory, prepare_file,
+        };
+
+        #[test]
+        fn group_name_as_target() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing")
+                    .join("group_name_as_target")
+                    .join("base"),
+                0o755,
+            )?;
+            let target = prepare_directory(
+                get_testroot("syncing")
+                    .join("group_name_as_target")
+                    .join("target"),
+                0o755,
+            )?;
+            prepare_file(base.join("item"), 0o644)?;
+
+            capture_logs();
+            sync(
+                DTConfig::from_str(&format!(
+                    r#"
+[[local]]
+name = "logging/target"
+base = "{}"
+sources = ["item"]
+target = "{}""#,
+                    base.display(),
+                    target.display(),
+                ))?,
+                true,
+            )?;
+
+            let group_logs: Vec<_> = captured_logs()
+                .into_iter()
+                .filter(|(_, msg)| msg.contains("[logging/target]"))
+                .collect();
+            assert!(!group_logs.is_empty());
+            for (target, _) in group_logs {
+                assert_eq!(target, "dt::logging::target");
+            }
+            Ok(())
+        }
+    }
+
+    mod error_context {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+
+        use crate::{config::DTConfig, error::Error, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn group_and_source_in_message() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing")
+                    .join("group_and_source_in_message")
+                    .join("base"),
+                0o755,
+            )?;
+            let target = prepare_directory(
+                get_testroot("syncing")
+                    .join("group_and_source_in_message")
+                    .join("target"),
+                0o755,
+            )?;
+            let source = prepare_file(base.join("dir").join("item"), 0o644)?;
+            // A dangling symlink occupies the path where the target's parent
+            // directory should be created, forcing an IO error
+            if target.join("dir").symlink_metadata().is_ok() {
+                std::fs::remove_file(target.join("dir"))?;
+            }
+            std::os::unix::fs::symlink(target.join("nonexistent"), target.join("dir"))?;
+
+            let err = sync(
+                DTConfig::from_str(&format!(
+                    r#"
+[[local]]
+name = "error context"
+base = "{}"
+sources = ["dir"]
+target = "{}""#,
+                    base.display(),
+                    target.display(),
+                ))?,
+                false,
+            )
+            .expect_err("Syncing into a file as directory should fail");
+            match err {
+                Error::IoError(msg) => {
+                    assert!(msg.contains("Group [error context]"));
+                    assert!(msg.contains(&source.display().to_string()));
+                }
+                _ => panic!("Expected an IoError, got {:?}", err),
+            }
+            Ok(())
+        }
+    }
+
+    mod atomic_copy {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn no_temporary_file_remains() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing")
+                    .join("no_temporary_file_remains")
+                    .join("base"),
+                0o755,
+            )?;
+            let target = prepare_directory(
+                get_testroot("syncing")
+                    .join("no_temporary_file_remains")
+                    .join("target"),
+                0o755,
+            )?;
+            let source = prepare_file(base.join("item"), 0o644)?;
+            let config = format!(
+                r#"
+[[local]]
+name = "atomic copy"
+base = "{}"
+sources = ["item"]
+target = "{}"
+method = "Copy""#,
+                base.display(),
+                target.display(),
+            );
+
+            for content in ["first version\n", "second, longer version\n"] {
+                std::fs::write(&source, content)?;
+                sync(DTConfig::from_str(&config)?, false)?;
+                assert_eq!(std::fs::read_to_string(target.join("item"))?, content);
+                let entries: Vec<_> = std::fs::read_dir(&target)?
+                    .map(|entry| entry.map(|entry| entry.file_name()))
+                    .collect::<Result<_, _>>()?;
+                assert_eq!(entries, vec!["item"]);
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn falls_back_to_direct_write() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing")
+                    .join("falls_back_to_direct_write")
+                    .join("base"),
+                0o755,
+            )?;
+            let target = prepare_directory(
+                get_testroot("syncing")
+                    .join("falls_back_to_direct_write")
+                    .join("target"),
+                0o755,
+            )?;
+            let source = prepare_file(base.join("item"), 0o644)?;
+            std::fs::write(&source, "complete content\n")?;
+            // Occupy the temporary file's path so that writing to it fails
+            prepare_directory(
+                target.join(format!(".item.dt-{}.tmp", std::process::id())),
+                0o755,
+            )?;
+
+            sync(
+                DTConfig::from_str(&format!(
+                    r#"
+[[local]]
+name = "atomic copy"
+base = "{}"
+sources = ["item"]
+target = "{}"
+method = "Copy""#,
+                    base.display(),
+                    target.display(),
+                ))?,
+                false,
+            )?;
+            assert_eq!(
+                std::fs::read_to_string(target.join("item"))?,
+                "complete content\n",
+            );
+            Ok(())
+        }
+    }
+
+    mod ignore_failure_override {
+        use std::{path::PathBuf, str::FromStr};
+
+        use color_eyre::Report;
+
+        use crate::{
+            config::DTConfig,
+            syncing::{sync_with_options, SyncOptions},
+        };
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        /// Prepares a group whose only item fails to sync, returns its
+        /// config with `ignore_failure` set to the given value.
+        fn failing_group(name: &str, ignore_failure: bool) -> Result<DTConfig, Report> {
+            let base = prepare_directory(get_testroot("syncing").join(name).join("base"), 0o755)?;
+            let target =
+                prepare_directory(get_testroot("syncing").join(name).join("target"), 0o755)?;
+            prepare_file(base.join("dir").join("item"), 0o644)?;
+            // A dangling symlink occupies the path where the target's parent
+            // directory should be created, forcing an IO error
+            let obstacle: PathBuf = target.join("dir");
+            if obstacle.symlink_metadata().is_ok() {
+                std::fs::remove_file(&obstacle)?;
+            }
+            std::os::unix::fs::symlink(target.join("nonexistent"), obstacle)?;
+            Ok(DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "{}"
+base = "{}"
+sources = ["dir"]
+target = "{}"
+ignore_failure = {}"#,
+                name,
+                base.display(),
+                target.display(),
+                ignore_failure,
+            ))?)
+        }
+
+        #[test]
+        fn force_abort() -> Result<(), Report> {
+            let config = failing_group("force_abort", true)?;
+            assert!(sync_with_options(
+                config,
+                SyncOptions {
+                    ignore_failure: Some(false),
+                    ..Default::default()
+                },
+            )
+            .is_err());
+            Ok(())
+        }
+
+        #[test]
+        fn force_ignore() -> Result<(), Report> {
+            let config = failing_group("force_ignore", false)?;
+            sync_with_options(
+                config,
+                SyncOptions {
+                    ignore_failure: Some(true),
+                    ..Default::default()
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    mod target_prefix {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn mirrors_absolute_targets() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing")
+                    .join("mirrors_absolute_targets")
+                    .join("base"),
+                0o755,
+            )?;
+            let target = get_testroot("syncing")
+                .join("mirrors_absolute_targets")
+                .join("target");
+            let root = prepare_directory(
+                get_testroot("syncing")
+                    .join("mirrors_absolute_targets")
+                    .join("root"),
+                0o755,
+            )?;
+            std::fs::write(prepare_file(base.join("item"), 0o644)?, "prefixed")?;
+            let config = DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "prefixed"
+base = "{}"
+sources = ["item"]
+target = "{}"
+method = "Copy""#,
+                base.display(),
+                target.display(),
+            ))?
+            .with_target_prefix(&root)?;
+            assert_eq!(config.local[0].target, root.join(target.strip_prefix("/")?),);
+
+            sync(config, false)?;
+            assert_eq!(
+                std::fs::read_to_string(root.join(target.strip_prefix("/")?).join("item"))?,
+                "prefixed",
+            );
+            assert!(!target.join("item").exists());
+            Ok(())
+        }
+    }
+
+    mod skip_empty_renders {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn no_target_for_empty_render() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("no_target_for_empty_render");
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = root.join("target");
+            if target.exists() {
+                std::fs::remove_dir_all(&target)?;
+            }
+            std::fs::write(
+                prepare_file(base.join("empty"), 0o644)?,
+                r#"{{#if_user "nobody"}}only for nobody{{/if_user}}"#,
+            )?;
+            std::fs::write(prepare_file(base.join("nonempty"), 0o644)?, "content")?;
+            let config = |skip: bool| {
+                DTConfig::from_str(&format!(
+                    r#"
+[global]
+skip_empty_renders = {}
+
+[[local]]
+name = "empty"
+base = "{}"
+sources = ["empty", "nonempty"]
+target = "{}"
+method = "Copy""#,
+                    skip,
+                    base.display(),
+                    target.display(),
+                ))
+            };
+
+            sync(config(true)?, false)?;
+            assert!(!target.join("empty").exists());
+            assert!(target.join("nonempty").exists());
+
+            sync(config(false)?, false)?;
+            assert_eq!(std::fs::read(target.join("empty"))?, b"");
+            Ok(())
+        }
+    }
+
+    mod stats {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn populated_per_group() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("stats_populated_per_group");
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            prepare_file(base.join("a"), 0o644)?;
+            prepare_file(base.join("b"), 0o644)?;
+            let summary = sync(
+                DTConfig::from_str(&format!(
+                    r#"
+[[local]]
+name = "two"
+base = "{0}"
+sources = ["a", "b"]
+target = "{1}"
+
+[[local]]
+name = "empty"
+base = "{0}"
+sources = []
+target = "{1}""#,
+                    base.display(),
+                    root.join("target").display(),
+                ))?,
+                true,
+            )?;
+
+            assert_eq!(
+                summary
+                    .stats
+                    .iter()
+                    .map(|stats| (stats.group.to_string(), stats.items))
+                    .collect::<Vec<_>>(),
+                vec![("two".to_owned(), 2), ("empty".to_owned(), 0)],
+            );
+            for stats in &summary.stats {
+                assert_eq!(stats.total(), stats.expanding + stats.populating);
+            }
+            assert!(summary.stats[0]
+                .to_string()
+                .starts_with("[two]: 2 items in "));
+            Ok(())
+        }
+    }
+
+    mod target_matches {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, item::Operate, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        fn check(name: &str, method: &str) -> Result<(), Report> {
+            let root = get_testroot("syncing").join(name);
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = root.join("target");
+            if target.exists() {
+                std::fs::remove_dir_all(&target)?;
+            }
+            let item = prepare_file(base.join("item"), 0o644)?;
+            std::fs::write(&item, "content")?;
+            let config = DTConfig::from_str(&format!(
+                r#"
+[global]
+staging = "{}"
+
+[[local]]
+name = "matches"
+base = "{}"
+sources = ["item"]
+target = "{}"
+method = "{}""#,
+                root.join("staging").display(),
+                base.display(),
+                target.display(),
+                method,
+            ))?;
+            let group = &config.local[0];
+
+            // Missing target
+            assert!(!item.target_matches(group, b"content")?);
+
+            sync(config.to_owned(), false)?;
+            assert!(item.target_matches(group, b"content")?);
+            assert!(!item.target_matches(group, b"other content")?);
+
+            // Target replaced with a regular file of the same content
+            std::fs::remove_file(target.join("item"))?;
+            std::fs::write(target.join("item"), "content")?;
+            assert_eq!(item.target_matches(group, b"content")?, method == "Copy",);
+            Ok(())
+        }
+
+        #[test]
+        fn copy() -> Result<(), Report> {
+            check("target_matches_copy", "Copy")
+        }
+
+        #[test]
+        fn symlink() -> Result<(), Report> {
+            check("target_matches_symlink", "Symlink")
+        }
+    }
+
+    mod drift {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn passes_on_normal_copy() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing")
+                    .join("verify_passes_on_normal_copy")
+                    .join("base"),
+                0o755,
+            )?;
+            let target = get_testroot("syncing")
+                .join("verify_passes_on_normal_copy")
+                .join("target");
+            std::fs::write(prepare_file(base.join("item"), 0o644)?, "verified")?;
+            let config = DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "verified"
+base = "{}"
+sources = ["item"]
+target = "{}"
+method = "Copy"
+verify = true"#,
+                base.display(),
+                target.display(),
+            ))?;
+            assert!(config.local[0].verify);
+
+            let summary = sync(config.to_owned(), false)?;
+            assert_eq!(summary.synced(), 1);
+            assert_eq!(std::fs::read_to_string(target.join("item"))?, "verified");
+
+            // Verifies an up-to-date target as well
+            let summary = sync(config, false)?;
+            assert_eq!(summary.synced(), 1);
+            Ok(())
+        }
+    }
+
+    mod atomic_staging {
+        use std::{io::Read, str::FromStr};
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn staged_file_always_complete() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("atomic_staging_always_complete");
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = root.join("target");
+            let staging = root.join("staging");
+            for dir in [&target, &staging] {
+                if dir.exists() {
+                    std::fs::remove_dir_all(dir)?;
+                }
+            }
+            let source = prepare_file(base.join("item"), 0o644)?;
+            let config = format!(
+                r#"
+[global]
+staging = "{}"
+allow_overwrite = true
+
+[[local]]
+name = "atomic"
+base = "{}"
+sources = ["item"]
+target = "{}"
+method = "Symlink""#,
+                staging.display(),
+                base.display(),
+                target.display(),
+            );
+
+            std::fs::write(&source, "first version\n")?;
+            sync(DTConfig::from_str(&config)?, false)?;
+            // A reader of the staged file, e.g. through the target symlink,
+            // still sees complete contents while it is being replaced.
+            let mut reader = std::fs::File::open(target.join("item"))?;
+            std::fs::write(&source, "second, longer version\n")?;
+            sync(DTConfig::from_str(&config)?, false)?;
+            let mut read = String::new();
+            reader.read_to_string(&mut read)?;
+            assert_eq!(read, "first version\n");
+            assert_eq!(
+                std::fs::read_to_string(target.join("item"))?,
+                "second, longer version\n",
+            );
+
+            let staged = std::fs::read_link(target.join("item"))?;
+            let entries: Vec<_> = std::fs::read_dir(staged.parent().unwrap())?
+                .map(|entry| entry.map(|entry| entry.file_name()))
+                .collect::<Result<_, _>>()?;
+            assert_eq!(entries, vec!["item"]);
+            Ok(())
+        }
+    }
+
+    mod in_place {
+        use std::{os::unix::fs::MetadataExt, str::FromStr};
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn preserves_inode() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing")
+                    .join("in_place_preserves_inode")
+                    .join("base"),
+                0o755,
+            )?;
+            let target = prepare_directory(
+                get_testroot("syncing")
+                    .join("in_place_preserves_inode")
+                    .join("target"),
+                0o755,
+            )?;
+            std::fs::write(prepare_file(base.join("item"), 0o644)?, "new contents")?;
+            std::fs::write(prepare_file(target.join("item"), 0o644)?, "old")?;
+            let inode = target.join("item").metadata()?.ino();
+
+            let config = DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "in place"
+base = "{}"
+sources = ["item"]
+target = "{}"
+method = "Copy"
+in_place = true"#,
+                base.display(),
+                target.display(),
+            ))?;
+            assert!(config.local[0].in_place);
+
+            sync(config, false)?;
+            assert_eq!(
+                std::fs::read_to_string(target.join("item"))?,
+                "new contents",
+            );
+            assert_eq!(target.join("item").metadata()?.ino(), inode);
+            Ok(())
+        }
+    }
+
+    mod allow_same_base_target {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{
+            config::DTConfig,
+            syncing::{sync, verify},
+        };
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn renders_in_place() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing").join("allow_same_base_target_renders_in_place"),
+                0o755,
+            )?;
+            std::fs::write(
+                prepare_file(base.join("greeting"), 0o644)?,
+                "Hello, {{greeting.name}}!",
+            )?;
+            let config = DTConfig::from_str(&format!(
+                r#"
+[context.greeting]
+name = "world"
+
+[[local]]
+name = "greeting"
+base = "{0}"
+sources = ["greeting"]
+target = "{0}"
+method = "Copy"
+allow_same_base_target = true"#,
+                base.display(),
+            ))?;
+
+            let summary = sync(config.to_owned(), false)?;
+            assert_eq!(summary.synced(), 1);
+            assert_eq!(
+                std::fs::read_to_string(base.join("greeting"))?,
+                "Hello, world!",
+            );
+            assert_eq!(std::fs::read_dir(&base)?.count(), 1);
+
+            // Rendering an already rendered item again leaves it unchanged
+            sync(config, false)?;
+            assert_eq!(
+                std::fs::read_to_string(base.join("greeting"))?,
+                "Hello, world!",
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn verified_after_sync() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing").join("allow_same_base_target_verified_after_sync"),
+                0o755,
+            )?;
+            std::fs::write(
+                prepare_file(base.join("binary"), 0o644)?,
+                [0x7f, b'E', b'L', b'F', 0, 0, 0, 0],
+            )?;
+            let config = DTConfig::from_str(&format!(
+                r#"
+[global]
+default_method_for_binary = "SymlinkDirect"
+
+[[local]]
+name = "binary"
+base = "{0}"
+sources = ["binary"]
+target = "{0}"
+method = "Copy"
+allow_same_base_target = true"#,
+                base.display(),
+            ))?;
+
+            sync(config.to_owned(), false)?;
+            assert!(!base.join("binary").is_symlink());
+            assert_eq!(verify(config)?, Vec::new());
+            Ok(())
+        }
+    }
+
+    mod umask {
+        use std::{os::unix::prelude::PermissionsExt, str::FromStr};
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn masks_target_mode() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("umask_masks_target_mode");
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = root.join("target");
+            if target.exists() {
+                std::fs::remove_dir_all(&target)?;
+            }
+            prepare_file(base.join("global").join("item"), 0o666)?;
+            prepare_file(base.join("group").join("item"), 0o666)?;
+            let config = DTConfig::from_str(&format!(
+                r#"
+[global]
+method = "Copy"
+umask = 0o022
+
+[[local]]
+name = "global"
+base = "{0}"
+sources = ["global"]
+target = "{1}"
+
+[[local]]
+name = "group"
+base = "{0}"
+sources = ["group"]
+target = "{1}"
+umask = 0o077"#,
+                base.display(),
+                target.display(),
+            ))?;
+
+            sync(config, false)?;
+            let mode = |path: std::path::PathBuf| -> Result<u32, Report> {
+                Ok(path.metadata()?.permissions().mode() & 0o777)
+            };
+            assert_eq!(mode(target.join("global").join("item"))?, 0o644);
+            assert_eq!(mode(target.join("group").join("item"))?, 0o600);
+            Ok(())
+        }
+    }
+
+    mod verifying {
+        use std::{fs::Permissions, os::unix::prelude::PermissionsExt, str::FromStr};
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{
+            config::DTConfig,
+            syncing::{sync, verify, DriftKind},
+        };
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn seeded_drift() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("verify_seeded_drift");
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = root.join("target");
+            if target.exists() {
+                std::fs::remove_dir_all(&target)?;
+            }
+            for name in ["missing", "mismatched", "mode", "clean"] {
+                prepare_file(base.join(name), 0o644)?;
+            }
+            let config = DTConfig::from_str(&format!(
+                r#"
+[global]
+method = "Copy"
+
+[[local]]
+name = "verified"
+base = "{}"
+sources = ["*"]
+target = "{}""#,
+                base.display(),
+                target.display(),
+            ))?;
+
+            sync(config.to_owned(), false)?;
+            assert!(verify(config.to_owned())?.is_empty());
+
+            std::fs::remove_file(target.join("missing"))?;
+            std::fs::write(target.join("mismatched"), "drifted\n")?;
+            std::fs::set_permissions(target.join("mode"), Permissions::from_mode(0o600))?;
+            let mut drifts: Vec<_> = verify(config)?
+                .into_iter()
+                .map(|d| (d.target, d.kind))
+                .collect();
+            drifts.sort_by(|a, b| a.0.cmp(&b.0));
+            assert_eq!(
+                drifts,
+                vec![
+                    (target.join("mismatched"), DriftKind::Mismatched),
+                    (target.join("missing"), DriftKind::Missing),
+                    (
+                        target.join("mode"),
+                        DriftKind::Mode {
+                            expected: 0o644,
+                            actual: 0o600,
+                        },
+                    ),
+                ],
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn empty_render_skipped() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("verify_empty_render_skipped");
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = root.join("target");
+            if target.exists() {
+                std::fs::remove_dir_all(&target)?;
+            }
+            std::fs::write(
+                prepare_file(base.join("empty"), 0o644)?,
+                "{{#if false}}nothing{{/if}}",
+            )?;
+            let config = DTConfig::from_str(&format!(
+                r#"
+[global]
+method = "Copy"
+skip_empty_renders = true
+
+[[local]]
+name = "empty"
+base = "{}"
+sources = ["empty"]
+target = "{}""#,
+                base.display(),
+                target.display(),
+            ))?;
+
+            sync(config.to_owned(), false)?;
+            assert!(!target.join("empty").exists());
+            assert!(verify(config)?.is_empty());
+            Ok(())
+        }
+
+        #[test]
+        fn copy_fallback() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("verify_copy_fallback");
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = root.join("target");
+            if target.exists() {
+                std::fs::remove_dir_all(&target)?;
+            }
+            std::fs::write(prepare_file(base.join("item"), 0o644)?, "linked\n")?;
+            let config = |link_mode: &str| {
+                DTConfig::from_str(&format!(
+                    r#"
+[global]
+staging = "{}"
+link_mode = "{}"
+
+[[local]]
+name = "fallback"
+base = "{}"
+sources = ["item"]
+target = "{}"
+method = "Symlink""#,
+                    root.join("staging").display(),
+                    link_mode,
+                    base.display(),
+                    target.display(),
+                ))
+            };
+
+            sync(config("copy_fallback")?, false)?;
+            // Copy the staged item over its link, as if the target
+            // filesystem did not support symlinks
+            let staged = std::fs::read_link(target.join("item"))?;
+            std::fs::remove_file(target.join("item"))?;
+            std::fs::copy(staged, target.join("item"))?;
+
+            assert!(verify(config("copy_fallback")?)?.is_empty());
+            assert_eq!(
+                verify(config("symlink")?)?
+                    .into_iter()
+                    .map(|d| d.kind)
+                    .collect::<Vec<_>>(),
+                vec![DriftKind::Mismatched],
+            );
+            Ok(())
+        }
+    }
+
+    mod ignore_patterns {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::expand};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn global_and_group() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing").join("ignore_patterns_global_and_group"),
+                0o755,
+            )?;
+            for repo in ["nvim", "zsh"] {
+                prepare_file(base.join(repo).join(".git").join("config"), 0o644)?;
+                prepare_file(base.join(repo).join("init"), 0o644)?;
+                prepare_file(base.join(repo).join(".init.swp"), 0o644)?;
+            }
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+[global]
+ignore_patterns = [".git"]
+
+[[local]]
+name = "nvim"
+base = "{0}"
+sources = ["nvim"]
+target = "/tmp"
+ignored = ["*.swp"]
+
+[[local]]
+name = "zsh"
+base = "{0}"
+sources = ["zsh"]
+target = "/tmp""#,
+                base.display(),
+            ))?)?;
+            assert_eq!(
+                config.local[0].get_ignore_patterns(),
+                vec![".git".to_owned(), "*.swp".to_owned()],
+            );
+            assert_eq!(
+                config.local[0].sources,
+                vec![base.join("nvim").join("init")]
+            );
+            let mut zsh = config.local[1].sources.to_owned();
+            zsh.sort();
+            assert_eq!(
+                zsh,
+                vec![
+                    base.join("zsh").join(".init.swp"),
+                    base.join("zsh").join("init")
+                ],
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn slash_denied() {
+            assert!(DTConfig::from_str(
+                r#"
+[global]
+ignore_patterns = ["a/b"]
+
+[[local]]
+name = "group"
+base = "/tmp"
+sources = []
+target = "/tmp""#,
+            )
+            .is_err());
+        }
+    }
+
+    mod force_mode {
+        use std::{os::unix::prelude::PermissionsExt, str::FromStr};
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn overrides_source_mode() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("force_mode_overrides_source_mode");
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = root.join("target");
+            if target.exists() {
+                std::fs::remove_dir_all(&target)?;
+            }
+            prepare_file(base.join("copied").join("item"), 0o600)?;
+            prepare_file(base.join("linked").join("item"), 0o600)?;
+            let config = DTConfig::from_str(&format!(
+                r#"
+[global]
+staging = "{0}"
+force_mode = 0o644
+
+[[local]]
+name = "copied"
+base = "{1}"
+sources = ["copied"]
+target = "{2}"
+method = "Copy"
+
+[[local]]
+name = "linked"
+base = "{1}"
+sources = ["linked"]
+target = "{2}"
+method = "Symlink""#,
+                root.join("staging").display(),
+                base.display(),
+                target.display(),
+            ))?;
+
+            sync(config, false)?;
+            let mode = |path: std::path::PathBuf| -> Result<u32, Report> {
+                Ok(path.metadata()?.permissions().mode() & 0o7777)
+            };
+            assert_eq!(mode(target.join("copied").join("item"))?, 0o644);
+            assert_eq!(mode(target.join("linked").join("item"))?, 0o644);
+            assert_eq!(mode(base.join("copied").join("item"))?, 0o600);
+            Ok(())
+        }
+
+        #[test]
+        fn overridden_after_loading() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("force_mode_overridden_after_loading");
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = root.join("target");
+            if target.exists() {
+                std::fs::remove_dir_all(&target)?;
+            }
+            prepare_file(base.join("item"), 0o644)?;
+            let config = DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "overridden"
+base = "{}"
+sources = ["item"]
+target = "{}"
+method = "Copy""#,
+                base.display(),
+                target.display(),
+            ))?
+            .with_global(|global| global.force_mode = Some(0o600))?;
+
+            sync(config, false)?;
+            assert_eq!(
+                target.join("item").metadata()?.permissions().mode() & 0o7777,
+                0o600,
+            );
+            Ok(())
+        }
+    }
+
+    mod only_changed {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{
+            config::DTConfig,
+            syncing::{sync, sync_with_options, SyncOptions},
+        };
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn skips_unchanged() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("only_changed_skips_unchanged");
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = root.join("target");
+            if target.exists() {
+                std::fs::remove_dir_all(&target)?;
+            }
+            std::fs::write(prepare_file(base.join("unchanged"), 0o644)?, "same")?;
+            std::fs::write(prepare_file(base.join("changed"), 0o644)?, "old")?;
+            let config = DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "only changed"
+base = "{}"
+sources = ["*"]
+target = "{}"
+method = "Copy""#,
+                base.display(),
+                target.display(),
+            ))?;
+            assert_eq!(sync(config.to_owned(), false)?.synced(), 2);
+
+            std::fs::write(base.join("changed"), "new")?;
+            let summary = sync_with_options(
+                config,
+                SyncOptions {
+                    only_changed: true,
+                    ..Default::default()
+                },
+            )?;
+            assert_eq!(
+                summary
+                    .items
+                    .iter()
+                    .map(|item| item.source.to_owned())
+                    .collect::<Vec<_>>(),
+                vec![base.join("changed")],
+            );
+            assert_eq!(std::fs::read_to_string(target.join("changed"))?, "new");
+            assert_eq!(std::fs::read_to_string(target.join("unchanged"))?, "same");
+            Ok(())
+        }
+    }
+
+    mod source_encoding {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, error::Error as AppError, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        fn config(name: &str, encoding: Option<&str>) -> Result<DTConfig, Report> {
+            let root = get_testroot("syncing").join(name);
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            // "Café {{cafe.name}}" in Latin-1
+            std::fs::write(
+                prepare_file(base.join("latin1"), 0o644)?,
+                b"Caf\xe9 {{cafe.name}}",
+            )?;
+            Ok(DTConfig::from_str(&format!(
+                r#"
+[global]
+{}
+
+[context.cafe]
+name = "crème"
+
+[[local]]
+name = "cafe"
+base = "{}"
+sources = ["latin1"]
+target = "{}"
+method = "Copy""#,
+                encoding
+                    .map(|e| format!("source_encoding = \"{}\"", e))
+                    .unwrap_or_default(),
+                base.display(),
+                root.join("target").display(),
+            ))?)
+        }
+
+        #[test]
+        fn latin1_rendered() -> Result<(), Report> {
+            sync(
+                config("source_encoding_latin1_rendered", Some("latin1"))?,
+                false,
+            )?;
+            assert_eq!(
+                std::fs::read(
+                    get_testroot("syncing")
+                        .join("source_encoding_latin1_rendered")
+                        .join("target")
+                        .join("latin1"),
+                )?,
+                b"Caf\xe9 cr\xe8me",
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn undecodable_as_utf8() -> Result<(), Report> {
+            match sync(config("source_encoding_undecodable_as_utf8", None)?, false) {
+                Err(AppError::RenderingError(msg)) => {
+                    assert!(msg.contains("could not be decoded as UTF-8"), "{}", msg);
+                }
+                other => panic!("expected a rendering error, got {:?}", other),
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn unknown_encoding() -> Result<(), Report> {
+            assert_eq!(
+                config("source_encoding_unknown_encoding", Some("klingon"))
+                    .unwrap_err()
+                    .downcast::<AppError>()?,
+                AppError::ConfigError("unknown source encoding 'klingon'".to_owned()),
+            );
+            Ok(())
+        }
+    }
+
+    mod lock {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+
+        use crate::{
+            config::DTConfig,
+            error::Error as AppError,
+            syncing::{sync_with_options, SyncOptions, LOCK_FILENAME},
+            utils::try_lock,
+        };
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn fails_while_held() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("lock_fails_while_held");
+            if root.join("target").exists() {
+                std::fs::remove_dir_all(root.join("target"))?;
+            }
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let staging = prepare_directory(root.join("staging"), 0o755)?;
+            prepare_file(base.join("item"), 0o644)?;
+            let config = DTConfig::from_str(&format!(
+                r#"
+[global]
+staging = "{}"
+
+[[local]]
+name = "locked"
+base = "{}"
+sources = ["item"]
+target = "{}""#,
+                staging.display(),
+                base.display(),
+                root.join("target").display(),
+            ))?;
+
+            let held = try_lock(staging.join(LOCK_FILENAME))?;
+            assert!(matches!(
+                sync_with_options(config.to_owned(), SyncOptions::default()),
+                Err(AppError::SyncingError(_)),
+            ));
+            assert!(!root.join("target").join("item").exists());
+
+            drop(held);
+            sync_with_options(config, SyncOptions::default())?;
+            assert!(root.join("target").join("item").exists());
+            Ok(())
+        }
+
+        #[test]
+        fn no_lock_ignores_held_lock() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("no_lock_ignores_held_lock");
+            if root.exists() {
+                std::fs::remove_dir_all(&root)?;
+            }
+            let staging = root.join("staging");
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            prepare_file(base.join("item"), 0o644)?;
+            let config = DTConfig::from_str(&format!(
+                r#"
+[global]
+staging = "{}"
+
+[[local]]
+name = "unlocked"
+base = "{}"
+sources = ["item"]
+target = "{}""#,
+                staging.display(),
+                base.display(),
+                root.join("target").display(),
+            ))?;
+
+            std::fs::create_dir_all(&staging)?;
+            let _held = try_lock(staging.join(LOCK_FILENAME))?;
+            sync_with_options(
+                config,
+                SyncOptions {
+                    no_lock: true,
+                    ..Default::default()
+                },
+            )?;
+            assert!(root.join("target").join("item").exists());
+            Ok(())
+        }
+
+        #[test]
+        fn copy_only_creates_no_staging() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("lock_copy_only_creates_no_staging");
+            if root.exists() {
+                std::fs::remove_dir_all(&root)?;
+            }
+            let staging = root.join("staging");
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            prepare_file(base.join("item"), 0o644)?;
+            let config = DTConfig::from_str(&format!(
+                r#"
+[global]
+staging = "{}"
+method = "Copy"
+
+[[local]]
+name = "copied"
+base = "{}"
+sources = ["item"]
+target = "{}""#,
+                staging.display(),
+                base.display(),
+                root.join("target").display(),
+            ))?;
+
+            sync_with_options(config, SyncOptions::default())?;
+            assert!(root.join("target").join("item").exists());
+            assert!(!staging.exists());
+            Ok(())
+        }
+    }
+
+    mod render {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::render};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn writes_under_output_dir() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("render_writes_under_output_dir");
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = root.join("target");
+            let output_dir = root.join("output");
+            std::fs::write(
+                prepare_file(base.join("nested").join("rendered"), 0o644)?,
+                "Hello, {{who.name}}!",
+            )?;
+            std::fs::write(prepare_file(base.join("raw"), 0o644)?, "{{not rendered}}")?;
+            let config = DTConfig::from_str(&format!(
+                r#"
+[context.who]
+name = "world"
+
+[[local]]
+name = "who"
+base = "{0}"
+sources = ["nested"]
+target = "{1}"
+method = "Symlink"
+
+[[local]]
+name = "raw"
+base = "{0}"
+sources = ["raw"]
+target = "{1}"
+renderable = false"#,
+                base.display(),
+                target.display(),
+            ))?;
+
+            let written = render(config, &output_dir)?;
+            assert_eq!(
+                written,
+                vec![
+                    output_dir.join("nested").join("rendered"),
+                    output_dir.join("raw"),
+                ],
+            );
+            assert_eq!(
+                std::fs::read_to_string(output_dir.join("nested").join("rendered"))?,
+                "Hello, world!",
+            );
+            assert_eq!(
+                std::fs::read_to_string(output_dir.join("raw"))?,
+                "{{not rendered}}",
+            );
+            assert!(!output_dir.join("raw").is_symlink());
+            assert!(!target.exists());
+            Ok(())
+        }
+
+        #[test]
+        fn links_symlinked_dirs() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("render_links_symlinked_dirs");
+            if root.exists() {
+                std::fs::remove_dir_all(&root)?;
+            }
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let real = prepare_directory(root.join("real"), 0o755)?;
+            std::fs::write(prepare_file(real.join("inner"), 0o644)?, "{{not a file}}")?;
+            std::os::unix::fs::symlink(&real, base.join("linked"))?;
+            let output_dir = root.join("output");
+            let config = DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "linked"
+base = "{}"
+sources = ["linked"]
+target = "{}"
+follow_symlinks = false"#,
+                base.display(),
+                root.join("target").display(),
+            ))?;
+
+            let written = render(config.to_owned(), &output_dir)?;
+            assert_eq!(written, vec![output_dir.join("linked")]);
+            assert_eq!(
+                std::fs::read_link(output_dir.join("linked"))?,
+                real.canonicalize()?,
+            );
+
+            // Rendering again replaces the link
+            render(config, &output_dir)?;
+            assert_eq!(
+                std::fs::read_link(output_dir.join("linked"))?,
+                real.canonicalize()?,
+            );
+            Ok(())
+        }
+    }
+
+    mod delete_orphans {
+        use std::{
+            path::{Path, PathBuf},
+            str::FromStr,
+        };
+
+        use color_eyre::{eyre::eyre, Report};
+        use pretty_assertions::assert_eq;
+
+        use crate::{
+            config::DTConfig,
+            error::Error as AppError,
+            syncing::{sync_with_options, SyncOptions},
+        };
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn prunes_removed_group() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("prunes_removed_group");
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = root.join("target");
+            std::fs::write(prepare_file(base.join("kept"), 0o644)?, "kept")?;
+            std::fs::write(prepare_file(base.join("removed"), 0o644)?, "removed")?;
+            let group = |name: &str| {
+                format!(
+                    r#"
+[[local]]
+name = "{0}"
+base = "{1}"
+sources = ["{0}"]
+target = "{2}"
+method = "Copy""#,
+                    name,
+                    base.display(),
+                    target.display(),
+                )
+            };
+            let global = format!(
+                r#"
+[global]
+staging = "{}""#,
+                root.join("staging").display(),
+            );
+            let options = |dry_run| SyncOptions {
+                dry_run,
+                delete_orphans: true,
+                ..Default::default()
+            };
+
+            sync_with_options(
+                DTConfig::from_str(&format!("{}{}{}", global, group("kept"), group("removed")))?,
+                options(false),
+            )?;
+            assert_eq!(std::fs::read_to_string(target.join("removed"))?, "removed");
+
+            // Removing a group only reports its old target under dry run
+            let config = DTConfig::from_str(&format!("{}{}", global, group("kept")))?;
+            sync_with_options(config.to_owned(), options(true))?;
+            assert!(target.join("removed").exists());
+
+            sync_with_options(config, options(false))?;
+            assert!(!target.join("removed").exists());
+            assert_eq!(std::fs::read_to_string(target.join("kept"))?, "kept");
+            Ok(())
+        }
+
+        /// Syncs a group of two items with given method, then edits the
+        /// target of the second one with `edit`, and syncs again without
+        /// it.  Returns the path to the edited target.
+        fn orphan_edited(
+            name: &str,
+            method: &str,
+            edit: fn(&Path) -> std::io::Result<()>,
+        ) -> Result<PathBuf, Report> {
+            let root = get_testroot("syncing").join(name);
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = root.join("target");
+            for dir in [&target, &root.join("staging")] {
+                if dir.exists() {
+                    std::fs::remove_dir_all(dir)?;
+                }
+            }
+            std::fs::write(prepare_file(base.join("kept"), 0o644)?, "kept")?;
+            std::fs::write(prepare_file(base.join("edited"), 0o644)?, "edited")?;
+            let config = |sources: &str| {
+                DTConfig::from_str(&format!(
+                    r#"
+[global]
+staging = "{}"
+
+[[local]]
+name = "orphans"
+base = "{}"
+sources = [{}]
+target = "{}"
+method = "{}""#,
+                    root.join("staging").display(),
+                    base.display(),
+                    sources,
+                    target.display(),
+                    method,
+                ))
+            };
+            let options = SyncOptions {
+                delete_orphans: true,
+                ..Default::default()
+            };
+            sync_with_options(config(r#""kept", "edited""#)?, options.to_owned())?;
+            let edited = target.join("edited");
+            edit(&edited)?;
+            sync_with_options(config(r#""kept""#)?, options)?;
+            Ok(edited)
+        }
+
+        #[test]
+        fn keeps_edited_copy() -> Result<(), Report> {
+            let edited = orphan_edited("delete_orphans_keeps_edited_copy", "Copy", |path| {
+                std::fs::write(path, "edited by user")
+            })?;
+            assert_eq!(std::fs::read_to_string(edited)?, "edited by user");
+            Ok(())
+        }
+
+        #[test]
+        fn keeps_replaced_symlink() -> Result<(), Report> {
+            let edited =
+                orphan_edited("delete_orphans_keeps_replaced_symlink", "Symlink", |path| {
+                    std::fs::remove_file(path)?;
+                    std::fs::write(path, "edited")
+                })?;
+            assert!(!edited.is_symlink());
+            assert_eq!(std::fs::read_to_string(edited)?, "edited");
+            Ok(())
+        }
+
+        #[test]
+        fn removes_intact_symlink() -> Result<(), Report> {
+            let edited = orphan_edited("delete_orphans_removes_intact_symlink", "Symlink", |_| {
+                Ok(())
+            })?;
+            assert!(!edited.exists() && !edited.is_symlink());
+            Ok(())
+        }
+
+        #[test]
+        fn prunes_created_dirs() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("delete_orphans_prunes_created_dirs");
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = root.join("target");
+            for dir in [&target, &root.join("staging")] {
+                if dir.exists() {
+                    std::fs::remove_dir_all(dir)?;
+                }
+            }
+            prepare_file(base.join("sub").join("deep").join("a"), 0o644)?;
+            prepare_file(base.join("mine").join("b"), 0o644)?;
+            prepare_file(base.join("kept"), 0o644)?;
+            // Directories created by the user before syncing
+            prepare_directory(target.join("mine"), 0o755)?;
+            prepare_directory(target.join("sibling"), 0o755)?;
+            let config = |sources: &str| {
+                DTConfig::from_str(&format!(
+                    r#"
+[global]
+staging = "{}"
+
+[[local]]
+name = "prune"
+base = "{}"
+sources = [{}]
+target = "{}"
+method = "Copy""#,
+                    root.join("staging").display(),
+                    base.display(),
+                    sources,
+                    target.display(),
+                ))
+            };
+            let options = |dry_run| SyncOptions {
+                dry_run,
+                delete_orphans: true,
+                prune: true,
+                ..Default::default()
+            };
+            sync_with_options(config(r#""sub", "mine", "kept""#)?, options(false))?;
+            assert!(target.join("sub").join("deep").join("a").exists());
+
+            // Nothing is pruned under dry run
+            sync_with_options(config(r#""kept""#)?, options(true))?;
+            assert!(target.join("sub").join("deep").exists());
+
+            sync_with_options(config(r#""kept""#)?, options(false))?;
+            // Created by syncing and emptied, thus pruned
+            assert!(!target.join("sub").exists());
+            // Created by the user, thus kept even if emptied
+            assert!(target.join("mine").is_dir());
+            assert!(!target.join("mine").join("b").exists());
+            assert!(target.join("sibling").is_dir());
+            assert!(target.join("kept").exists());
+            Ok(())
+        }
+
+        #[test]
+        fn refuses_filtered_config() -> Result<(), Report> {
+            let config = DTConfig::from_str(
+                r#"
+[[local]]
+name = "a"
+base = "/tmp/dt-testing/delete_orphans_refuses_filtered_config/base"
+sources = []
+target = "/tmp/dt-testing/delete_orphans_refuses_filtered_config/target""#,
+            )?
+            .filter_names(vec!["a".to_owned()]);
+            if let Err(err) = sync_with_options(
+                config,
+                SyncOptions {
+                    delete_orphans: true,
+                    ..Default::default()
+                },
+            ) {
+                assert!(matches!(err, AppError::ConfigError(_)));
+                Ok(())
+            } else {
+                Err(eyre!(
+                    "This sync should fail because groups have been filtered out of the config"
+                ))
+            }
+        }
+    }
+
+    mod fail_on_warning {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, error::Error as AppError, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory};
+
+        /// Returns a config whose only source matches nothing, which produces
+        /// a warning when synced.
+        fn config_with_warning(fail_on_warning: bool) -> Result<DTConfig, Report> {
+            let base = prepare_directory(
+                get_testroot("syncing").join("fail_on_warning").join("base"),
+                0o755,
+            )?;
+            let target = prepare_directory(
+                get_testroot("syncing")
+                    .join("fail_on_warning")
+                    .join("target"),
+                0o755,
+            )?;
+            Ok(DTConfig::from_str(&format!(
+                r#"
+[global]
+fail_on_warning = {}
+
+[[local]]
+name = "warning"
+base = "{}"
+sources = ["nonexistent-*"]
+target = "{}""#,
+                fail_on_warning,
+                base.display(),
+                target.display(),
+            ))?)
+        }
+
+        #[test]
+        fn escalated() -> Result<(), Report> {
+            assert_eq!(
+                sync(config_with_warning(true)?, true),
+                Err(AppError::SyncingError(
+                    "1 warning logged while `fail_on_warning` is set".to_owned()
+                )),
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn not_escalated() -> Result<(), Report> {
+            sync(config_with_warning(false)?, true)?;
+            Ok(())
+        }
+    }
+
+    mod strip_components {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn nested_source() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing")
+                    .join("strip_components_nested_source")
+                    .join("base"),
+                0o755,
+            )?;
+            let target = prepare_directory(
+                get_testroot("syncing")
+                    .join("strip_components_nested_source")
+                    .join("target"),
+                0o755,
+            )?;
+            std::fs::write(
+                prepare_file(base.join("nvim").join("lua").join("_dot_init"), 0o644)?,
+                "nested",
+            )?;
+            std::fs::write(prepare_file(base.join("nvim").join("top"), 0o644)?, "top")?;
+            sync(
+                DTConfig::from_str(&format!(
+                    r#"
+[[local]]
+name = "stripped"
+base = "{}"
+sources = ["nvim"]
+target = "{}"
+method = "Copy"
+strip_components = 1
+rename = [["^_dot_", "."]]"#,
+                    base.display(),
+                    target.display(),
+                ))?,
+                false,
+            )?;
+            assert_eq!(
+                std::fs::read_to_string(target.join("lua").join(".init"))?,
+                "nested",
+            );
+            assert_eq!(std::fs::read_to_string(target.join("top"))?, "top");
+            assert!(!target.join("nvim").exists());
+            Ok(())
+        }
+    }
+
+    mod profile {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+
+        use crate::{config::DTConfig, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn only_profile_groups_synced() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing")
+                    .join("only_profile_groups_synced")
+                    .join("base"),
+                0o755,
+            )?;
+            let target = prepare_directory(
+                get_testroot("syncing")
+                    .join("only_profile_groups_synced")
+                    .join("target"),
+                0o755,
+            )?;
+            for name in ["laptop-item", "desktop-item", "shared-item"] {
+                prepare_file(base.join(name), 0o644)?;
+                if target.join(name).exists() {
+                    std::fs::remove_file(target.join(name))?;
+                }
+            }
+            let config = DTConfig::from_str(&format!(
+                r#"
+[profiles.laptop]
+groups = ["laptop", "shared"]
+
+[profiles.desktop]
+groups = ["desktop", "shared"]
+
+[[local]]
+name = "laptop"
+base = "{0}"
+sources = ["laptop-item"]
+target = "{1}"
+method = "Copy"
+
+[[local]]
+name = "desktop"
+base = "{0}"
+sources = ["desktop-item"]
+target = "{1}"
+method = "Copy"
+
+[[local]]
+name = "shared"
+base = "{0}"
+sources = ["shared-item"]
+target = "{1}"
+method = "Copy""#,
+                base.display(),
+                target.display(),
+            ))?;
+
+            sync(config.select_profile("laptop")?, false)?;
+            assert!(target.join("laptop-item").is_file());
+            assert!(target.join("shared-item").is_file());
+            assert!(!target.join("desktop-item").exists());
+            Ok(())
+        }
+    }
+
+    mod empty_source {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn empty_target_produced() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing")
+                    .join("empty_target_produced")
+                    .join("base"),
+                0o755,
+            )?;
+            let target = prepare_directory(
+                get_testroot("syncing")
+                    .join("empty_target_produced")
+                    .join("target"),
+                0o755,
+            )?;
+            std::fs::write(prepare_file(base.join("empty"), 0o644)?, "")?;
+            std::fs::write(target.join("empty"), "stale content")?;
+            sync(
+                DTConfig::from_str(&format!(
+                    r#"
+[[local]]
+name = "empty"
+base = "{}"
+sources = ["empty"]
+target = "{}"
+method = "Copy""#,
+                    base.display(),
+                    target.display(),
+                ))?,
+                false,
+            )?;
+            assert_eq!(std::fs::read(target.join("empty"))?, Vec::<u8>::new());
+            Ok(())
+        }
+    }
+
+    mod ownership {
+        use std::{os::unix::fs::MetadataExt, str::FromStr};
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn target_chowned() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing").join("target_chowned").join("base"),
+                0o755,
+            )?;
+            let target = prepare_directory(
+                get_testroot("syncing")
+                    .join("target_chowned")
+                    .join("target"),
+                0o755,
+            )?;
+            prepare_file(base.join("item"), 0o644)?;
+            sync(
+                DTConfig::from_str(&format!(
+                    r#"
+[[local]]
+name = "chowned"
+base = "{}"
+sources = ["item"]
+target = "{}"
+method = "Copy"
+owner = "65534"
+group = "65534""#,
+                    base.display(),
+                    target.display(),
+                ))?,
+                false,
+            )?;
+            let metadata = target.join("item").symlink_metadata()?;
+            if users::get_effective_uid() == 0 {
+                assert_eq!((metadata.uid(), metadata.gid()), (65534, 65534));
+            } else {
+                // Without privilege, chown is skipped with a warning
+                assert_eq!(metadata.uid(), users::get_effective_uid());
+            }
+            Ok(())
+        }
+    }
+
+    mod keep_going {
+        use std::str::FromStr;
+
+        use color_eyre::{eyre::eyre, Report};
+        use pretty_assertions::assert_eq;
+
+        use crate::{
+            config::DTConfig,
+            error::Error as AppError,
+            syncing::{sync_with_options, SyncOptions},
+        };
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn all_failures_reported() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing")
+                    .join("all_failures_reported")
+                    .join("base"),
+                0o755,
+            )?;
+            let target = prepare_directory(
+                get_testroot("syncing")
+                    .join("all_failures_reported")
+                    .join("target"),
+                0o755,
+            )?;
+            for name in ["a", "b"] {
+                prepare_file(base.join(name).join("item"), 0o644)?;
+                // A dangling symlink occupies the path where the target's
+                // parent directory should be created, forcing an IO error
+                let obstacle = target.join(name);
+                if obstacle.symlink_metadata().is_ok() {
+                    std::fs::remove_file(&obstacle)?;
+                }
+                std::os::unix::fs::symlink(target.join("nonexistent"), obstacle)?;
+            }
+            prepare_file(base.join("ok"), 0o644)?;
+            let config = DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "keep going"
+base = "{}"
+sources = ["a", "b", "ok"]
+target = "{}"
+method = "Copy""#,
+                base.display(),
+                target.display(),
+            ))?;
+
+            match sync_with_options(
+                config,
+                SyncOptions {
+                    keep_going: true,
+                    ..Default::default()
+                },
+            ) {
+                Err(AppError::MultipleErrors(errors)) => {
+                    assert_eq!(errors.len(), 2);
+                    for (e, name) in errors.iter().zip(["a", "b"]) {
+                        assert!(matches!(e, AppError::IoError(_)));
+                        assert!(e
+                            .to_string()
+                            .contains(&base.join(name).join("item").display().to_string()));
+                    }
+                }
+                other => return Err(eyre!("Expected multiple errors, got {:?}", other)),
+            }
+            assert!(target.join("ok").is_file());
+            Ok(())
+        }
+    }
+
+    mod sensitive {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+
+        use crate::{config::DTConfig, syncing::sync};
+
+        use crate::utils::testing::{
+            capture_logs, captured_logs, get_testroot, prepare_directory, prepare_file,
+        };
+
+        /// Syncs a group using a secret templating value, returns whether
+        /// the secret appears in the captured logs.
+        fn logs_with_secret(name: &str, sensitive: bool) -> Result<bool, Report> {
+            let base = prepare_directory(get_testroot("syncing").join(name).join("base"), 0o755)?;
+            let target =
+                prepare_directory(get_testroot("syncing").join(name).join("target"), 0o755)?;
+            std::fs::write(prepare_file(base.join("item"), 0o644)?, "{{secrets.token}}")?;
+
+            capture_logs();
+            sync(
+                DTConfig::from_str(&format!(
+                    r#"
+[context.secrets]
+token = "correct-horse-battery-staple"
+
+[[local]]
+name = "secrets"
+base = "{}"
+sources = ["item"]
+target = "{}"
+method = "Copy"
+sensitive = {}"#,
+                    base.display(),
+                    target.display(),
+                    sensitive,
+                ))?,
+                false,
+            )?;
+            Ok(captured_logs()
+                .into_iter()
+                .any(|(_, msg)| msg.contains("correct-horse-battery-staple")))
+        }
+
+        #[test]
+        fn rendered_redacted() -> Result<(), Report> {
+            assert!(!logs_with_secret("rendered_redacted", true)?);
+            Ok(())
+        }
+
+        #[test]
+        fn rendered_logged_when_not_sensitive() -> Result<(), Report> {
+            assert!(logs_with_secret(
+                "rendered_logged_when_not_sensitive",
+                false
+            )?);
+            Ok(())
+        }
+
+        #[test]
+        fn context_never_logged() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing")
+                    .join("context_never_logged")
+                    .join("base"),
+                0o755,
+            )?;
+            prepare_file(base.join("item"), 0o644)?;
+            capture_logs();
+            sync(
+                DTConfig::from_str(&format!(
+                    r#"
+[context.secrets]
+token = "correct-horse-battery-staple"
+
+[[local]]
+name = "secrets"
+base = "{}"
+sources = ["item"]
+target = "{}""#,
+                    base.display(),
+                    base.with_file_name("target").display(),
+                ))?,
+                false,
+            )?;
+            assert!(!captured_logs()
+                .into_iter()
+                .any(|(_, msg)| msg.contains("correct-horse-battery-staple")));
+            Ok(())
+        }
+    }
+
+    mod summary {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn matches_filesystem_effects() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing")
+                    .join("matches_filesystem_effects")
+                    .join("base"),
+                0o755,
+            )?;
+            let target = prepare_directory(
+                get_testroot("syncing")
+                    .join("matches_filesystem_effects")
+                    .join("target"),
+                0o755,
+            )?;
+            let good = prepare_file(base.join("good"), 0o644)?;
+            let bad = prepare_file(base.join("dir").join("bad"), 0o644)?;
+            // A dangling symlink occupies the path where the target's parent
+            // directory should be created, forcing an IO error
+            if target.join("dir").symlink_metadata().is_ok() {
+                std::fs::remove_file(target.join("dir"))?;
+            }
+            if target.join("good").exists() {
+                std::fs::remove_file(target.join("good"))?;
+            }
+            std::os::unix::fs::symlink(target.join("nonexistent"), target.join("dir"))?;
+
+            let summary = sync(
+                DTConfig::from_str(&format!(
+                    r#"
+[[local]]
+name = "summary"
+base = "{}"
+sources = ["good", "dir"]
+target = "{}"
+method = "Copy"
+ignore_failure = true
+
+[[local]]
+name = "empty"
+base = "{}"
+sources = []
+target = "{}""#,
+                    base.display(),
+                    target.display(),
+                    base.display(),
+                    target.display(),
+                ))?,
+                false,
+            )?;
+
+            assert!(!summary.dry_run);
+            assert_eq!(summary.groups, 2);
+            assert_eq!(summary.items.len(), 2);
+            assert_eq!(summary.synced(), 1);
+            assert_eq!(summary.failed(), 1);
+            for item in &summary.items {
+                assert_eq!(item.group.to_string(), "summary");
+                if item.source == good {
+                    assert!(item.result.is_ok());
+                    assert!(target.join("good").is_file());
+                } else {
+                    assert_eq!(item.source, bad);
+                    assert!(item.result.is_err());
+                    assert!(!target.join("dir").join("bad").exists());
+                }
+            }
+            Ok(())
+        }
+    }
+
+    mod group_dry_run {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+
+        use crate::{config::DTConfig, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn preview_one_apply_another() -> Result<(), Report> {
+            let base = prepare_directory(
+                get_testroot("syncing")
+                    .join("preview_one_apply_another")
+                    .join("base"),
+                0o755,
+            )?;
+            let preview_target = get_testroot("syncing")
+                .join("preview_one_apply_another")
+                .join("preview");
+            let apply_target = get_testroot("syncing")
+                .join("preview_one_apply_another")
+                .join("apply");
+            for target in [&preview_target, &apply_target] {
+                if target.exists() {
+                    std::fs::remove_dir_all(target)?;
+                }
+            }
+            prepare_file(base.join("risky"), 0o644)?;
+            prepare_file(base.join("safe"), 0o644)?;
+
+            sync(
+                DTConfig::from_str(&format!(
+                    r#"
+[[local]]
+name = "risky"
+base = "{0}"
+sources = ["risky"]
+target = "{1}"
+method = "Copy"
+dry_run = true
+
+[[local]]
+name = "safe"
+base = "{0}"
+sources = ["safe"]
+target = "{2}"
+method = "Copy""#,
+                    base.display(),
+                    preview_target.display(),
+                    apply_target.display(),
+                ))?,
+                false,
+            )?;
+
+            assert!(!preview_target.join("risky").exists());
+            assert!(apply_target.join("safe").is_file());
+            Ok(())
+        }
+    }
+
+    mod explain {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{
+            config::{DTConfig, GroupName},
+            syncing::{explain, Decision, Explanation},
+        };
+
+        #[test]
+        fn dropped_by_priority() -> Result<(), Report> {
+            let explanations = explain(DTConfig::from_str(
+                r#"
+                [[local]]
+                name = "low"
+                scope = "General"
+                base = "../dt-cli"
+                sources = ["Cargo.toml"]
+                target = "."
+                [[local]]
+                name = "high"
+                scope = "App"
+                base = "../dt-server"
+                sources = ["Cargo.toml"]
+                target = "."
+            "#,
+            )?)?;
+
+            let dropped = explanations
+                .iter()
+                .filter(|e| matches!(e.decision, Decision::LostPriority(_)))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                dropped,
+                vec![&Explanation {
+                    group: GroupName("low".into()),
+                    source: std::fs::canonicalize("../dt-cli/Cargo.toml")?,
+                    decision: Decision::LostPriority(GroupName("high".into())),
+                }],
+            );
+            assert_eq!(
+                dropped[0].to_string(),
+                format!(
+                    "[low] '{}': excluded, lost priority to group [high]",
+                    dropped[0].source.display(),
+                ),
+            );
+            Ok(())
+        }
+    }
+
+    mod priority_resolving {
+        use std::str::FromStr;
+
+        use crate::{
+            config::*,
+            error::*,
+            syncing::{expand, resolved},
+        };
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        #[test]
+        fn proper_priority_orders() -> Result<()> {
+            assert!(DTScope::Dropin > DTScope::App);
+            assert!(DTScope::App > DTScope::General);
+            assert!(DTScope::Dropin > DTScope::General);
+
+            assert!(DTScope::App < DTScope::Dropin);
+            assert!(DTScope::General < DTScope::App);
+            assert!(DTScope::General < DTScope::Dropin);
+
+            Ok(())
+        }
+
+        #[test]
+        fn former_group_has_higher_priority_within_same_scope() -> Result<()> {
+            let config = expand(DTConfig::from_str(
+                r#"
+                [[local]]
+                name = "highest"
+                # Scope is omitted to use default scope (i.e. General)
+                base = "../dt-cli"
+                sources = ["Cargo.toml"]
+                target = "."
+                [[local]]
+                name = "low"
+                # Scope is omitted to use default scope (i.e. General)
+                base = "../dt-server"
+                sources = ["Cargo.toml"]
+                target = "."
+        "#,
+            )?)?;
+
+            assert!(!config.local[0].sources.is_empty());
+            assert!(config.local[1].sources.is_empty());
+
+            Ok(())
+        }
+
+        #[test]
+        fn conflict_log() -> std::result::Result<(), color_eyre::Report> {
+            let root = get_testroot("syncing").join("conflict_log");
+            let app = prepare_directory(root.join("app"), 0o755)?;
+            let general = prepare_directory(root.join("general"), 0o755)?;
+            let target = prepare_directory(root.join("target"), 0o755)?;
+            let log = root.join("conflicts.log");
+            if log.exists() {
+                std::fs::remove_file(&log)?;
+            }
+            prepare_file(general.join("rc"), 0o644)?;
+            prepare_file(app.join("rc"), 0o644)?;
+            prepare_file(general.join("only-general"), 0o644)?;
+
+            let config = expand(DTConfig::from_str(&format!(
+                r#"
+                [global]
+                conflict_log = "{}"
+                [[local]]
+                name = "general"
+                base = "{}"
+                sources = ["*"]
+                target = "{}"
+                [[local]]
+                name = "app"
+                scope = "App"
+                base = "{}"
+                sources = ["rc"]
+                target = "{}"
+        "#,
+                log.display(),
+                general.display(),
+                target.display(),
+                app.display(),
+                target.display(),
+            ))?)?;
+            assert_eq!(config.local[0].sources, vec![general.join("only-general")]);
+            assert_eq!(
+                std::fs::read_to_string(&log)?,
+                format!(
+                    "'{}' => '{}': dropped from [general], synced by [app]\n",
+                    general.join("rc").display(),
+                    target.join("rc").display(),
+                ),
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn resolved_winners() -> std::result::Result<(), color_eyre::Report> {
+            let root = get_testroot("syncing").join("resolved_winners");
+            let app = prepare_directory(root.join("app"), 0o755)?;
+            let general = prepare_directory(root.join("general"), 0o755)?;
+            let target = prepare_directory(root.join("target"), 0o755)?;
+            prepare_file(general.join("rc"), 0o644)?;
+            prepare_file(app.join("rc"), 0o644)?;
+            prepare_file(general.join("only-general"), 0o644)?;
+
+            let resolutions = resolved(DTConfig::from_str(&format!(
+                r#"
+                [[local]]
+                name = "general"
+                base = "{}"
+                sources = ["*"]
+                target = "{}"
+                [[local]]
+                name = "app"
+                scope = "App"
+                base = "{}"
+                sources = ["rc"]
+                target = "{}"
+        "#,
+                general.display(),
+                target.display(),
+                app.display(),
+                target.display(),
+            ))?)?;
+            assert_eq!(
+                resolutions
+                    .iter()
+                    .map(|r| r.to_string())
+                    .collect::<Vec<_>>(),
+                vec![
+                    format!(
+                        "'{}' <= [general] '{}'",
+                        target.join("only-general").display(),
+                        general.join("only-general").display(),
+                    ),
+                    format!(
+                        "'{}' <= [app] '{}'",
+                        target.join("rc").display(),
+                        app.join("rc").display(),
+                    ),
+                ],
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn definition_order_preserved() -> Result<()> {
+            // Names are deliberately not in lexical order
+            let names: Vec<String> = (0..16).map(|i| format!("g{}", (i * 7) % 16)).collect();
+            let config = DTConfig::from_str(
+                &names
+                    .iter()
+                    .map(|name| {
+                        format!(
+                            r#"
+                [[local]]
+                name = "{}"
+                base = "../dt-cli"
+                sources = ["Cargo.toml"]
+                target = ".""#,
+                            name,
+                        )
+                    })
+                    .collect::<String>(),
+            )?;
+            let group_names = |config: &DTConfig| {
+                config
+                    .local
+                    .iter()
+                    .map(|group| group.name.to_string())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(group_names(&config), names);
+
+            let config = expand(config)?;
+            assert_eq!(group_names(&config), names);
+            // Only the first defined group keeps the item
+            assert!(!config.local[0].sources.is_empty());
+            assert!(config.local[1..]
+                .iter()
+                .all(|group| group.sources.is_empty()));
+
+            Ok(())
+        }
+
+        #[test]
+        fn dropin_has_highest_priority() -> Result<()> {
+            let config = expand(DTConfig::from_str(
+                r#"
+                [[local]]
+                name = "lowest"
+                scope = "General"
+                base = "../dt-cli"
+                sources = ["Cargo.toml"]
+                target = "."
+                [[local]]
+                name = "medium"
+                scope = "App"
+                base = "../dt-server"
+                sources = ["Cargo.toml"]
+                target = "."
+                [[local]]
+                name = "highest"
+                scope = "Dropin"
+                base = ".."
+                sources = ["Cargo.toml"]
+                target = "."
+            "#,
+            )?)?;
+
+            assert!(config.local[0].sources.is_empty());
+            assert!(config.local[1].sources.is_empty());
+            assert!(!config.local[2].sources.is_empty());
+
+            Ok(())
+        }
+
+        #[test]
+        fn app_has_medium_priority() -> Result<()> {
+            let config = expand(DTConfig::from_str(
+                r#"
+                [[local]]
+                name = "lowest"
+                scope = "General"
+                base = "../dt-cli"
+                sources = ["Cargo.toml"]
+                target = "."
+                [[local]]
+                name = "medium"
+                scope = "App"
+                base = "../dt-server"
+                sources = ["Cargo.toml"]
+                target = "."
+            "#,
+            )?)?;
+
+            assert!(config.local[0].sources.is_empty());
+            assert!(!config.local[1].sources.is_empty());
+
+            Ok(())
+        }
+
+        #[test]
+        fn default_scope_is_general() -> Result<()> {
+            let config = expand(DTConfig::from_str(
+                r#"
+                [[local]]
+                name = "omitted scope but defined first, has higher priority"
+                # Scope is omitted to use default scope (i.e. General)
+                base = "../dt-cli"
+                sources = ["Cargo.toml"]
+                target = "."
+                [[local]]
+                name = "specified scope but defined last, has lower priority"
+                scope = "General"
+                base = "../dt-server"
+                sources = ["Cargo.toml"]
+                target = "."
+            "#,
+            )?)?;
+
+            assert!(!config.local[0].sources.is_empty());
+            assert!(config.local[1].sources.is_empty());
+
+            let config = expand(DTConfig::from_str(
+                r#"
+                [[local]]
+                name = "omitted scope, uses general"
+                # Scope is omitted to use default scope (i.e. General)
+                base = ".."
+                sources = ["Cargo.toml"]
+                target = "."
+                [[local]]
+                name = "specified scope with higher priority"
+                scope = "App"
+                base = ".."
+                sources = ["Cargo.toml"]
+                target = "."
+            "#,
+            )?)?;
+
+            assert!(config.local[0].sources.is_empty());
+            assert!(!config.local[1].sources.is_empty());
+
+            Ok(())
+        }
+
+        #[test]
+        fn duplicated_item_same_name_same_scope() -> Result<()> {
+            let config = expand(DTConfig::from_str(
+                r#"
+                [[local]]
+                name = "dup"
+                scope = "General"
+                base = "../dt-cli"
+                sources = ["Cargo.toml"]
+                target = "."
+                [[local]]
+                name = "dup"
+                scope = "General"
+                base = "../dt-server"
+                sources = ["Cargo.toml"]
+                target = "."
+            "#,
+            )?)?;
+
+            assert!(!config.local[0].sources.is_empty());
+            assert!(config.local[1].sources.is_empty());
+
+            Ok(())
+        }
+
+        #[test]
+        fn duplicated_item_same_name_different_scope() -> Result<()> {
+            let config = expand(DTConfig::from_str(
+                r#"
+                [[local]]
+                name = "dup"
+                scope = "General"
+                base = "../dt-cli"
+                sources = ["Cargo.toml"]
+                target = "."
+                [[local]]
+                name = "dup"
+                scope = "App"
+                base = "../dt-server"
+                sources = ["Cargo.toml"]
+                target = "."
+            "#,
+            )?)?;
+
+            assert!(config.local[0].sources.is_empty());
+            assert!(!config.local[1].sources.is_empty());
+
+            Ok(())
+        }
+    }
+
+    mod link_mode {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::sync};
+
+        use crate::utils::testing::{
+            get_testroot, prepare_directory, prepare_file, set_symlink_unsupported,
+        };
+
+        fn config(name: &str, link_mode: &str) -> Result<(DTConfig, std::path::PathBuf), Report> {
+            let root = get_testroot("syncing").join(name);
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = root.join("target");
+            if target.exists() {
+                std::fs::remove_dir_all(&target)?;
+            }
+            prepare_file(base.join("item"), 0o644)?;
+            let config = DTConfig::from_str(&format!(
+                r#"
+[global]
+staging = "{}"
+link_mode = "{}"
+
+[[local]]
+name = "fat"
+base = "{}"
+sources = ["item"]
+target = "{}""#,
+                root.join("staging").display(),
+                link_mode,
+                base.display(),
+                target.display(),
+            ))?;
+            Ok((config, target))
+        }
+
+        #[test]
+        fn copy_fallback() -> Result<(), Report> {
+            let (config, target) = config("link_mode_copy_fallback", "copy_fallback")?;
+            set_symlink_unsupported(true);
+            let result = sync(config, false);
+            set_symlink_unsupported(false);
+            result?;
+            let item = target.join("item");
+            assert!(!item.is_symlink());
+            assert_eq!(
+                std::fs::read_to_string(item)?,
+                "Created by: `dt_core::syncing::tests::prepare_file`\n",
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn strict_symlink() -> Result<(), Report> {
+            let (config, target) = config("link_mode_strict_symlink", "symlink")?;
+            set_symlink_unsupported(true);
+            let result = sync(config, false);
+            set_symlink_unsupported(false);
+            assert!(result.is_err());
+            assert!(!target.join("item").exists());
+            Ok(())
+        }
+    }
+
+    mod sidecar {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
         use pretty_assertions::assert_eq;
 
-        use crate::{config::*, item::Operate};
+        use crate::{config::DTConfig, syncing::sync};
 
-        use super::super::expand;
         use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
 
         #[test]
-        fn glob() -> Result<(), Report> {
-            let target_path =
-                prepare_directory(get_testroot("syncing").join("glob").join("target"), 0o755)?;
+        fn forces_copy() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("sidecar_forces_copy");
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = root.join("target");
+            if target.exists() {
+                std::fs::remove_dir_all(&target)?;
+            }
+            prepare_file(base.join("linked"), 0o644)?;
+            prepare_file(base.join("copied"), 0o644)?;
+            std::fs::write(
+                base.join("copied.dt.toml"),
+                "method = \"Copy\"\nrename = [[\"^copied$\", \"renamed\"]]\n",
+            )?;
+            let config = DTConfig::from_str(&format!(
+                r#"
+[global]
+staging = "{}"
+method = "Symlink"
 
-            let config = expand(
-                DTConfig::from_str(&format!(
-                    r#"
 [[local]]
-name = "globbing test"
-base = ".."
-sources = ["dt-c*"]
+name = "sidecar"
+base = "{}"
+sources = ["*"]
 target = "{}""#,
-                    target_path.display(),
-                ))
-                .unwrap(),
-            )?;
-            for group in &config.local {
-                assert_eq!(
-                    group.sources,
-                    vec![
-                        PathBuf::from_str("../dt-cli/Cargo.toml")
-                            .unwrap()
-                            .absolute()?,
-                        PathBuf::from_str("../dt-cli/README.md")
-                            .unwrap()
-                            .absolute()?,
-                        PathBuf::from_str("../dt-cli/src/main.rs")
-                            .unwrap()
-                            .absolute()?,
-                        PathBuf::from_str("../dt-core/Cargo.toml")
-                            .unwrap()
-                            .absolute()?,
-                        PathBuf::from_str("../dt-core/README.md")
-                            .unwrap()
-                            .absolute()?,
-                        PathBuf::from_str("../dt-core/src/config.rs")
-                            .unwrap()
-                            .absolute()?,
-                        PathBuf::from_str("../dt-core/src/error.rs")
-                            .unwrap()
-                            .absolute()?,
-                        PathBuf::from_str("../dt-core/src/item.rs")
-                            .unwrap()
-                            .absolute()?,
-                        PathBuf::from_str("../dt-core/src/lib.rs")
-                            .unwrap()
-                            .absolute()?,
-                        PathBuf::from_str("../dt-core/src/registry.rs")
-                            .unwrap()
-                            .absolute()?,
-                        PathBuf::from_str("../dt-core/src/syncing.rs")
-                            .unwrap()
-                            .absolute()?,
-                        PathBuf::from_str("../dt-core/src/utils.rs")
-                            .unwrap()
-                            .absolute()?,
-                    ],
-                );
+                root.join("staging").display(),
+                base.display(),
+                target.display(),
+            ))?;
+
+            sync(config, false)?;
+            assert!(target.join("linked").is_symlink());
+            assert!(!target.join("renamed").is_symlink());
+            assert_eq!(
+                std::fs::read_to_string(target.join("renamed"))?,
+                std::fs::read_to_string(base.join("copied"))?,
+            );
+            assert!(!target.join("copied").exists());
+            assert!(!target.join("copied.dt.toml").exists());
+            assert!(!target.join("renamed.dt.toml").exists());
+            Ok(())
+        }
+    }
+
+    mod since_commit {
+        use std::{path::Path, process::Command, str::FromStr};
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{
+            config::DTConfig,
+            syncing::{sync_with_options, SyncOptions},
+        };
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        fn git(repo: &Path, args: &[&str]) -> Result<(), Report> {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(repo)
+                .args(["-c", "user.name=dt", "-c", "user.email=dt@localhost"])
+                .args(args)
+                .output()?
+                .status;
+            assert!(status.success(), "git {:?} failed", args);
+            Ok(())
+        }
+
+        #[test]
+        fn only_changed_files() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("since_commit_only_changed_files");
+            if root.exists() {
+                std::fs::remove_dir_all(&root)?;
             }
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = root.join("target");
+            prepare_file(base.join("changed"), 0o644)?;
+            prepare_file(base.join("unchanged"), 0o644)?;
+            git(&base, &["init", "--quiet"])?;
+            git(&base, &["add", "."])?;
+            git(&base, &["commit", "--quiet", "--message", "initial"])?;
+            std::fs::write(base.join("changed"), "changed\n")?;
+            git(
+                &base,
+                &["commit", "--quiet", "--all", "--message", "change"],
+            )?;
+            std::fs::write(base.join(".git").join("info").join("exclude"), "ignored\n")?;
+            std::fs::write(base.join("untracked with\ttab"), "untracked\n")?;
+            prepare_file(base.join("ignored"), 0o644)?;
+
+            let config = DTConfig::from_str(&format!(
+                r#"
+[global]
+method = "Copy"
+
+[[local]]
+name = "repo"
+base = "{}"
+sources = ["*"]
+target = "{}""#,
+                base.display(),
+                target.display(),
+            ))?;
+            let summary = sync_with_options(
+                config,
+                SyncOptions {
+                    since_commit: Some("HEAD~1".to_owned()),
+                    ..Default::default()
+                },
+            )?;
+            assert_eq!(summary.items.len(), 2);
+            assert_eq!(
+                std::fs::read_to_string(target.join("changed"))?,
+                "changed\n"
+            );
+            assert_eq!(
+                std::fs::read_to_string(target.join("untracked with\ttab"))?,
+                "untracked\n"
+            );
+            assert!(!target.join("ignored").exists());
+            assert!(!target.join("unchanged").exists());
             Ok(())
         }
 
         #[test]
-        fn sorting_and_deduping() -> Result<(), Report> {
-            println!("Creating base ..");
-            let base_path = prepare_directory(
+        fn non_repo_skipped() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("since_commit_non_repo_skipped");
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = root.join("target");
+            if target.exists() {
+                std::fs::remove_dir_all(&target)?;
+            }
+            prepare_file(base.join("item"), 0o644)?;
+            let config = DTConfig::from_str(&format!(
+                r#"
+[global]
+method = "Copy"
+
+[[local]]
+name = "plain"
+base = "{}"
+sources = ["item"]
+target = "{}""#,
+                base.display(),
+                target.display(),
+            ))?;
+            let summary = sync_with_options(
+                config,
+                SyncOptions {
+                    since_commit: Some("HEAD".to_owned()),
+                    ..Default::default()
+                },
+            )?;
+            assert!(summary.items.is_empty());
+            assert!(!target.join("item").exists());
+            Ok(())
+        }
+    }
+
+    mod on_missing_source {
+        use std::str::FromStr;
+
+        use color_eyre::{eyre::eyre, Report};
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::expand};
+
+        use crate::utils::testing::{
+            capture_logs, captured_logs, get_testroot, prepare_directory, prepare_file,
+        };
+
+        fn config(policy: &str) -> Result<DTConfig, Report> {
+            let base = prepare_directory(
                 get_testroot("syncing")
-                    .join("sorting_and_deduping")
-                    .join("base"),
+                    .join("on_missing_source")
+                    .join(policy),
                 0o755,
             )?;
-            println!("Creating target ..");
-            let target_path = prepare_directory(
+            prepare_file(base.join("present"), 0o644)?;
+            Ok(DTConfig::from_str(&format!(
+                r#"
+[global]
+on_missing_source = "{}"
+
+[[local]]
+name = "missing"
+base = "{}"
+sources = ["present", "gone"]
+target = "/tmp""#,
+                policy,
+                base.display(),
+            ))?)
+        }
+
+        #[test]
+        fn error() -> Result<(), Report> {
+            if let Err(err) = expand(config("error")?) {
+                assert!(err.to_string().contains("gone"));
+                assert!(err.to_string().contains("does not exist"));
+                Ok(())
+            } else {
+                Err(eyre!(
+                    "This config should not be loaded because a listed source is missing"
+                ))
+            }
+        }
+
+        #[test]
+        fn warn() -> Result<(), Report> {
+            let config = config("warn")?;
+            let base = config.local[0].base.to_owned();
+            capture_logs();
+            let config = expand(config)?;
+            assert_eq!(config.local[0].sources, vec![base.join("present")]);
+            assert!(captured_logs()
+                .iter()
+                .any(|(_, msg)| msg.contains("gone") && msg.contains("does not exist")));
+            Ok(())
+        }
+
+        #[test]
+        fn skip() -> Result<(), Report> {
+            let config = config("skip")?;
+            let base = config.local[0].base.to_owned();
+            let config = expand(config)?;
+            assert_eq!(config.local[0].sources, vec![base.join("present")]);
+            Ok(())
+        }
+
+        #[test]
+        fn unset_warns() -> Result<(), Report> {
+            let base = prepare_directory(
                 get_testroot("syncing")
-                    .join("sorting_and_deduping")
-                    .join("target"),
+                    .join("on_missing_source")
+                    .join("unset"),
                 0o755,
             )?;
-            for f in ["A-a", "A-b", "A-c", "B-a", "B-b", "B-c"] {
-                println!("Creating source {} ..", f);
-                prepare_file(base_path.join(f), 0o644)?;
-            }
+            prepare_file(base.join("present"), 0o644)?;
+            let config = DTConfig::from_str(&format!(
+                r#"
+[[local]]
+name = "missing"
+base = "{}"
+sources = ["present", "gone"]
+target = "/tmp""#,
+                base.display(),
+            ))?;
+            capture_logs();
+            let config = expand(config)?;
+            assert_eq!(config.local[0].sources, vec![base.join("present")]);
+            assert!(captured_logs()
+                .iter()
+                .any(|(_, msg)| msg.contains("gone") && msg.contains("did not match anything")));
+            Ok(())
+        }
+    }
+
+    mod show_rendered {
+        use std::str::FromStr;
+
+        use color_eyre::{eyre::eyre, Report};
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::render_source};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        fn config(name: &str) -> Result<DTConfig, Report> {
+            let base = prepare_directory(get_testroot("syncing").join(name), 0o755)?;
+            std::fs::write(
+                prepare_file(base.join("gitconfig"), 0o644)?,
+                "[user]\n\tname = {{user.name}}\n",
+            )?;
+            prepare_file(base.join("other"), 0o644)?;
+            Ok(DTConfig::from_str(&format!(
+                r#"
+[context.user]
+name = "dt"
 
-            let config = expand(
-                DTConfig::from_str(&format!(
-                    r#"
 [[local]]
-name = "sorting and deduping"
+name = "git"
 base = "{}"
-sources = ["B-*", "*-c", "A-b", "A-a"]
-target = "{}""#,
-                    base_path.display(),
-                    target_path.display(),
+sources = ["gitconfig"]
+target = "/tmp""#,
+                base.display(),
+            ))?)
+        }
+
+        #[test]
+        fn renders_one_source() -> Result<(), Report> {
+            let config = config("show_rendered_renders_one_source")?;
+            let source = config.local[0].base.join("gitconfig");
+            assert_eq!(
+                String::from_utf8(render_source(config, source)?)?,
+                "[user]\n\tname = dt\n",
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn not_in_any_group() -> Result<(), Report> {
+            let config = config("show_rendered_not_in_any_group")?;
+            let source = config.local[0].base.join("other");
+            if let Err(err) = render_source(config, source) {
+                assert!(err.to_string().contains("is not a source of any group"));
+                Ok(())
+            } else {
+                Err(eyre!(
+                    "This source should not be rendered because no group contains it"
                 ))
-                .unwrap(),
+            }
+        }
+    }
+
+    mod symlink_broken_ok {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        fn config(name: &str, symlink_broken_ok: bool) -> Result<DTConfig, Report> {
+            let root = get_testroot("syncing").join(name);
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            for dir in ["staging", "target"] {
+                if root.join(dir).exists() {
+                    std::fs::remove_dir_all(root.join(dir))?;
+                }
+            }
+            prepare_file(base.join("item"), 0o644)?;
+            Ok(DTConfig::from_str(&format!(
+                r#"
+[global]
+staging = "{0}/staging"
+symlink_broken_ok = {1}
+
+[[local]]
+name = "bootstrap"
+base = "{0}/base"
+sources = ["item"]
+target = "{0}/target""#,
+                root.display(),
+                symlink_broken_ok,
+            ))?)
+        }
+
+        #[test]
+        fn dangling_link_created() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("symlink_broken_ok_dangling_link_created");
+            sync(
+                config("symlink_broken_ok_dangling_link_created", true)?,
+                false,
             )?;
-            for group in config.local {
-                assert_eq!(
-                    group.sources,
-                    vec![
-                        base_path.join("A-a"),
-                        base_path.join("A-b"),
-                        base_path.join("A-c"),
-                        base_path.join("B-a"),
-                        base_path.join("B-b"),
-                        base_path.join("B-c"),
-                    ],
-                );
+            let tpath = root.join("target").join("item");
+            assert!(tpath.is_symlink());
+            assert!(!tpath.exists());
+            assert_eq!(
+                std::fs::read_link(&tpath)?,
+                root.join("staging").join("bootstrap").join("item"),
+            );
+            assert!(!root.join("staging").exists());
+            Ok(())
+        }
+
+        #[test]
+        fn staged_by_default() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("symlink_broken_ok_staged_by_default");
+            sync(config("symlink_broken_ok_staged_by_default", false)?, false)?;
+            let tpath = root.join("target").join("item");
+            assert!(tpath.is_symlink());
+            assert!(tpath.exists());
+            Ok(())
+        }
+    }
+
+    mod hooks {
+        use std::{path::PathBuf, str::FromStr};
+
+        use color_eyre::{eyre::eyre, Report};
+        use pretty_assertions::assert_eq;
+
+        use crate::{config::DTConfig, syncing::sync};
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        /// Prepares a config with two groups, whose hooks append to a log
+        /// file under the test directory, which is returned along with it.
+        fn hooked(name: &str, global: &str) -> Result<(DTConfig, PathBuf), Report> {
+            let root = get_testroot("syncing").join(name);
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = root.join("target");
+            if target.exists() {
+                std::fs::remove_dir_all(&target)?;
+            }
+            let log = root.join("hooks.log");
+            if log.exists() {
+                std::fs::remove_file(&log)?;
+            }
+            prepare_file(base.join("a"), 0o644)?;
+            prepare_file(base.join("b"), 0o644)?;
+            let config = DTConfig::from_str(&format!(
+                r#"
+[global]
+method = "Copy"
+{0}
+
+[[local]]
+name = "a"
+base = "{1}"
+sources = ["a"]
+target = "{2}"
+
+[[local]]
+name = "b"
+base = "{1}"
+sources = ["b"]
+target = "{2}""#,
+                global
+                    .replace("{log}", &log.display().to_string())
+                    .replace("{target}", &target.display().to_string()),
+                base.display(),
+                target.display(),
+            ))?;
+            Ok((config, log))
+        }
+
+        #[test]
+        fn run_once() -> Result<(), Report> {
+            let (config, log) = hooked(
+                "hooks_run_once",
+                r#"pre_sync = "test ! -e {target}/a && echo pre >> {log}"
+post_sync = "test -e {target}/a && test -e {target}/b && echo post >> {log}""#,
+            )?;
+            sync(config, false)?;
+            assert_eq!(std::fs::read_to_string(log)?, "pre\npost\n");
+            Ok(())
+        }
+
+        #[test]
+        fn dry_run() -> Result<(), Report> {
+            let (config, log) = hooked(
+                "hooks_dry_run",
+                r#"pre_sync = "echo pre >> {log}"
+post_sync = "echo post >> {log}""#,
+            )?;
+            sync(config, true)?;
+            assert!(!log.exists());
+            Ok(())
+        }
+
+        #[test]
+        fn failure() -> Result<(), Report> {
+            let (config, log) = hooked(
+                "hooks_failure",
+                r#"pre_sync = "false"
+post_sync = "echo post >> {log}""#,
+            )?;
+            if let Err(err) = sync(config, false) {
+                assert!(err.to_string().contains("pre_sync"));
+                assert!(!log.exists());
+            } else {
+                return Err(eyre!("Syncing should fail because `pre_sync` failed"));
             }
+
+            let (config, log) = hooked(
+                "hooks_failure",
+                r#"pre_sync = "false"
+post_sync = "echo post >> {log}"
+ignore_hook_failure = true"#,
+            )?;
+            sync(config, false)?;
+            assert_eq!(std::fs::read_to_string(log)?, "post\n");
             Ok(())
         }
     }
 
-    mod priority_resolving {
-        use std::str::FromStr;
+    #[cfg(feature = "remote")]
+    mod remote {
+        use std::{
+            io::{Read, Write},
+            net::TcpListener,
+            path::PathBuf,
+            str::FromStr,
+        };
+
+        use color_eyre::{eyre::eyre, Report};
+        use pretty_assertions::assert_eq;
+
+        use crate::{
+            config::{DTConfig, SyncMethod},
+            error::Error as AppError,
+            syncing::{sync, sync_with_options, verify, DriftKind, SyncOptions},
+        };
+
+        use crate::utils::testing::{get_testroot, prepare_directory};
+
+        /// Serves `body` to the next `times` HTTP requests on a local port,
+        /// which is returned.
+        fn serve(body: &'static str, times: usize) -> Result<u16, Report> {
+            let listener = TcpListener::bind("127.0.0.1:0")?;
+            let port = listener.local_addr()?.port();
+            std::thread::spawn(move || {
+                for stream in listener.incoming().take(times) {
+                    let mut stream = stream.unwrap();
+                    let mut request = Vec::new();
+                    let mut buf = [0; 1024];
+                    while !request.ends_with(b"\r\n\r\n") {
+                        let n = stream.read(&mut buf).unwrap();
+                        if n == 0 {
+                            break;
+                        }
+                        request.extend_from_slice(&buf[..n]);
+                    }
+                    write!(
+                        stream,
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body,
+                    )
+                    .unwrap();
+                }
+            });
+            Ok(port)
+        }
+
+        /// Prepares a config with a remote group fetching `greeting` from
+        /// given port, returns it along with its target directory.
+        fn remote(name: &str, port: u16, group: &str) -> Result<(DTConfig, PathBuf), Report> {
+            let root = prepare_directory(get_testroot("syncing").join(name), 0o755)?;
+            let target = root.join("target");
+            let staging = root.join("staging");
+            for dir in [&target, &staging] {
+                if dir.exists() {
+                    std::fs::remove_dir_all(dir)?;
+                }
+            }
+            let config = DTConfig::from_str(&format!(
+                r#"
+[global]
+staging = "{}"
+
+[context.greeting]
+name = "world"
 
-        use crate::{config::*, error::*, syncing::expand};
+[[remote]]
+name = "greeting"
+base = "http://127.0.0.1:{}/dotfiles/"
+sources = ["http://127.0.0.1:{1}/dotfiles/greeting"]
+target = "{}"
+{}"#,
+                staging.display(),
+                port,
+                target.display(),
+                group,
+            ))?;
+            Ok((config, target))
+        }
 
         #[test]
-        fn proper_priority_orders() -> Result<()> {
-            assert!(DTScope::Dropin > DTScope::App);
-            assert!(DTScope::App > DTScope::General);
-            assert!(DTScope::Dropin > DTScope::General);
+        fn copy() -> Result<(), Report> {
+            let port = serve("Hello, {{greeting.name}}!", 1)?;
+            let (config, target) = remote("remote_copy", port, r#"method = "Copy""#)?;
+            let summary = sync(config, false)?;
+            let tpath = target.join("greeting");
+            assert!(!tpath.is_symlink());
+            assert_eq!(std::fs::read_to_string(tpath)?, "Hello, world!");
+            assert_eq!(summary.items.len(), 1);
+            assert_eq!(
+                summary.items[0]
+                    .url
+                    .as_ref()
+                    .map(|url| url.as_str().to_owned()),
+                Some(format!("http://127.0.0.1:{}/dotfiles/greeting", port)),
+            );
+            assert!(summary.items[0].result.is_ok());
+            Ok(())
+        }
 
-            assert!(DTScope::App < DTScope::Dropin);
-            assert!(DTScope::General < DTScope::App);
-            assert!(DTScope::General < DTScope::Dropin);
+        #[test]
+        fn symlink() -> Result<(), Report> {
+            let port = serve("Hello, {{greeting.name}}!", 1)?;
+            let (config, target) = remote("remote_symlink", port, r#"method = "Symlink""#)?;
+            sync(config, false)?;
+            let tpath = target.join("greeting");
+            assert!(tpath.is_symlink());
+            assert_eq!(std::fs::read_to_string(tpath)?, "Hello, world!");
+            Ok(())
+        }
 
+        #[test]
+        fn unrenderable() -> Result<(), Report> {
+            let port = serve("Hello, {{greeting.name}}!", 1)?;
+            let (config, target) = remote(
+                "remote_unrenderable",
+                port,
+                r#"method = "Copy"
+renderable = false"#,
+            )?;
+            sync(config, false)?;
+            assert_eq!(
+                std::fs::read_to_string(target.join("greeting"))?,
+                "Hello, {{greeting.name}}!",
+            );
             Ok(())
         }
 
         #[test]
-        fn former_group_has_higher_priority_within_same_scope() -> Result<()> {
-            let config = expand(DTConfig::from_str(
-                r#"
-                [[local]]
-                name = "highest"
-                # Scope is omitted to use default scope (i.e. General)
-                base = "../dt-cli"
-                sources = ["Cargo.toml"]
-                target = "."
-                [[local]]
-                name = "low"
-                # Scope is omitted to use default scope (i.e. General)
-                base = "../dt-server"
-                sources = ["Cargo.toml"]
-                target = "."
-        "#,
-            )?)?;
+        fn overwrite_denied() -> Result<(), Report> {
+            let port = serve("Hello, {{greeting.name}}!", 1)?;
+            let (config, target) = remote("remote_overwrite_denied", port, r#"method = "Copy""#)?;
+            std::fs::create_dir_all(&target)?;
+            std::fs::write(target.join("greeting"), "existing")?;
+            sync(config, false)?;
+            assert_eq!(
+                std::fs::read_to_string(target.join("greeting"))?,
+                "existing"
+            );
+            Ok(())
+        }
 
-            assert!(!config.local[0].sources.is_empty());
-            assert!(config.local[1].sources.is_empty());
+        #[test]
+        fn overwrite_allowed() -> Result<(), Report> {
+            let port = serve("Hello, {{greeting.name}}!", 1)?;
+            let (config, target) = remote(
+                "remote_overwrite_allowed",
+                port,
+                r#"method = "Copy"
+allow_overwrite = true"#,
+            )?;
+            std::fs::create_dir_all(&target)?;
+            std::fs::write(target.join("greeting"), "existing")?;
+            sync(config, false)?;
+            assert_eq!(
+                std::fs::read_to_string(target.join("greeting"))?,
+                "Hello, world!",
+            );
+            Ok(())
+        }
 
+        #[test]
+        fn dry_run() -> Result<(), Report> {
+            // Nothing is served, a dry run does not fetch anything
+            let port = TcpListener::bind("127.0.0.1:0")?.local_addr()?.port();
+            let (config, target) = remote("remote_dry_run", port, r#"method = "Copy""#)?;
+            sync(config, true)?;
+            assert!(!target.exists());
             Ok(())
         }
 
         #[test]
-        fn dropin_has_highest_priority() -> Result<()> {
-            let config = expand(DTConfig::from_str(
-                r#"
-                [[local]]
-                name = "lowest"
-                scope = "General"
-                base = "../dt-cli"
-                sources = ["Cargo.toml"]
-                target = "."
-                [[local]]
-                name = "medium"
-                scope = "App"
-                base = "../dt-server"
-                sources = ["Cargo.toml"]
-                target = "."
-                [[local]]
-                name = "highest"
-                scope = "Dropin"
-                base = ".."
-                sources = ["Cargo.toml"]
-                target = "."
-            "#,
-            )?)?;
+        fn unreachable() -> Result<(), Report> {
+            // The listener is dropped right away, so connections are refused
+            let port = TcpListener::bind("127.0.0.1:0")?.local_addr()?.port();
+            let (config, target) = remote("remote_unreachable", port, r#"method = "Copy""#)?;
+            if let Err(err) = sync(config, false) {
+                assert!(matches!(err, AppError::NetworkError(_)), "{}", err);
+                assert!(!target.join("greeting").exists());
+                Ok(())
+            } else {
+                Err(eyre!(
+                    "This sync should fail because the remote item is unreachable"
+                ))
+            }
+        }
 
-            assert!(config.local[0].sources.is_empty());
-            assert!(config.local[1].sources.is_empty());
-            assert!(!config.local[2].sources.is_empty());
+        #[test]
+        fn orphan_deleted() -> Result<(), Report> {
+            let port = serve("Hello, {{greeting.name}}!", 1)?;
+            let (config, target) = remote("remote_orphan_deleted", port, r#"method = "Symlink""#)?;
+            let options = || SyncOptions {
+                delete_orphans: true,
+                ..Default::default()
+            };
+            let staging = config.global.staging.0.to_owned();
+            sync_with_options(config, options())?;
+            assert!(target.join("greeting").is_symlink());
+
+            // The remote group is gone from the config
+            let config = DTConfig::from_str(&format!(
+                r#"
+[global]
+staging = "{}"
 
+[[local]]
+name = "empty"
+base = "{}"
+sources = []
+target = "{}""#,
+                staging.display(),
+                target.parent().unwrap().display(),
+                target.parent().unwrap().join("other").display(),
+            ))?;
+            sync_with_options(config, options())?;
+            assert!(!target.join("greeting").is_symlink());
             Ok(())
         }
 
         #[test]
-        fn app_has_medium_priority() -> Result<()> {
-            let config = expand(DTConfig::from_str(
-                r#"
-                [[local]]
-                name = "lowest"
-                scope = "General"
-                base = "../dt-cli"
-                sources = ["Cargo.toml"]
-                target = "."
-                [[local]]
-                name = "medium"
-                scope = "App"
-                base = "../dt-server"
-                sources = ["Cargo.toml"]
-                target = "."
-            "#,
-            )?)?;
+        fn verified() -> Result<(), Report> {
+            let port = serve("Hello, {{greeting.name}}!", 1)?;
+            let (config, target) = remote("remote_verified", port, r#"method = "Copy""#)?;
+            sync(config.to_owned(), false)?;
+            // Verifying uses the downloaded copy, nothing is served anymore
+            assert!(verify(config.to_owned())?.is_empty());
 
-            assert!(config.local[0].sources.is_empty());
-            assert!(!config.local[1].sources.is_empty());
+            std::fs::write(target.join("greeting"), "drifted")?;
+            let drifts = verify(config.to_owned())?;
+            assert_eq!(drifts.len(), 1);
+            assert_eq!(drifts[0].target, target.join("greeting"));
+            assert_eq!(drifts[0].kind, DriftKind::Mismatched);
 
+            std::fs::remove_file(target.join("greeting"))?;
+            assert_eq!(verify(config)?[0].kind, DriftKind::Missing);
             Ok(())
         }
 
         #[test]
-        fn default_scope_is_general() -> Result<()> {
-            let config = expand(DTConfig::from_str(
-                r#"
-                [[local]]
-                name = "omitted scope but defined first, has higher priority"
-                # Scope is omitted to use default scope (i.e. General)
-                base = "../dt-cli"
-                sources = ["Cargo.toml"]
-                target = "."
-                [[local]]
-                name = "specified scope but defined last, has lower priority"
-                scope = "General"
-                base = "../dt-server"
-                sources = ["Cargo.toml"]
-                target = "."
-            "#,
-            )?)?;
+        fn target_prefix() -> Result<(), Report> {
+            let port = serve("Hello, {{greeting.name}}!", 1)?;
+            let (config, target) = remote("remote_target_prefix", port, r#"method = "Copy""#)?;
+            let root = target.parent().unwrap().join("root");
+            if root.exists() {
+                std::fs::remove_dir_all(&root)?;
+            }
+            let config = config.with_target_prefix(&root)?;
+            sync(config, false)?;
+            assert_eq!(
+                std::fs::read_to_string(root.join(target.strip_prefix("/")?).join("greeting"))?,
+                "Hello, world!",
+            );
+            assert!(!target.join("greeting").exists());
+            Ok(())
+        }
 
-            assert!(!config.local[0].sources.is_empty());
-            assert!(config.local[1].sources.is_empty());
+        #[test]
+        fn symlink_direct_unvalidated() -> Result<(), Report> {
+            let port = serve("Hello, {{greeting.name}}!", 1)?;
+            let (mut config, target) = remote("remote_symlink_direct_unvalidated", port, "")?;
+            // Validating would have denied this
+            config.remote[0].method = Some(SyncMethod::SymlinkDirect);
+            if let Err(err) = sync(config, false) {
+                assert_eq!(
+                    err,
+                    AppError::ConfigError(
+                        "remote group 'greeting' cannot use the `SymlinkDirect` method".to_owned()
+                    )
+                    .context(format!(
+                        "Group [greeting], source 'http://127.0.0.1:{}/dotfiles/greeting'",
+                        port,
+                    )),
+                );
+                assert!(!target.join("greeting").exists());
+                Ok(())
+            } else {
+                Err(eyre!(
+                    "This sync should fail because remote items can not be linked directly"
+                ))
+            }
+        }
+    }
 
-            let config = expand(DTConfig::from_str(
+    mod watch {
+        use std::{cell::Cell, path::Path, str::FromStr};
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{
+            config::DTConfig,
+            syncing::{watch, SyncOptions, WatchEvent},
+        };
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        /// Loads a config with a single group syncing `item` from `base`
+        /// into `target`, counting loads with `loads`.
+        fn load(base: &Path, target: &Path, loads: &Cell<usize>) -> crate::error::Result<DTConfig> {
+            loads.set(loads.get() + 1);
+            DTConfig::from_str(&format!(
                 r#"
-                [[local]]
-                name = "omitted scope, uses general"
-                # Scope is omitted to use default scope (i.e. General)
-                base = ".."
-                sources = ["Cargo.toml"]
-                target = "."
-                [[local]]
-                name = "specified scope with higher priority"
-                scope = "App"
-                base = ".."
-                sources = ["Cargo.toml"]
-                target = "."
-            "#,
-            )?)?;
+[[local]]
+name = "watched"
+base = "{}"
+sources = ["item"]
+target = "{}"
+method = "Copy""#,
+                base.display(),
+                target.display(),
+            ))
+        }
 
-            assert!(config.local[0].sources.is_empty());
-            assert!(!config.local[1].sources.is_empty());
+        #[test]
+        fn reload_resyncs() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("watch_reload_resyncs");
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = root.join("target");
+            if target.exists() {
+                std::fs::remove_dir_all(&target)?;
+            }
+            std::fs::write(prepare_file(base.join("item"), 0o644)?, "before")?;
+            let loads = Cell::new(0);
 
+            let events = [WatchEvent::Reload, WatchEvent::Exit, WatchEvent::Reload]
+                .into_iter()
+                .inspect(|event| {
+                    // The source changes before the reload arrives
+                    if *event == WatchEvent::Reload {
+                        std::fs::write(base.join("item"), "after").unwrap();
+                    }
+                    // Every event is handled after the previous one
+                    if *event == WatchEvent::Exit {
+                        assert_eq!(
+                            std::fs::read_to_string(target.join("item")).unwrap(),
+                            "after",
+                        );
+                    }
+                });
+            let synced = watch(
+                load(&base, &target, &loads)?,
+                || load(&base, &target, &loads),
+                SyncOptions::default(),
+                events,
+            )?;
+            // Nothing is handled after exiting, and the config is only
+            // loaded again on reloading
+            assert_eq!(synced, 2);
+            assert_eq!(loads.get(), 2);
+            assert_eq!(std::fs::read_to_string(target.join("item"))?, "after");
             Ok(())
         }
 
         #[test]
-        fn duplicated_item_same_name_same_scope() -> Result<()> {
-            let config = expand(DTConfig::from_str(
+        fn failed_reload_keeps_watching() -> Result<(), Report> {
+            let root = get_testroot("syncing").join("watch_failed_reload_keeps_watching");
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = root.join("target");
+            if target.exists() {
+                std::fs::remove_dir_all(&target)?;
+            }
+            prepare_file(base.join("item"), 0o644)?;
+            let loads = Cell::new(0);
+
+            let synced = watch(
+                load(&base, &target, &loads)?,
+                || {
+                    // The config is broken on the first reload only
+                    if loads.get() == 1 {
+                        loads.set(2);
+                        return DTConfig::from_str("not a config");
+                    }
+                    load(&base, &target, &loads)
+                },
+                SyncOptions::default(),
+                [WatchEvent::Reload, WatchEvent::Reload],
+            )?;
+            assert_eq!(synced, 2);
+            Ok(())
+        }
+    }
+
+    mod diff {
+        use std::str::FromStr;
+
+        use color_eyre::Report;
+        use pretty_assertions::assert_eq;
+
+        use crate::{
+            config::DTConfig,
+            syncing::{diff, ItemDiff},
+        };
+
+        use crate::utils::testing::{get_testroot, prepare_directory, prepare_file};
+
+        /// Prepares a group whose only item renders differently from its
+        /// existing target, returns the difference of that item.
+        fn changed(name: &str) -> Result<ItemDiff, Report> {
+            let root = get_testroot("syncing").join(name);
+            let base = prepare_directory(root.join("base"), 0o755)?;
+            let target = prepare_directory(root.join("target"), 0o755)?;
+            std::fs::write(
+                prepare_file(base.join("item"), 0o644)?,
+                "same\n{{item.value}}\n",
+            )?;
+            std::fs::write(target.join("item"), "same\nold\n")?;
+            let mut diffs = diff(DTConfig::from_str(&format!(
                 r#"
-                [[local]]
-                name = "dup"
-                scope = "General"
-                base = "../dt-cli"
-                sources = ["Cargo.toml"]
-                target = "."
-                [[local]]
-                name = "dup"
-                scope = "General"
-                base = "../dt-server"
-                sources = ["Cargo.toml"]
-                target = "."
-            "#,
-            )?)?;
+[context.item]
+value = "new"
 
-            assert!(!config.local[0].sources.is_empty());
-            assert!(config.local[1].sources.is_empty());
+[[local]]
+name = "item"
+base = "{}"
+sources = ["item"]
+target = "{}"
+method = "Copy""#,
+                base.display(),
+                target.display(),
+            ))?)?;
+            assert_eq!(diffs.len(), 1);
+            Ok(diffs.remove(0))
+        }
 
+        #[test]
+        fn unified() -> Result<(), Report> {
+            let item = changed("diff_unified")?;
+            assert_eq!(item.current, b"same\nold\n");
+            assert_eq!(item.proposed, b"same\nnew\n");
+            let unified = item.unified();
+            assert!(unified.contains("-old\n"), "{}", unified);
+            assert!(unified.contains("+new\n"), "{}", unified);
+            assert!(unified.contains(" same\n"), "{}", unified);
             Ok(())
         }
 
         #[test]
-        fn duplicated_item_same_name_different_scope() -> Result<()> {
-            let config = expand(DTConfig::from_str(
-                r#"
-                [[local]]
-                name = "dup"
-                scope = "General"
-                base = "../dt-cli"
-                sources = ["Cargo.toml"]
-                target = "."
-                [[local]]
-                name = "dup"
-                scope = "App"
-                base = "../dt-server"
-                sources = ["Cargo.toml"]
-                target = "."
-            "#,
-            )?)?;
-
-            assert!(config.local[0].sources.is_empty());
-            assert!(!config.local[1].sources.is_empty());
+        fn tool() -> Result<(), Report> {
+            let item = changed("diff_tool")?;
+            assert!(item.open_with("true")?.success());
+            // A non-zero status (here: contents differ) is not an error
+            assert!(!item.open_with("cmp -s")?.success());
+            Ok(())
+        }
 
+        #[test]
+        fn tool_files_private() -> Result<(), Report> {
+            let item = changed("diff_tool_files_private")?;
+            assert!(item
+                .open_with(
+                    r#"f() { test "$(stat -c %a "$1")" = 600 && test "$(stat -c %a "${1%/*/*}")" = 700; }; f"#
+                )?
+                .success());
             Ok(())
         }
     }
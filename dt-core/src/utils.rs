@@ -1,12 +1,17 @@
-use std::path::{Path, PathBuf};
+use std::{
+    cell::Cell,
+    io::{IsTerminal, Read, Seek},
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+};
 
 use crate::error::{Error as AppError, Result};
 
 /// Gets config path from environment variables, or infer one.
 ///
 /// 1. If the environment variable indexed by `env_for_file`'s value is
-/// present, that environment variable's value is returned as the config file
-/// path.
+///    present, that environment variable's value is returned as the config
+///    file path.
 ///
 /// # Example
 ///
@@ -21,12 +26,12 @@ use crate::error::{Error as AppError, Result};
 /// );
 /// ```
 ///
-/// 2. Otherwise, if the environment variable indexed by `env_for_dir`'s value
-/// is present, that environment variable's value is considered the parent
-/// directory of the returned config path, filenames within `search_list` will
-/// be checked in order and the first existing file's path will be returned.
-/// If none of the `search_list` exists, a fallback filename `config.toml`
-/// will be used.
+/// 2. Otherwise, if the environment variable indexed by `env_for_dir`'s
+///    value is present, that environment variable's value is considered the
+///    parent directory of the returned config path, filenames within
+///    `search_list` will be checked in order and the first existing file's
+///    path will be returned.  If none of the `search_list` exists, a fallback
+///    filename `config.toml` will be used.
 ///
 /// # Example
 ///
@@ -46,9 +51,13 @@ use crate::error::{Error as AppError, Result};
 /// ```
 ///
 /// 3. When neither of `env_for_file`'s and `env_for_dir`'s corresponding
-/// environment variable exists, the parent directory of returned path is
-/// inferred as `$XDG_CONFIG_HOME/dt`, or `$HOME/.config/dt` if
-/// XDG_CONFIG_HOME is not set in the runtime environment.
+///    environment variable exists, a `.dt.toml` file is searched upward from
+///    the current working directory, see [`find_config_upward`].
+///
+/// 4. When none of above yields a config file, the parent directory of
+///    returned path is inferred as `$XDG_CONFIG_HOME/dt`, or
+///    `$HOME/.config/dt` if XDG_CONFIG_HOME is not set in the runtime
+///    environment.
 ///
 /// # Example
 ///
@@ -100,6 +109,13 @@ pub fn default_config_path<P: AsRef<Path>>(
                 dir_path.into()
             }
             _ => {
+                if let Some(file_path) = std::env::current_dir().ok().and_then(find_config_upward) {
+                    log::debug!(
+                        "Using config file '{}' (found upward from current directory)",
+                        file_path.display(),
+                    );
+                    return Ok(file_path);
+                }
                 if let Some(dir_path) = dirs::config_dir() {
                     log::debug!("Using config directory '{}' (inferred)", dir_path.display(),);
                     dir_path.join("dt")
@@ -123,28 +139,617 @@ pub fn default_config_path<P: AsRef<Path>>(
     }
 }
 
+/// Searches for a `.dt.toml` file from `start` upward through its ancestors,
+/// like how git finds its `.git` directory.  The search stops at the
+/// filesystem root, or after checking a directory that contains a `.git`
+/// entry, i.e. the root of a git repository.
+///
+/// # Example
+///
+/// ```
+/// # use dt_core::utils::find_config_upward;
+/// let root = std::env::temp_dir().join("dt-find-config-upward-doctest");
+/// let nested = root.join("repo").join("a").join("b");
+/// std::fs::create_dir_all(&nested)?;
+/// std::fs::write(root.join("repo").join(".dt.toml"), "")?;
+/// assert_eq!(
+///     find_config_upward(&nested),
+///     Some(root.join("repo").join(".dt.toml")),
+/// );
+///
+/// // A `.git` entry marks the boundary of the search
+/// std::fs::create_dir_all(nested.join(".git"))?;
+/// assert_eq!(find_config_upward(&nested), None);
+/// # std::fs::remove_dir_all(&root)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn find_config_upward<P: AsRef<Path>>(start: P) -> Option<PathBuf> {
+    for dir in start.as_ref().ancestors() {
+        let candidate = dir.join(".dt.toml");
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    None
+}
+
+/// Gets the list of config filenames to search for with
+/// [`default_config_path`].  Colon-separated entries from the environment
+/// variable indexed by `env_for_search`'s value (if present) are placed
+/// before `defaults`, so that they are tried first.
+///
+/// # Example
+///
+/// ```
+/// # use dt_core::utils::{config_search_list, default_config_path};
+/// # use std::path::PathBuf;
+/// # use std::str::FromStr;
+/// let dir = std::env::temp_dir().join("dt-config-search-list-doctest");
+/// std::fs::create_dir_all(&dir)?;
+/// std::fs::write(dir.join("cli.toml"), "")?;
+/// std::fs::write(dir.join("experimental.toml"), "")?;
+/// std::env::set_var("DT_CONFIG_DIR", &dir);
+///
+/// let defaults = ["cli.toml", "config.toml", "dt.toml"];
+/// assert_eq!(
+///     default_config_path(
+///         "some_non_existing_var",
+///         "DT_CONFIG_DIR",
+///         &config_search_list("DT_CLI_CONFIG_SEARCH", &defaults),
+///     ),
+///     Ok(dir.join("cli.toml")),
+/// );
+///
+/// std::env::set_var("DT_CLI_CONFIG_SEARCH", "nonexistent.toml:experimental.toml");
+/// assert_eq!(
+///     config_search_list("DT_CLI_CONFIG_SEARCH", &defaults),
+///     vec![
+///         PathBuf::from_str("nonexistent.toml").unwrap(),
+///         PathBuf::from_str("experimental.toml").unwrap(),
+///         PathBuf::from_str("cli.toml").unwrap(),
+///         PathBuf::from_str("config.toml").unwrap(),
+///         PathBuf::from_str("dt.toml").unwrap(),
+///     ],
+/// );
+/// assert_eq!(
+///     default_config_path(
+///         "some_non_existing_var",
+///         "DT_CONFIG_DIR",
+///         &config_search_list("DT_CLI_CONFIG_SEARCH", &defaults),
+///     ),
+///     Ok(dir.join("experimental.toml")),
+/// );
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn config_search_list<P: AsRef<Path>>(env_for_search: &str, defaults: &[P]) -> Vec<PathBuf> {
+    let mut ret: Vec<PathBuf> = match std::env::var(env_for_search) {
+        Ok(search) => {
+            log::debug!(
+                "Searching for config file '{}' first (from environment variable `{}`)",
+                search,
+                env_for_search,
+            );
+            search
+                .split(':')
+                .filter(|name| !name.is_empty())
+                .map(PathBuf::from)
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+    ret.extend(defaults.iter().map(|name| name.as_ref().to_owned()));
+    ret
+}
+
+/// Gets current machine's hostname.  Use [`get_hostname`] instead to honor
+/// [`hostname_override`].
+///
+/// [`get_hostname`]: crate::config::GlobalConfig::get_hostname
+/// [`hostname_override`]: crate::config::GlobalConfig::hostname_override
+pub fn hostname() -> String {
+    gethostname::gethostname()
+        .to_str()
+        .expect("Failed getting hostname")
+        .to_owned()
+}
+
 /// Gets the host-specific suffix, according to given [`hostname_sep`] and
-/// current machine's hostname.
+/// `hostname`.
 ///
 /// [`hostname_sep`]: crate::config::GlobalConfig::hostname_sep
-pub fn host_specific_suffix(hostname_sep: &str) -> String {
-    hostname_sep.to_owned()
-        + gethostname::gethostname()
-            .to_str()
-            .expect("Failed getting hostname")
+pub fn host_specific_suffix(hostname_sep: &str, hostname: &str) -> String {
+    hostname_sep.to_owned() + hostname
 }
 
+/// Loads environment variables from a `.env`-style file, i.e. one
+/// `KEY=VALUE` per line, into the environment of current process.  Variables
+/// that are already set are overridden, so that templates depending on them
+/// render the same regardless of the invoking shell.
+///
+/// # Example
+///
+/// ```
+/// # use dt_core::{error::Error, utils::load_env_file};
+/// let path = std::env::temp_dir().join("dt-load-env-file-example.env");
+/// std::fs::write(&path, "# comment\nDT_EXAMPLE_GREETING=\"hello\"\n")?;
+/// load_env_file(&path)?;
+/// assert_eq!(std::env::var("DT_EXAMPLE_GREETING").as_deref(), Ok("hello"));
+/// # Ok::<(), Error>(())
+/// ```
+pub fn load_env_file<P: AsRef<Path>>(path: P) -> Result<()> {
+    let path = path.as_ref();
+    dotenvy::from_path_override(path).map_err(|e| {
+        AppError::ConfigError(format!(
+            "Could not load environment variables from '{}': {}",
+            path.display(),
+            e,
+        ))
+    })
+}
+
+/// Writes `contents` to `path` atomically, by first writing to a temporary
+/// file in the same directory as `path`, then renaming it to `path`.  Falls
+/// back to writing to `path` directly when writing to or renaming the
+/// temporary file fails (e.g. when the parent directory does not allow
+/// creating new files).  The temporary file is always removed on failure.
+pub fn write_atomically<P: AsRef<Path>>(path: P, contents: &[u8]) -> std::io::Result<()> {
+    let path = path.as_ref();
+    let tmppath = match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => parent.join(format!(
+            ".{}.dt-{}.tmp",
+            name.to_string_lossy(),
+            std::process::id(),
+        )),
+        _ => {
+            std::fs::write(path, contents)?;
+            return Ok(());
+        }
+    };
+
+    if let Err(e) = std::fs::write(&tmppath, contents).and_then(|_| std::fs::rename(&tmppath, path))
+    {
+        log::debug!(
+            "Could not atomically write to '{}' ({}), writing to it directly",
+            path.display(),
+            e,
+        );
+        if tmppath.is_file() {
+            std::fs::remove_file(&tmppath)?;
+        }
+        std::fs::write(path, contents)?;
+    }
+    Ok(())
+}
+
+/// Runs `op`, retrying it up to `retries` more times when it fails with a
+/// transient IO error (i.e. interrupted, would block, or timed out), with
+/// an exponential backoff starting at 10 milliseconds.  Other errors (e.g.
+/// permission denied) are returned immediately.
+///
+/// # Example
+///
+/// ```
+/// # use dt_core::utils::retry_transient;
+/// # use std::io::{Error, ErrorKind};
+/// let mut attempts = 0;
+/// let result = retry_transient(3, || {
+///     attempts += 1;
+///     if attempts < 3 {
+///         Err(Error::from(ErrorKind::Interrupted))
+///     } else {
+///         Ok(attempts)
+///     }
+/// });
+/// assert_eq!(result.unwrap(), 3);
+///
+/// let mut attempts = 0;
+/// let result: std::io::Result<()> = retry_transient(3, || {
+///     attempts += 1;
+///     Err(Error::from(ErrorKind::PermissionDenied))
+/// });
+/// assert_eq!(result.unwrap_err().kind(), ErrorKind::PermissionDenied);
+/// assert_eq!(attempts, 1);
+/// ```
+pub fn retry_transient<T, F>(retries: u32, mut op: F) -> std::io::Result<T>
+where
+    F: FnMut() -> std::io::Result<T>,
+{
+    let mut attempt = 0;
+    loop {
+        match op() {
+            Err(e)
+                if attempt < retries
+                    && matches!(
+                        e.kind(),
+                        std::io::ErrorKind::Interrupted
+                            | std::io::ErrorKind::WouldBlock
+                            | std::io::ErrorKind::TimedOut
+                    ) =>
+            {
+                let backoff = std::time::Duration::from_millis(10 << attempt);
+                log::debug!("Transient IO error ({}), retrying in {:?}", e, backoff);
+                std::thread::sleep(backoff);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Checks whether the file at `path` has binary contents, by inspecting its
+/// first 1024 bytes with the [`content_inspector`] crate.  Empty files are
+/// considered text.
+///
+/// [`content_inspector`]: https://crates.io/crates/content_inspector
+pub fn is_binary<P: AsRef<Path>>(path: P) -> Result<bool> {
+    let mut f = std::fs::File::open(path)?;
+    let len = f.metadata()?.len();
+    if len == 0 {
+        return Ok(false);
+    }
+    f.seek(std::io::SeekFrom::Start(0))?;
+    let mut indicator = vec![0; std::cmp::min(1024, len as usize)];
+    f.read_exact(&mut indicator)?;
+    Ok(content_inspector::inspect(&indicator).is_binary())
+}
+
+/// Gets a short, stable hexadecimal digest of `bytes`, i.e. the 64-bit
+/// FNV-1a hash of `bytes`, truncated to its higher 32 bits.
+///
+/// # Example
+///
+/// ```rust
+/// # use dt_core::utils::short_hash;
+/// assert_eq!(short_hash(b""), "cbf29ce4");
+/// assert_eq!(short_hash(b"dt"), short_hash(b"dt"));
+/// assert_ne!(short_hash(b"dt"), short_hash(b"td"));
+/// ```
+pub fn short_hash(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:08x}", hash >> 32)
+}
+
+/// Creates (if missing) and exclusively locks the file at `path` with an
+/// advisory lock, without blocking.  The lock is held until the returned
+/// file is dropped.  Errors if the lock is held by another process (or
+/// another open handle of the same file).
+///
+/// # Example
+///
+/// ```
+/// # use dt_core::utils::try_lock;
+/// let path = std::env::temp_dir().join("dt-try-lock-example.lock");
+/// let lock = try_lock(&path)?;
+/// assert!(try_lock(&path).is_err());
+/// drop(lock);
+/// assert!(try_lock(&path).is_ok());
+/// # Ok::<(), dt_core::error::Error>(())
+/// ```
+pub fn try_lock<P: AsRef<Path>>(path: P) -> Result<std::fs::File> {
+    let path = path.as_ref();
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)?;
+    if let Err(e) = fs2::FileExt::try_lock_exclusive(&file) {
+        return Err(AppError::SyncingError(format!(
+            "could not lock '{}', another instance of DT might be running ({})",
+            path.display(),
+            e,
+        )));
+    }
+    Ok(file)
+}
+
+/// Checks whether the standard output of current process is attached to a
+/// terminal.
+pub fn stdout_is_tty() -> bool {
+    std::io::stdout().is_terminal()
+}
+
+/// Detects the container runtime current process runs in, one of
+/// `"podman"`, `"docker"` and `"lxc"`.  Returns `None` when not running in a
+/// (recognized) container.
+///
+/// Podman and Docker are detected by the `/run/.containerenv` and
+/// `/.dockerenv` files they create, respectively, other runtimes are
+/// detected from cgroup paths of the init process.
+pub fn container_runtime() -> Option<String> {
+    if Path::new("/run/.containerenv").exists() {
+        return Some("podman".to_owned());
+    }
+    if Path::new("/.dockerenv").exists() {
+        return Some("docker".to_owned());
+    }
+    let cgroup = std::fs::read_to_string("/proc/1/cgroup").ok()?;
+    [("libpod", "podman"), ("docker", "docker"), ("lxc", "lxc")]
+        .iter()
+        .find(|(pattern, _)| cgroup.contains(pattern))
+        .map(|(_, runtime)| runtime.to_string())
+}
+
+/// Gets the desktop environment(s) of current session, lowercased, from
+/// `$XDG_CURRENT_DESKTOP` (which may hold a colon-separated list, e.g.
+/// `ubuntu:GNOME`), or from `$DESKTOP_SESSION` when the former is unset or
+/// empty.  Returns an empty vector when neither is set.
+pub fn current_desktops() -> Vec<String> {
+    ["XDG_CURRENT_DESKTOP", "DESKTOP_SESSION"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.trim().is_empty())
+        .map(|value| {
+            value
+                .split(':')
+                .map(|desktop| desktop.trim().to_lowercase())
+                .filter(|desktop| !desktop.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Gets the locale of current session from `$LC_ALL`, or from `$LANG` when
+/// the former is unset or empty, e.g. `en_US.UTF-8`.  Returns `None` when
+/// neither is set.
+pub fn current_locale() -> Option<String> {
+    ["LC_ALL", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .map(|value| value.trim().to_owned())
+        .find(|value| !value.is_empty())
+}
+
+/// Checks whether a TCP connection to `addr` (`host:port`) can be
+/// established within `timeout`.  Each address `addr` resolves to is tried
+/// in turn.
+pub fn is_reachable(addr: &str, timeout: std::time::Duration) -> bool {
+    let addrs = match std::net::ToSocketAddrs::to_socket_addrs(addr) {
+        Ok(addrs) => addrs,
+        Err(e) => {
+            log::debug!("Could not resolve '{}': {}", addr, e);
+            return false;
+        }
+    };
+    addrs
+        .into_iter()
+        .any(|addr| std::net::TcpStream::connect_timeout(&addr, timeout).is_ok())
+}
+
+/// Gets the release of the running kernel, e.g. `6.1.12-arch1-1`, from
+/// `/proc/sys/kernel/osrelease`, or from `uname` when the former is not
+/// readable.
+pub fn kernel_release() -> Option<String> {
+    std::fs::read_to_string("/proc/sys/kernel/osrelease")
+        .ok()
+        .or_else(|| sys_info::os_release().ok())
+        .map(|release| release.trim().to_owned())
+}
+
+/// Gets a field of the current user's passwd entry, one of `shell`, `home`
+/// and `gecos`.  Returns `None` for other field names, or if the current
+/// user has no passwd entry.
+pub fn passwd_field(field: &str) -> Option<String> {
+    use users::os::unix::UserExt;
+
+    let user = users::get_user_by_uid(users::get_current_uid())?;
+    match field {
+        "shell" => Some(user.shell().to_string_lossy().into_owned()),
+        "home" => Some(user.home_dir().to_string_lossy().into_owned()),
+        // The `users` crate does not expose the GECOS field
+        "gecos" => {
+            let name = user.name().to_string_lossy();
+            std::fs::read_to_string("/etc/passwd")
+                .ok()?
+                .lines()
+                .map(|line| line.split(':').collect::<Vec<_>>())
+                .find(|entry| entry.len() == 7 && entry[0] == name)
+                .map(|entry| entry[4].to_owned())
+        }
+        _ => None,
+    }
+}
+
+/// Time allowed for connecting to a server when fetching, see [`fetch`].
+pub const FETCH_CONNECT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+/// Time allowed for each read from a server when fetching, see [`fetch`].
+pub const FETCH_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Fetches the contents of `url` with a blocking HTTP(S) `GET` request.
+/// Errors if the request fails, the server responds with an error status,
+/// or the server does not connect or respond within
+/// [`FETCH_CONNECT_TIMEOUT`] or [`FETCH_READ_TIMEOUT`].
+#[cfg(feature = "remote")]
+pub fn fetch(url: &url::Url) -> Result<Vec<u8>> {
+    let response = ureq::AgentBuilder::new()
+        .timeout_connect(FETCH_CONNECT_TIMEOUT)
+        .timeout_read(FETCH_READ_TIMEOUT)
+        .build()
+        .get(url.as_str())
+        .call()
+        .map_err(|e| AppError::NetworkError(format!("could not fetch '{}': {}", url, e)))?;
+    let mut content = Vec::new();
+    response
+        .into_reader()
+        .read_to_end(&mut content)
+        .map_err(|e| AppError::NetworkError(format!("could not read '{}': {}", url, e)))?;
+    Ok(content)
+}
+
+/// Always errors, since fetching remote items requires the `remote`
+/// feature.
+#[cfg(not(feature = "remote"))]
+pub fn fetch(url: &url::Url) -> Result<Vec<u8>> {
+    Err(AppError::NetworkError(format!(
+        "could not fetch '{}': dt-core is built without the `remote` feature",
+        url,
+    )))
+}
+
+/// Lists absolute paths to files that changed since git revision `rev` in
+/// the git work tree containing `dir`, as reported by `git diff
+/// --name-only`, together with untracked files under `dir` that are not
+/// ignored.  Returns `None` if `dir` is not inside a git work tree.
+pub fn git_changed_files<P: AsRef<Path>>(dir: P, rev: &str) -> Result<Option<Vec<PathBuf>>> {
+    let git = |args: &[&str]| {
+        std::process::Command::new("git")
+            .arg("-C")
+            .arg(dir.as_ref())
+            .args(args)
+            .stderr(std::process::Stdio::null())
+            .output()
+    };
+    let toplevel = git(&["rev-parse", "--show-toplevel"])?;
+    if !toplevel.status.success() {
+        return Ok(None);
+    }
+    let toplevel = PathBuf::from(std::ffi::OsStr::from_bytes(
+        toplevel.stdout.trim_ascii_end(),
+    ));
+    // Paths are NUL-terminated and never quoted with `-z`
+    let diff = git(&["diff", "--name-only", "-z", rev, "--"])?;
+    let untracked = git(&[
+        "ls-files",
+        "--others",
+        "--exclude-standard",
+        "--full-name",
+        "-z",
+    ])?;
+    if !diff.status.success() || !untracked.status.success() {
+        return Err(AppError::SyncingError(format!(
+            "could not list files changed since '{}' in '{}'",
+            rev,
+            toplevel.display(),
+        )));
+    }
+    Ok(Some(
+        diff.stdout
+            .split(|&b| b == 0)
+            .chain(untracked.stdout.split(|&b| b == 0))
+            .filter(|name| !name.is_empty())
+            .map(|name| toplevel.join(std::ffi::OsStr::from_bytes(name)))
+            .collect(),
+    ))
+}
+
+/// Checks whether the machine is running on battery power, by reading
+/// `/sys/class/power_supply/*/{type,status,online}`.  The machine is
+/// considered on battery when a battery is discharging and no mains power
+/// supply is online.  Machines without a battery are considered on AC.
+pub fn on_battery() -> bool {
+    let supplies = match std::fs::read_dir("/sys/class/power_supply") {
+        Ok(supplies) => supplies,
+        Err(_) => return false,
+    };
+    let read = |path: PathBuf| {
+        std::fs::read_to_string(path)
+            .map(|s| s.trim().to_owned())
+            .unwrap_or_default()
+    };
+    let mut discharging = false;
+    for supply in supplies.flatten().map(|entry| entry.path()) {
+        match read(supply.join("type")).as_str() {
+            "Mains" if read(supply.join("online")) == "1" => return false,
+            "Battery" if read(supply.join("status")) == "Discharging" => discharging = true,
+            _ => {}
+        }
+    }
+    discharging
+}
+
+thread_local! {
+    static WARNING_COUNT: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Counts a warning towards the current thread's warning count.
+pub(crate) fn count_warning() {
+    WARNING_COUNT.with(|count| count.set(count.get() + 1));
+}
+
+/// Gets the number of warnings logged with [`warning!`] so far in the current
+/// thread.
+pub(crate) fn warning_count() -> usize {
+    WARNING_COUNT.with(|count| count.get())
+}
+
+/// Logs a warning like [`log::warn!`], and counts it with
+/// [`count_warning`], so that warnings can be escalated to errors with
+/// [`fail_on_warning`].
+///
+/// [`fail_on_warning`]: crate::config::GlobalConfig::fail_on_warning
+macro_rules! warning {
+    ($($arg:tt)+) => {{
+        $crate::utils::count_warning();
+        log::warn!($($arg)+);
+    }};
+}
+pub(crate) use warning;
+
 #[cfg(test)]
 pub(crate) mod testing {
     use std::{
-        ffi::OsString, fs::Permissions, os::unix::prelude::PermissionsExt, path::PathBuf,
+        cell::{Cell, RefCell},
+        ffi::OsString,
+        fs::Permissions,
+        os::unix::prelude::PermissionsExt,
+        path::{Path, PathBuf},
         str::FromStr,
+        sync::Once,
     };
 
     use color_eyre::Report;
 
     const TESTROOT: &str = "/tmp/dt-testing";
 
+    thread_local! {
+        static CAPTURED_LOGS: RefCell<Vec<(String, String)>> = const { RefCell::new(Vec::new()) };
+        static STDOUT_IS_TTY: Cell<bool> = const { Cell::new(false) };
+        static CONTAINER_RUNTIME: RefCell<Option<String>> = const { RefCell::new(None) };
+        static REACHABLE: Cell<bool> = const { Cell::new(false) };
+        static ON_BATTERY: Cell<bool> = const { Cell::new(false) };
+        static EFFECTIVE_UID: Cell<users::uid_t> = const { Cell::new(418) };
+        static KERNEL_RELEASE: RefCell<Option<String>> = const { RefCell::new(None) };
+        static SYMLINK_UNSUPPORTED: Cell<bool> = const { Cell::new(false) };
+        static PASSWD: RefCell<Vec<(String, String)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    struct CapturingLogger;
+    impl log::Log for CapturingLogger {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+        fn log(&self, record: &log::Record) {
+            CAPTURED_LOGS.with(|logs| {
+                logs.borrow_mut()
+                    .push((record.target().to_owned(), record.args().to_string()))
+            });
+        }
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CapturingLogger = CapturingLogger;
+    static INIT_LOGGER: Once = Once::new();
+
+    /// Starts capturing `(target, message)` pairs of log records emitted
+    /// from the current thread, discarding previously captured ones.
+    pub fn capture_logs() {
+        INIT_LOGGER.call_once(|| {
+            log::set_logger(&LOGGER).unwrap();
+            log::set_max_level(log::LevelFilter::Trace);
+        });
+        CAPTURED_LOGS.with(|logs| logs.borrow_mut().clear());
+    }
+
+    /// Gets the log records captured from the current thread since the last
+    /// call to [`capture_logs`].
+    pub fn captured_logs() -> Vec<(String, String)> {
+        CAPTURED_LOGS.with(|logs| logs.borrow().to_owned())
+    }
+
     pub fn get_testroot(top_level: &str) -> PathBuf {
         PathBuf::from_str(TESTROOT).unwrap().join(top_level)
     }
@@ -167,14 +772,114 @@ pub(crate) mod testing {
         Ok(abspath)
     }
 
+    /// Pretends the standard output to be (or not to be) attached to a
+    /// terminal for the current thread.
+    pub fn set_stdout_tty(is_tty: bool) {
+        STDOUT_IS_TTY.with(|flag| flag.set(is_tty));
+    }
+
+    pub fn stdout_is_tty() -> bool {
+        STDOUT_IS_TTY.with(|flag| flag.get())
+    }
+
+    /// Pretends to run in the given container runtime (or on bare metal,
+    /// with `None`) for the current thread.
+    pub fn set_container_runtime(runtime: Option<&str>) {
+        CONTAINER_RUNTIME.with(|r| *r.borrow_mut() = runtime.map(str::to_owned));
+    }
+
+    pub fn container_runtime() -> Option<String> {
+        CONTAINER_RUNTIME.with(|r| r.borrow().to_owned())
+    }
+
+    /// Pretends every address to be (or not to be) reachable for the
+    /// current thread.
+    pub fn set_reachable(reachable: bool) {
+        REACHABLE.with(|flag| flag.set(reachable));
+    }
+
+    pub fn is_reachable(_addr: &str, _timeout: std::time::Duration) -> bool {
+        REACHABLE.with(|flag| flag.get())
+    }
+
+    /// Pretends the machine to be on battery (or on AC) for the current
+    /// thread.
+    pub fn set_on_battery(on_battery: bool) {
+        ON_BATTERY.with(|flag| flag.set(on_battery));
+    }
+
+    pub fn on_battery() -> bool {
+        ON_BATTERY.with(|flag| flag.get())
+    }
+
+    /// Pretends to run on a kernel of the given release for the current
+    /// thread.
+    pub fn set_kernel_release(release: Option<&str>) {
+        KERNEL_RELEASE.with(|r| *r.borrow_mut() = release.map(str::to_owned));
+    }
+
+    pub fn kernel_release() -> Option<String> {
+        KERNEL_RELEASE.with(|r| r.borrow().to_owned())
+    }
+
+    /// Pretends the filesystem to not support symlinks for the current
+    /// thread.
+    pub fn set_symlink_unsupported(unsupported: bool) {
+        SYMLINK_UNSUPPORTED.with(|flag| flag.set(unsupported));
+    }
+
+    pub fn symlink<P: AsRef<Path>, Q: AsRef<Path>>(original: P, link: Q) -> std::io::Result<()> {
+        if SYMLINK_UNSUPPORTED.with(|flag| flag.get()) {
+            Err(std::io::ErrorKind::Unsupported.into())
+        } else {
+            std::os::unix::fs::symlink(original, link)
+        }
+    }
+
+    /// Pretends the current user's passwd entry to have given value in given
+    /// field for the current thread.
+    pub fn set_passwd_field(field: &str, value: &str) {
+        PASSWD.with(|passwd| {
+            let mut passwd = passwd.borrow_mut();
+            passwd.retain(|(f, _)| f != field);
+            passwd.push((field.to_owned(), value.to_owned()));
+        });
+    }
+
+    pub fn passwd_field(field: &str) -> Option<String> {
+        PASSWD.with(|passwd| {
+            passwd
+                .borrow()
+                .iter()
+                .find(|(f, _)| f == field)
+                .map(|(_, value)| value.to_owned())
+        })
+    }
+
     pub fn gethostname() -> OsString {
         "r2d2".into()
     }
 
+    /// Like [`super::hostname`], but uses [`gethostname`].
+    pub fn hostname() -> String {
+        gethostname().to_string_lossy().into_owned()
+    }
+
     pub fn get_current_uid() -> users::uid_t {
         418
     }
 
+    /// Pretends current process to run with given effective uid for the
+    /// current thread, which defaults to the real uid (see
+    /// [`get_current_uid`]).
+    pub fn set_effective_uid(uid: users::uid_t) {
+        EFFECTIVE_UID.with(|euid| euid.set(uid));
+    }
+
+    pub fn get_effective_uid() -> users::uid_t {
+        EFFECTIVE_UID.with(|euid| euid.get())
+    }
+
     pub fn get_current_username() -> Option<OsString> {
         Some("luke".into())
     }